@@ -9,8 +9,11 @@ use pairing::{
     CurveAffine,
     PrimeFieldDecodingError,
     GroupDecodingError,
-    EncodedPoint
+    EncodedPoint,
+    CanonicalEncoding,
+    IoError
 };
+use pairing::utils::{Read, Write};
 
 use rstd::{cmp::Ordering, num::Wrapping};
 #[cfg(feature = "std")]
@@ -25,6 +28,26 @@ const MODULUS_R: Wrapping<u32> = Wrapping(64513);
 #[derive(Copy, Clone, Debug, PartialEq, Eq, Default)]
 pub struct Fr(pub Wrapping<u32>);
 
+impl CanonicalEncoding for Fr {
+    fn write_canonical<W: Write>(&self, mut writer: W) -> Result<(), IoError> {
+        writer.write_u32((self.0).0)
+    }
+
+    fn read_canonical<R: Read>(mut reader: R) -> Result<Self, IoError> {
+        let fr = Fr(Wrapping(reader.read_u32()?));
+
+        if fr.is_well_formed() {
+            Ok(fr)
+        } else {
+            Err(IoError::NotInField)
+        }
+    }
+
+    fn is_well_formed(&self) -> bool {
+        self.0 < MODULUS_R
+    }
+}
+
 impl Encode for Fr {
     fn using_encoded<R, F: FnOnce(&[u8]) -> R>(&self, f: F) -> R {
 		// self.0.using_encoded(f)
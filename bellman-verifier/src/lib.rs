@@ -9,18 +9,22 @@ extern crate alloc;
 
 use pairing::{
     Engine,
+    Field,
     CurveAffine,
     EncodedPoint,
+    CanonicalEncoding,
     IoError,
     GroupDecodingError,
 };
 
 use rstd::prelude::*;
+use rstd::mem::size_of;
 #[cfg(feature = "std")]
 use std::{io, fmt::{self, Debug}, error::Error};
 
 mod verifier;
 mod dummy_engine;
+pub mod multipack;
 
 pub use self::verifier::*;
 pub use pairing::utils::*;
@@ -149,70 +153,50 @@ impl<E: Engine> PartialEq for VerifyingKey<E> {
 }
 
 impl<E: Engine> VerifyingKey<E> {
-    pub fn write(
+    pub fn write<W: Write>(
         &self,
-        writer: &mut Vec<u8>
+        mut writer: W
     ) -> Result<(), IoError>
-    {        
-        writer.write_all(self.alpha_g1.into_uncompressed().as_ref())?;        
+    {
+        writer.write_all(self.alpha_g1.into_uncompressed().as_ref())?;
         writer.write_all(self.beta_g1.into_uncompressed().as_ref())?;
         writer.write_all(self.beta_g2.into_uncompressed().as_ref())?;
         writer.write_all(self.gamma_g2.into_uncompressed().as_ref())?;
         writer.write_all(self.delta_g1.into_uncompressed().as_ref())?;
         writer.write_all(self.delta_g2.into_uncompressed().as_ref())?;
-        writer.write_u32(self.ic.len() as u32)?;
-        for ic in &self.ic {
-            writer.write_all(ic.into_uncompressed().as_ref())?;
-        }
+        write_points(&mut writer, &self.ic)?;
 
         Ok(())
     }
 
-    pub fn read(
-        mut reader: &[u8]
+    /// Reads a verifying key, consuming exactly the bytes written by
+    /// `write` so that further data can be read from the same reader.
+    pub fn read<R: Read>(
+        mut reader: R
     ) -> Result<Self, IoError>
-    {        
-        let mut g1_repr = <E::G1Affine as CurveAffine>::Uncompressed::empty();        
+    {
+        let mut g1_repr = <E::G1Affine as CurveAffine>::Uncompressed::empty();
         let mut g2_repr = <E::G2Affine as CurveAffine>::Uncompressed::empty();
 
-        reader.read_exact(g1_repr.as_mut())?;               
-        
-        let alpha_g1 = g1_repr.into_affine().map_err(|e| Err(e))?;                    
+        reader.read_exact(g1_repr.as_mut())?;
+        let alpha_g1 = g1_repr.into_affine()?;
 
         reader.read_exact(g1_repr.as_mut())?;
-        let beta_g1 = g1_repr.into_affine().map_err(|e| Err(e))?;        
+        let beta_g1 = g1_repr.into_affine()?;
 
         reader.read_exact(g2_repr.as_mut())?;
-        let beta_g2 = g2_repr.into_affine().map_err(|e| Err(e))?;
+        let beta_g2 = g2_repr.into_affine()?;
 
         reader.read_exact(g2_repr.as_mut())?;
-        let gamma_g2 = g2_repr.into_affine().map_err(|e| Err(e))?;
+        let gamma_g2 = g2_repr.into_affine()?;
 
         reader.read_exact(g1_repr.as_mut())?;
-        let delta_g1 = g1_repr.into_affine().map_err(|e| Err(e))?;
+        let delta_g1 = g1_repr.into_affine()?;
 
         reader.read_exact(g2_repr.as_mut())?;
-        let delta_g2 = g2_repr.into_affine().map_err(|e| Err(e))?;
-            
-        let ic_len = reader.read_u32().unwrap() as usize;        
+        let delta_g2 = g2_repr.into_affine()?;
 
-        let mut ic = vec![];
-        
-        for _ in 0..ic_len {
-            reader.read_exact(g1_repr.as_mut())?;            
-            
-            let g1 = g1_repr
-                     .into_affine()
-                    //  .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))
-                     .and_then(|e| if e.is_zero() {                         
-                        //  Err(io::Error::new(io::ErrorKind::InvalidData, "point at infinity"))
-                         Err(GroupDecodingError::NotOnCurve)
-                     } else {
-                         Ok(e)
-                     })?;
-
-            ic.push(g1);
-        }                
+        let ic = read_points::<E::G1Affine, _>(&mut reader)?;
 
         Ok(VerifyingKey {
             alpha_g1: alpha_g1,
@@ -226,6 +210,47 @@ impl<E: Engine> VerifyingKey<E> {
     }
 }
 
+/// Reads a length-prefixed vector of uncompressed points in a single pass,
+/// checking that they are in the prime order subgroup. Points at infinity
+/// are rejected. The length is untrusted, so at most 1 MiB is reserved up
+/// front.
+fn read_points<G: CurveAffine, R: Read>(
+    mut reader: R
+) -> Result<Vec<G>, IoError>
+{
+    let len = reader.read_u32()? as usize;
+
+    let mut points = Vec::with_capacity(len.min((1 << 20) / size_of::<G>()));
+    let mut repr = G::Uncompressed::empty();
+
+    for _ in 0..len {
+        reader.read_exact(repr.as_mut())?;
+
+        let point = repr.into_affine()?;
+
+        if point.is_zero() {
+            return Err(IoError::Infinity);
+        }
+
+        points.push(point);
+    }
+
+    Ok(points)
+}
+
+fn write_points<G: CurveAffine, W: Write>(
+    mut writer: W,
+    points: &[G]
+) -> Result<(), IoError>
+{
+    writer.write_u32(points.len() as u32)?;
+    for g in points {
+        writer.write_all(g.into_uncompressed().as_ref())?;
+    }
+
+    Ok(())
+}
+
 // #[cfg_attr(feature = "std", derive(Debug))]
 #[derive(Clone, Encode, Decode, Default, PartialEq, Eq)]
 pub struct PreparedVerifyingKey<E: Engine> {
@@ -239,6 +264,102 @@ pub struct PreparedVerifyingKey<E: Engine> {
     ic: Vec<E::G1Affine>
 }
 
+impl<E: Engine> PreparedVerifyingKey<E> {
+    /// Builds a prepared verifying key from parts computed elsewhere, e.g.
+    /// kept in storage, so that the G2 line coefficients need not be
+    /// recomputed. Every part is checked to be well-formed: field elements
+    /// must be in the field, the prepared points must have the full set of
+    /// line coefficients and not be the point at infinity, and `ic` must be
+    /// non-empty and contain only points in the prime order subgroup other
+    /// than the point at infinity. The parts are not checked to come from
+    /// the same verifying key.
+    pub fn from_parts(
+        alpha_g1_beta_g2: E::Fqk,
+        neg_gamma_g2: <E::G2Affine as CurveAffine>::Prepared,
+        neg_delta_g2: <E::G2Affine as CurveAffine>::Prepared,
+        ic: Vec<E::G1Affine>
+    ) -> Result<Self, SynthesisError>
+    {
+        let pvk = PreparedVerifyingKey {
+            alpha_g1_beta_g2: alpha_g1_beta_g2,
+            neg_gamma_g2: neg_gamma_g2,
+            neg_delta_g2: neg_delta_g2,
+            ic: ic
+        };
+
+        let ic_is_valid = pvk.ic.iter().all(|p: &E::G1Affine| {
+            !p.is_zero() && p.into_uncompressed().into_affine().is_ok()
+        });
+
+        if pvk.is_well_formed() && ic_is_valid {
+            Ok(pvk)
+        } else {
+            Err(SynthesisError::MalformedVerifyingKey)
+        }
+    }
+
+    /// Checks everything `from_parts` does except the IC points.
+    fn is_well_formed(&self) -> bool {
+        let infinity = E::G2Affine::zero().prepare();
+
+        self.alpha_g1_beta_g2.is_well_formed() &&
+        !self.alpha_g1_beta_g2.is_zero() &&
+        self.alpha_g1_beta_g2 != E::Fqk::one() &&
+        self.neg_gamma_g2.is_well_formed() &&
+        self.neg_gamma_g2 != infinity &&
+        self.neg_delta_g2.is_well_formed() &&
+        self.neg_delta_g2 != infinity &&
+        !self.ic.is_empty()
+    }
+
+    /// Writes the prepared verifying key in the same stable format as
+    /// `bellman`, unlike the derived `Encode` which follows the in-memory
+    /// representation:
+    ///
+    /// * `alpha_g1_beta_g2`, then `neg_gamma_g2` and `neg_delta_g2`, each in
+    ///   its `CanonicalEncoding`,
+    /// * the number of IC elements as a big-endian `u32`, followed by each
+    ///   element in its uncompressed encoding.
+    pub fn write<W: Write>(
+        &self,
+        mut writer: W
+    ) -> Result<(), IoError>
+    {
+        self.alpha_g1_beta_g2.write_canonical(&mut writer)?;
+        self.neg_gamma_g2.write_canonical(&mut writer)?;
+        self.neg_delta_g2.write_canonical(&mut writer)?;
+        write_points(&mut writer, &self.ic)?;
+
+        Ok(())
+    }
+
+    /// Reads a prepared verifying key written by `write`, with the same
+    /// checks as `from_parts`.
+    pub fn read<R: Read>(
+        mut reader: R
+    ) -> Result<Self, IoError>
+    {
+        let alpha_g1_beta_g2 = E::Fqk::read_canonical(&mut reader)?;
+        let neg_gamma_g2 = <E::G2Affine as CurveAffine>::Prepared::read_canonical(&mut reader)?;
+        let neg_delta_g2 = <E::G2Affine as CurveAffine>::Prepared::read_canonical(&mut reader)?;
+
+        let ic = read_points::<E::G1Affine, _>(&mut reader)?;
+
+        let pvk = PreparedVerifyingKey {
+            alpha_g1_beta_g2: alpha_g1_beta_g2,
+            neg_gamma_g2: neg_gamma_g2,
+            neg_delta_g2: neg_delta_g2,
+            ic: ic
+        };
+
+        if pvk.is_well_formed() {
+            Ok(pvk)
+        } else {
+            Err(IoError::Error)
+        }
+    }
+}
+
 /// This is an error that could occur during circuit synthesis contexts,
 /// such as CRS generation, proving or verification.
 #[derive(Debug)]
@@ -0,0 +1,287 @@
+//! Encoding of public data into the public inputs of a circuit.
+//!
+//! Circuits built with `sapling_crypto` expose byte-oriented data with
+//! `multipack::pack_into_inputs` and curve points with
+//! `EdwardsPoint::inputize`. The functions here compute the same field
+//! elements outside of a circuit without needing `std`, so that a verifier
+//! can rebuild the `&[E::Fr]` passed to `verify_proof` from the raw data.
+
+use pairing::{
+    Engine,
+    Field,
+    PrimeField
+};
+use rstd::prelude::*;
+
+/// Converts bytes into bits, most significant bit of each byte first.
+pub fn bytes_to_bits(bytes: &[u8]) -> Vec<bool>
+{
+    bytes.iter()
+         .flat_map(|&v| (0..8).rev().map(move |i| (v >> i) & 1 == 1))
+         .collect()
+}
+
+/// Converts bytes into bits, least significant bit of each byte first.
+pub fn bytes_to_bits_le(bytes: &[u8]) -> Vec<bool>
+{
+    bytes.iter()
+         .flat_map(|&v| (0..8).map(move |i| (v >> i) & 1 == 1))
+         .collect()
+}
+
+/// Packs bits into field elements, `E::Fr::CAPACITY` bits per element with
+/// the first bit being the least significant. This matches the inputs
+/// allocated by `pack_into_inputs` for the same bits.
+pub fn compute_multipacking<E: Engine>(
+    bits: &[bool]
+) -> Vec<E::Fr>
+{
+    let mut result = vec![];
+
+    for bits in bits.chunks(E::Fr::CAPACITY as usize)
+    {
+        let mut cur = E::Fr::zero();
+        let mut coeff = E::Fr::one();
+
+        for bit in bits {
+            if *bit {
+                cur.add_assign(&coeff);
+            }
+
+            coeff.double();
+        }
+
+        result.push(cur);
+    }
+
+    result
+}
+
+/// Builds the public inputs of a proof in the order the circuit allocates
+/// them.
+#[derive(Clone)]
+pub struct PublicInputs<E: Engine> {
+    inputs: Vec<E::Fr>
+}
+
+impl<E: Engine> Default for PublicInputs<E> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<E: Engine> PublicInputs<E> {
+    pub fn new() -> Self {
+        PublicInputs {
+            inputs: vec![]
+        }
+    }
+
+    /// Appends a field element exposed directly with `alloc_input` or
+    /// `AllocatedNum::inputize`.
+    pub fn push_fr(&mut self, value: E::Fr) -> &mut Self {
+        self.inputs.push(value);
+        self
+    }
+
+    /// Appends the inputs of a single `pack_into_inputs` call over `bits`.
+    pub fn push_bits(&mut self, bits: &[bool]) -> &mut Self {
+        self.inputs.extend(compute_multipacking::<E>(bits));
+        self
+    }
+
+    /// Appends the inputs of a single `pack_into_inputs` call over
+    /// `bytes_to_bits_le(bytes)`.
+    pub fn push_bytes(&mut self, bytes: &[u8]) -> &mut Self {
+        self.push_bits(&bytes_to_bits_le(bytes))
+    }
+
+    /// Appends a 64-bit value. Packing the 64 little-endian bits of the
+    /// value yields the value itself, so this is also the single input
+    /// produced by `pack_into_inputs` over those bits.
+    pub fn push_u64(&mut self, value: u64) -> &mut Self {
+        let value = E::Fr::from_repr(value.into())
+            .expect("a u64 is always in the field");
+
+        self.push_fr(value)
+    }
+
+    /// Appends the coordinates of a Jubjub point, as returned by
+    /// `edwards::Point::into_xy`. This matches `EdwardsPoint::inputize`.
+    pub fn push_point(&mut self, xy: (E::Fr, E::Fr)) -> &mut Self {
+        self.push_fr(xy.0);
+        self.push_fr(xy.1)
+    }
+
+    pub fn as_slice(&self) -> &[E::Fr] {
+        &self.inputs
+    }
+
+    pub fn into_vec(self) -> Vec<E::Fr> {
+        self.inputs
+    }
+}
+
+#[cfg(test)]
+mod test_with_bls12_381 {
+    use super::*;
+    use crate::{Proof, VerifyingKey, prepare_verifying_key, verify_proof};
+    use pairing::bls12_381::{Bls12, Fr, FrRepr};
+    use rand::{Rng, thread_rng};
+    use bellman::{Circuit, ConstraintSystem, SynthesisError};
+    use bellman::groth16::{generate_random_parameters, create_random_proof};
+    use sapling_crypto::circuit::boolean::{AllocatedBit, Boolean};
+    use sapling_crypto::circuit::ecc::EdwardsPoint;
+    use sapling_crypto::circuit::multipack;
+    use bellman::r1cs::{R1csConstraintSystem, read_witness};
+    use sapling_crypto::jubjub::{JubjubBls12, Unknown, edwards};
+
+    // Exposes its data with the gadgets of `sapling_crypto`, so that the
+    // inputs built here are checked against the real circuit encodings.
+    struct PackingCircuit<'a> {
+        bytes: Option<Vec<u8>>,
+        value: Option<u64>,
+        point: Option<edwards::Point<Bls12, Unknown>>,
+        params: &'a JubjubBls12
+    }
+
+    fn alloc_bits<CS: ConstraintSystem<Bls12>>(
+        mut cs: CS,
+        bits: Vec<Option<bool>>
+    ) -> Result<Vec<Boolean>, SynthesisError>
+    {
+        bits.into_iter().enumerate().map(|(i, bit)| {
+            AllocatedBit::alloc(cs.namespace(|| format!("bit {}", i)), bit).map(Boolean::from)
+        }).collect()
+    }
+
+    impl<'a> Circuit<Bls12> for PackingCircuit<'a> {
+        fn synthesize<CS: ConstraintSystem<Bls12>>(
+            self,
+            cs: &mut CS
+        ) -> Result<(), SynthesisError>
+        {
+            let bits = match self.bytes {
+                Some(bytes) => bytes_to_bits_le(&bytes).into_iter().map(Some).collect(),
+                None => vec![None; 40 * 8]
+            };
+            let bits = alloc_bits(cs.namespace(|| "bytes"), bits)?;
+            multipack::pack_into_inputs(cs.namespace(|| "pack bytes"), &bits)?;
+
+            let value = self.value;
+            let bits = alloc_bits(
+                cs.namespace(|| "value"),
+                (0..64).map(|i| value.map(|v| (v >> i) & 1 == 1)).collect()
+            )?;
+            multipack::pack_into_inputs(cs.namespace(|| "pack value"), &bits)?;
+
+            EdwardsPoint::witness(cs.namespace(|| "point"), self.point, self.params)?
+                .inputize(cs.namespace(|| "inputize point"))?;
+
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn test_multipacking() {
+        let rng = &mut thread_rng();
+
+        assert!(compute_multipacking::<Bls12>(&[]).is_empty());
+        assert_eq!(
+            compute_multipacking::<Bls12>(&bytes_to_bits_le(&[0x01, 0x02])),
+            vec![Fr::from_repr(FrRepr::from(0x0201)).unwrap()]
+        );
+        assert_eq!(
+            compute_multipacking::<Bls12>(&bytes_to_bits(&[0x01, 0x02])),
+            vec![Fr::from_repr(FrRepr::from(0x4080)).unwrap()]
+        );
+
+        // 32 bytes do not fit into a single element.
+        let packed = compute_multipacking::<Bls12>(&bytes_to_bits_le(&[0xff; 32]));
+        assert_eq!(packed.len(), 2);
+        assert_eq!(packed[1], Fr::from_repr(FrRepr::from(0b11)).unwrap());
+
+        for _ in 0..10 {
+            let bytes = (0..rng.gen_range(0, 100)).map(|_| rng.gen()).collect::<Vec<u8>>();
+            assert_eq!(bytes_to_bits(&bytes), multipack::bytes_to_bits(&bytes));
+            assert_eq!(bytes_to_bits_le(&bytes), multipack::bytes_to_bits_le(&bytes));
+            assert_eq!(
+                compute_multipacking::<Bls12>(&bytes_to_bits_le(&bytes)),
+                multipack::compute_multipacking::<Bls12>(&bytes_to_bits_le(&bytes))
+            );
+        }
+
+        let value = rng.gen::<u64>();
+        let mut inputs = PublicInputs::<Bls12>::new();
+        inputs.push_u64(value);
+        assert_eq!(
+            inputs.as_slice(),
+            &compute_multipacking::<Bls12>(&bytes_to_bits_le(&value.to_le_bytes()))[..]
+        );
+    }
+
+    #[test]
+    fn test_public_inputs() {
+        let rng = &mut thread_rng();
+        let jubjub_params = JubjubBls12::new();
+
+        let bytes = (0..40).map(|_| rng.gen()).collect::<Vec<u8>>();
+        let value = rng.gen::<u64>();
+        let point = edwards::Point::<Bls12, Unknown>::rand(rng, &jubjub_params);
+
+        let mut inputs = PublicInputs::<Bls12>::new();
+        inputs
+            .push_bytes(&bytes)
+            .push_u64(value)
+            .push_point(point.into_xy());
+        assert_eq!(inputs.as_slice().len(), 2 + 1 + 2);
+
+        let mut wrong = PublicInputs::<Bls12>::new();
+        wrong
+            .push_bytes(&bytes)
+            .push_u64(value.wrapping_add(1))
+            .push_point(point.into_xy());
+
+        // The inputs must be exactly those allocated by the gadgets.
+        let mut cs = R1csConstraintSystem::<Bls12>::with_witness();
+        PackingCircuit {
+            bytes: Some(bytes.clone()),
+            value: Some(value),
+            point: Some(point.clone()),
+            params: &jubjub_params
+        }.synthesize(&mut cs).unwrap();
+
+        let mut v = vec![];
+        cs.write_witness(&mut v).unwrap();
+        let witness = read_witness::<Bls12, _>(&v[..]).unwrap();
+        assert_eq!(&witness[1..cs.num_inputs()], inputs.as_slice());
+
+        // And a verifier can check proofs with them.
+        let params = generate_random_parameters::<Bls12, _, _>(
+            PackingCircuit { bytes: None, value: None, point: None, params: &jubjub_params },
+            rng
+        ).unwrap();
+
+        let mut v = vec![];
+        params.vk.write(&mut v).unwrap();
+        let pvk = prepare_verifying_key(&VerifyingKey::<Bls12>::read(&v[..]).unwrap());
+
+        let proof = create_random_proof(
+            PackingCircuit {
+                bytes: Some(bytes),
+                value: Some(value),
+                point: Some(point.clone()),
+                params: &jubjub_params
+            },
+            &params,
+            rng
+        ).unwrap();
+
+        let mut v = vec![];
+        proof.write(&mut v).unwrap();
+        let proof = Proof::<Bls12>::read(&v[..]).unwrap();
+
+        assert!(verify_proof(&pvk, &proof, inputs.as_slice()).unwrap());
+        assert!(!verify_proof(&pvk, &proof, wrong.as_slice()).unwrap());
+    }
+}
@@ -2,8 +2,11 @@ use pairing::{
     Engine,
     CurveProjective,
     CurveAffine,
-    PrimeField
+    PrimeField,
+    Field
 };
+use rand::Rng;
+use rstd::prelude::*;
 
 use super::{
     Proof,
@@ -63,11 +66,128 @@ pub fn verify_proof<'a, E: Engine>(
     ).unwrap() == pvk.alpha_g1_beta_g2)
 }
 
+/// Verifies a batch of proofs against the same verifying key.
+///
+/// Every proof is scaled by a random coefficient drawn from `rng` and the
+/// whole batch is checked with a single multi-Miller-loop and one final
+/// exponentiation. Returns `Ok(None)` if all proofs are valid. If the batch
+/// is rejected, the proofs are checked one by one and `Ok(Some(i))` is
+/// returned with the index of the first invalid proof.
+pub fn verify_proofs_batch<'a, E: Engine, R: Rng>(
+    pvk: &'a PreparedVerifyingKey<E>,
+    rng: &mut R,
+    batch: &[(&Proof<E>, &[E::Fr])]
+) -> Result<Option<usize>, SynthesisError>
+{
+    for &(_, public_inputs) in batch {
+        if (public_inputs.len() + 1) != pvk.ic.len() {
+            return Err(SynthesisError::MalformedVerifyingKey);
+        }
+    }
+
+    if batch.is_empty() {
+        return Ok(None);
+    }
+
+    // Each proof satisfies:
+    // A * B + inputs * (-gamma) + C * (-delta) = alpha * beta
+    // Scaling the i-th equation by a random r_i and summing gives:
+    // sum(r_i * A_i * B_i) + sum(r_i * inputs_i) * (-gamma)
+    //     + sum(r_i * C_i) * (-delta) = sum(r_i) * alpha * beta
+    // so the gamma and delta terms collapse into one pair each and the
+    // whole batch needs a single final exponentiation.
+
+    let mut r_sum = E::Fr::zero();
+    let mut input_coeffs = vec![E::Fr::zero(); pvk.ic.len() - 1];
+    let mut acc_c = E::G1::zero();
+    let mut a_scaled = Vec::with_capacity(batch.len());
+
+    for &(proof, public_inputs) in batch {
+        let r: E::Fr = rng.gen();
+        r_sum.add_assign(&r);
+
+        for (coeff, input) in input_coeffs.iter_mut().zip(public_inputs.iter()) {
+            let mut tmp = *input;
+            tmp.mul_assign(&r);
+            coeff.add_assign(&tmp);
+        }
+
+        acc_c.add_assign(&proof.c.mul(r.into_repr()));
+        a_scaled.push(proof.a.mul(r.into_repr()));
+    }
+
+    let mut acc_inputs = pvk.ic[0].mul(r_sum.into_repr());
+
+    for (coeff, b) in input_coeffs.iter().zip(pvk.ic.iter().skip(1)) {
+        acc_inputs.add_assign(&b.mul(coeff.into_repr()));
+    }
+
+    E::G1::batch_normalization(&mut a_scaled);
+
+    let a_prepared = a_scaled.iter().map(|a| a.into_affine().prepare()).collect::<Vec<_>>();
+    let b_prepared = batch.iter().map(|&(proof, _)| proof.b.prepare()).collect::<Vec<_>>();
+    let acc_inputs = acc_inputs.into_affine().prepare();
+    let acc_c = acc_c.into_affine().prepare();
+
+    let mut terms = a_prepared.iter().zip(b_prepared.iter()).collect::<Vec<_>>();
+    terms.push((&acc_inputs, &pvk.neg_gamma_g2));
+    terms.push((&acc_c, &pvk.neg_delta_g2));
+
+    let valid = E::final_exponentiation(&E::miller_loop(&terms)).unwrap()
+        == pvk.alpha_g1_beta_g2.pow(r_sum.into_repr());
+
+    if valid {
+        return Ok(None);
+    }
+
+    // The batch was rejected; find the culprit.
+    for (i, &(proof, public_inputs)) in batch.iter().enumerate() {
+        if !verify_proof(pvk, proof, public_inputs)? {
+            return Ok(Some(i));
+        }
+    }
+
+    Ok(None)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;    
     use super::super::dummy_engine::{Fr, DummyEngine};
     use rstd::num::Wrapping;
+    use bellman::{Circuit, ConstraintSystem};
+
+    pub(super) struct MySillyCircuit<E: Engine> {
+        pub a: Option<E::Fr>,
+        pub b: Option<E::Fr>
+    }
+
+    impl<E: Engine> Circuit<E> for MySillyCircuit<E> {
+        fn synthesize<CS: ConstraintSystem<E>>(
+            self,
+            cs: &mut CS
+        ) -> Result<(), bellman::SynthesisError>
+        {
+            let a = cs.alloc(|| "a", || self.a.ok_or(bellman::SynthesisError::AssignmentMissing))?;
+            let b = cs.alloc(|| "b", || self.b.ok_or(bellman::SynthesisError::AssignmentMissing))?;
+            let c = cs.alloc_input(|| "c", || {
+                let mut a = self.a.ok_or(bellman::SynthesisError::AssignmentMissing)?;
+                let b = self.b.ok_or(bellman::SynthesisError::AssignmentMissing)?;
+
+                a.mul_assign(&b);
+                Ok(a)
+            })?;
+
+            cs.enforce(
+                || "a*b=c",
+                |lc| lc + a,
+                |lc| lc + b,
+                |lc| lc + c
+            );
+
+            Ok(())
+        }
+    }
 
     #[test]
     fn test_verify() {
@@ -89,3 +209,187 @@ mod tests {
         assert!(verify_proof(&pvk, &proof, &pub_inp).unwrap());            
     }
 }
+
+#[cfg(test)]
+mod test_with_bls12_381 {
+    use super::*;
+    use crate::VerifyingKey;
+    use pairing::bls12_381::{Bls12, Fr};
+    use rand::{Rand, thread_rng};
+    use bellman::groth16::{generate_random_parameters, create_random_proof};
+    use super::tests::MySillyCircuit;
+
+    #[test]
+    fn test_verify_batch() {
+        let rng = &mut thread_rng();
+
+        let params = generate_random_parameters::<Bls12, _, _>(
+            MySillyCircuit { a: None, b: None },
+            rng
+        ).unwrap();
+
+        let mut v = vec![];
+        params.vk.write(&mut v).unwrap();
+        let pvk = prepare_verifying_key(&VerifyingKey::<Bls12>::read(&v[..]).unwrap());
+
+        let mut proofs = vec![];
+        let mut inputs = vec![];
+
+        for _ in 0..4 {
+            let a = Fr::rand(rng);
+            let b = Fr::rand(rng);
+            let mut c = a;
+            c.mul_assign(&b);
+
+            let proof = create_random_proof(
+                MySillyCircuit {
+                    a: Some(a),
+                    b: Some(b)
+                },
+                &params,
+                rng
+            ).unwrap();
+
+            let mut v = vec![];
+            proof.write(&mut v).unwrap();
+
+            proofs.push(Proof::<Bls12>::read(&v[..]).unwrap());
+            inputs.push(vec![c]);
+        }
+
+        {
+            let batch = proofs.iter().zip(inputs.iter()).map(|(p, i)| (p, &i[..])).collect::<Vec<_>>();
+            assert_eq!(verify_proofs_batch(&pvk, rng, &batch).unwrap(), None);
+            assert_eq!(verify_proofs_batch(&pvk, rng, &batch[..1]).unwrap(), None);
+            assert_eq!(verify_proofs_batch::<Bls12, _>(&pvk, rng, &[]).unwrap(), None);
+        }
+
+        // Swap the inputs of two proofs.
+        inputs.swap(1, 3);
+        {
+            let batch = proofs.iter().zip(inputs.iter()).map(|(p, i)| (p, &i[..])).collect::<Vec<_>>();
+            assert_eq!(verify_proofs_batch(&pvk, rng, &batch).unwrap(), Some(1));
+            assert_eq!(verify_proofs_batch(&pvk, rng, &batch[2..]).unwrap(), Some(1));
+        }
+
+        // A wrong number of public inputs is rejected outright.
+        inputs[0].push(Fr::one());
+        {
+            let batch = proofs.iter().zip(inputs.iter()).map(|(p, i)| (p, &i[..])).collect::<Vec<_>>();
+            assert!(verify_proofs_batch(&pvk, rng, &batch).is_err());
+        }
+    }
+
+    #[test]
+    fn test_prepared_verifying_key_codec() {
+        let rng = &mut thread_rng();
+
+        let params = generate_random_parameters::<Bls12, _, _>(
+            MySillyCircuit { a: None, b: None },
+            rng
+        ).unwrap();
+
+        // A key prepared once by bellman is read back without repeating
+        // the precomputation.
+        let mut v = vec![];
+        bellman::groth16::prepare_verifying_key(&params.vk).write(&mut v).unwrap();
+        let pvk = PreparedVerifyingKey::<Bls12>::read(&v[..]).unwrap();
+
+        let mut vk = vec![];
+        params.vk.write(&mut vk).unwrap();
+        assert!(pvk == prepare_verifying_key(&VerifyingKey::<Bls12>::read(&vk[..]).unwrap()));
+
+        // Both keys are written back byte for byte as bellman wrote them.
+        let mut w = vec![];
+        pvk.write(&mut w).unwrap();
+        assert_eq!(v, w);
+
+        let mut w = vec![];
+        VerifyingKey::<Bls12>::read(&vk[..]).unwrap().write(&mut w).unwrap();
+        assert_eq!(vk, w);
+
+        let a = Fr::rand(rng);
+        let b = Fr::rand(rng);
+        let mut c = a;
+        c.mul_assign(&b);
+
+        let proof = create_random_proof(
+            MySillyCircuit {
+                a: Some(a),
+                b: Some(b)
+            },
+            &params,
+            rng
+        ).unwrap();
+
+        let mut v = vec![];
+        proof.write(&mut v).unwrap();
+        let proof = Proof::<Bls12>::read(&v[..]).unwrap();
+
+        assert!(verify_proof(&pvk, &proof, &[c]).unwrap());
+
+        let from_parts = PreparedVerifyingKey::<Bls12>::from_parts(
+            pvk.alpha_g1_beta_g2,
+            pvk.neg_gamma_g2.clone(),
+            pvk.neg_delta_g2.clone(),
+            pvk.ic.clone()
+        ).unwrap();
+        assert!(pvk == from_parts);
+
+        assert!(PreparedVerifyingKey::<Bls12>::from_parts(
+            pvk.alpha_g1_beta_g2,
+            pvk.neg_gamma_g2.clone(),
+            pvk.neg_delta_g2.clone(),
+            vec![]
+        ).is_err());
+    }
+}
+
+#[cfg(test)]
+mod test_with_bn256 {
+    use super::*;
+    use pairing::bn256::{Bn256, Fr};
+    use rand::{Rand, thread_rng};
+    use bellman::groth16::{generate_random_parameters, create_random_proof};
+    use super::tests::MySillyCircuit;
+
+    #[test]
+    fn test_verify() {
+        let rng = &mut thread_rng();
+
+        let params = generate_random_parameters::<Bn256, _, _>(
+            MySillyCircuit { a: None, b: None },
+            rng
+        ).unwrap();
+
+        let mut v = vec![];
+        params.vk.write(&mut v).unwrap();
+        let pvk = prepare_verifying_key(&VerifyingKey::<Bn256>::read(&v[..]).unwrap());
+
+        let mut v = vec![];
+        bellman::groth16::prepare_verifying_key(&params.vk).write(&mut v).unwrap();
+        assert!(pvk == PreparedVerifyingKey::<Bn256>::read(&v[..]).unwrap());
+
+        let a = Fr::rand(rng);
+        let b = Fr::rand(rng);
+        let mut c = a;
+        c.mul_assign(&b);
+
+        let proof = create_random_proof(
+            MySillyCircuit {
+                a: Some(a),
+                b: Some(b)
+            },
+            &params,
+            rng
+        ).unwrap();
+
+        let mut v = vec![];
+        proof.write(&mut v).unwrap();
+        let proof = Proof::<Bn256>::read(&v[..]).unwrap();
+
+        assert!(verify_proof(&pvk, &proof, &[c]).unwrap());
+        assert!(!verify_proof(&pvk, &proof, &[a]).unwrap());
+        assert_eq!(verify_proofs_batch(&pvk, rng, &[(&proof, &[c][..])]).unwrap(), None);
+    }
+}
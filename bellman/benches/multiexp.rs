@@ -0,0 +1,90 @@
+//! Compares the bucket accumulation modes of `multiexp` in G1 and G2.
+//!
+//! ```text
+//! cargo bench -p bellman --bench multiexp
+//! ```
+//!
+//! `BELLMAN_BENCH_BASES` changes the number of bases and
+//! `BELLMAN_NUM_CPUS` the number of threads.
+
+extern crate bellman;
+extern crate futures;
+extern crate pairing;
+extern crate rand;
+
+use std::env;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use futures::Future;
+use rand::{thread_rng, Rand};
+
+use pairing::{CurveAffine, CurveProjective, Engine, PrimeField};
+use pairing::bls12_381::Bls12;
+
+use bellman::multicore::Worker;
+use bellman::multiexp::{
+    BucketAccumulation,
+    FullDensity,
+    multiexp_with_accumulation
+};
+
+const BASES: usize = 1 << 16;
+const RUNS: u32 = 3;
+
+fn bench<G: CurveAffine>(name: &str, worker: &Worker, num_bases: usize) {
+    let rng = &mut thread_rng();
+
+    // Multiples of a random point, which are much faster to sample.
+    let g = G::Projective::rand(rng);
+    let mut acc = g;
+    let mut bases = (0..num_bases).map(|_| { acc.add_assign(&g); acc }).collect::<Vec<_>>();
+    G::Projective::batch_normalization(&mut bases);
+    let bases = Arc::new(bases.into_iter().map(|p| p.into_affine()).collect::<Vec<_>>());
+    let exponents = Arc::new(
+        (0..num_bases).map(|_| <G::Engine as Engine>::Fr::rand(rng).into_repr()).collect::<Vec<_>>()
+    );
+
+    let mut results = vec![];
+    for &accumulation in &[BucketAccumulation::Projective, BucketAccumulation::BatchAffine] {
+        let mut total = Duration::new(0, 0);
+        let mut result = None;
+
+        for _ in 0..RUNS {
+            let start = Instant::now();
+            result = Some(multiexp_with_accumulation(
+                worker,
+                (bases.clone(), 0),
+                FullDensity,
+                exponents.clone(),
+                accumulation
+            ).wait().unwrap());
+            total += start.elapsed();
+        }
+
+        let avg = total / RUNS;
+        println!(
+            "{} {:?}: {}.{:03} seconds",
+            name,
+            accumulation,
+            avg.as_secs(),
+            avg.subsec_millis()
+        );
+
+        results.push(result);
+    }
+
+    assert!(results[0] == results[1]);
+}
+
+fn main() {
+    let num_bases = env::var("BELLMAN_BENCH_BASES").ok()
+        .and_then(|n| n.parse().ok())
+        .unwrap_or(BASES);
+
+    let worker = Worker::new();
+    println!("Multiexps of {} bases with {} threads...", num_bases, worker.num_threads());
+
+    bench::<<Bls12 as Engine>::G1Affine>("G1", &worker, num_bases);
+    bench::<<Bls12 as Engine>::G2Affine>("G2", &worker, num_bases);
+}
@@ -0,0 +1,272 @@
+//! Measures prover wall-clock time on a circuit of about the size of a
+//! Sapling spend (~100k constraints).
+//!
+//! Compare the multicore backends by running it with each of them:
+//!
+//! ```text
+//! cargo bench -p bellman --features multithread --bench prover
+//! cargo bench -p bellman --features multithread-rayon --bench prover
+//! ```
+//!
+//! On a single thread both backends run the same code serially, so they
+//! only differ with several threads. Interleave the runs of the two
+//! backends, as the timings of back-to-back runs drift.
+//!
+//! `BELLMAN_BENCH_CONSTRAINTS` changes the size of the circuit and
+//! `BELLMAN_NUM_CPUS` the number of threads. Setting
+//! `BELLMAN_BENCH_PRECOMPUTED` proves with `PrecomputedParameters`, and
+//! setting `BELLMAN_BENCH_LOW_MEMORY` proves with
+//! `create_proof_low_memory`, spilling to a `FileStore` in the temporary
+//! directory. The peak memory allocated while proving is reported so the
+//! modes can be compared.
+
+extern crate bellman;
+extern crate pairing;
+extern crate rand;
+
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::env;
+use std::fs;
+use std::sync::{Arc, Mutex};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::{Duration, Instant};
+
+use rand::{thread_rng, Rand};
+
+use pairing::{Engine, Field};
+use pairing::bls12_381::{Bls12, Fr};
+
+use bellman::{Circuit, ConstraintSystem, SynthesisError};
+use bellman::groth16::{
+    CancellationToken,
+    FileStore,
+    ParameterSource,
+    PrecomputedParameters,
+    PreparedVerifyingKey,
+    ProverObserver,
+    ProverPhase,
+    create_proof_low_memory_with_observer,
+    create_proof_with_observer,
+    generate_random_parameters,
+    prepare_verifying_key,
+    verify_proof
+};
+use bellman::multicore::Worker;
+
+const SAPLING_SPEND_CONSTRAINTS: usize = 98_777;
+const PROOFS: u32 = 5;
+
+/// Tracks the number of bytes allocated and its peak.
+struct PeakAlloc {
+    current: AtomicUsize,
+    peak: AtomicUsize
+}
+
+impl PeakAlloc {
+    fn current(&self) -> usize {
+        self.current.load(Ordering::SeqCst)
+    }
+
+    fn peak(&self) -> usize {
+        self.peak.load(Ordering::SeqCst)
+    }
+
+    fn reset_peak(&self) {
+        self.peak.store(self.current(), Ordering::SeqCst);
+    }
+}
+
+unsafe impl GlobalAlloc for PeakAlloc {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        let ptr = System.alloc(layout);
+        if !ptr.is_null() {
+            let current = self.current.fetch_add(layout.size(), Ordering::SeqCst) + layout.size();
+            self.peak.fetch_max(current, Ordering::SeqCst);
+        }
+        ptr
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout);
+        self.current.fetch_sub(layout.size(), Ordering::SeqCst);
+    }
+}
+
+#[global_allocator]
+static ALLOC: PeakAlloc = PeakAlloc {
+    current: AtomicUsize::new(0),
+    peak: AtomicUsize::new(0)
+};
+
+/// Records the peak memory of every phase of a proof, above what was
+/// allocated before it started.
+struct PhasePeaks {
+    baseline: usize,
+    peaks: Mutex<Vec<(ProverPhase, usize)>>
+}
+
+impl ProverObserver for PhasePeaks {
+    fn phase_started(&self, _: ProverPhase) {
+        ALLOC.reset_peak();
+    }
+
+    fn phase_finished(&self, phase: ProverPhase) {
+        let peak = ALLOC.peak().saturating_sub(self.baseline);
+        self.peaks.lock().unwrap().push((phase, peak));
+    }
+}
+
+/// Repeatedly computes x := x * x + c, with every intermediate value
+/// private and the result public, so the witness is dense like that of
+/// a hash-heavy circuit.
+struct SquareChain<E: Engine> {
+    x: Option<E::Fr>,
+    c: E::Fr,
+    num_constraints: usize
+}
+
+impl<E: Engine> Circuit<E> for SquareChain<E> {
+    fn synthesize<CS: ConstraintSystem<E>>(
+        self,
+        cs: &mut CS
+    ) -> Result<(), SynthesisError>
+    {
+        let c = self.c;
+        let mut x_val = self.x;
+        let mut x = cs.alloc(|| "x", || x_val.ok_or(SynthesisError::AssignmentMissing))?;
+
+        for i in 0..self.num_constraints - 1 {
+            let y_val = x_val.map(|mut x| {
+                x.square();
+                x.add_assign(&c);
+                x
+            });
+            let y = cs.alloc(|| format!("y {}", i), || y_val.ok_or(SynthesisError::AssignmentMissing))?;
+
+            // x * x = y - c
+            cs.enforce(
+                || format!("round {}", i),
+                |lc| lc + x,
+                |lc| lc + x,
+                |lc| lc + y - (c, CS::one())
+            );
+
+            x = y;
+            x_val = y_val;
+        }
+
+        let out = cs.alloc_input(|| "out", || x_val.ok_or(SynthesisError::AssignmentMissing))?;
+        cs.enforce(
+            || "out",
+            |lc| lc + x,
+            |lc| lc + CS::one(),
+            |lc| lc + out
+        );
+
+        Ok(())
+    }
+}
+
+fn main() {
+    let num_constraints = env::var("BELLMAN_BENCH_CONSTRAINTS").ok()
+        .and_then(|n| n.parse().ok())
+        .unwrap_or(SAPLING_SPEND_CONSTRAINTS);
+
+    let rng = &mut thread_rng();
+    let c = Fr::rand(rng);
+
+    println!("Creating parameters for {} constraints...", num_constraints);
+    let params = generate_random_parameters::<Bls12, _, _>(
+        SquareChain { x: None, c: c, num_constraints: num_constraints },
+        rng
+    ).unwrap();
+    let pvk = prepare_verifying_key(&params.vk);
+
+    let worker = Worker::new();
+
+    if env::var_os("BELLMAN_BENCH_PRECOMPUTED").is_some() {
+        println!("Precomputing bases...");
+        let start = Instant::now();
+        let precomputed = PrecomputedParameters::new(&params, &worker);
+        let elapsed = start.elapsed();
+        println!("Precomputed in {}.{:03} seconds", elapsed.as_secs(), elapsed.subsec_millis());
+
+        bench(&precomputed, &pvk, &worker, c, num_constraints);
+    } else {
+        bench(&params, &pvk, &worker, c, num_constraints);
+    }
+}
+
+fn bench<P: ParameterSource<Bls12> + Copy>(
+    params: P,
+    pvk: &PreparedVerifyingKey<Bls12>,
+    worker: &Worker,
+    c: Fr,
+    num_constraints: usize
+)
+{
+    let rng = &mut thread_rng();
+    println!("Creating {} proofs with {} threads...", PROOFS, worker.num_threads());
+
+    let store_dir = if env::var_os("BELLMAN_BENCH_LOW_MEMORY").is_some() {
+        let dir = env::temp_dir().join(format!("bellman-bench-{}", Fr::rand(rng)));
+        fs::create_dir(&dir).unwrap();
+        println!("Proving in low-memory mode, spilling to {}", dir.display());
+        Some(dir)
+    } else {
+        None
+    };
+
+    let mut total = Duration::new(0, 0);
+    let mut peaks = vec![];
+    for _ in 0..PROOFS {
+        let x = Fr::rand(rng);
+        let mut out = x;
+        for _ in 0..num_constraints - 1 {
+            out.square();
+            out.add_assign(&c);
+        }
+
+        let r = Fr::rand(rng);
+        let s = Fr::rand(rng);
+
+        let circuit = SquareChain { x: Some(x), c: c, num_constraints: num_constraints };
+        let observer = Arc::new(PhasePeaks {
+            baseline: ALLOC.current(),
+            peaks: Mutex::new(vec![])
+        });
+        let cancel = CancellationToken::new();
+
+        let start = Instant::now();
+        let proof = match store_dir {
+            Some(ref dir) => create_proof_low_memory_with_observer(
+                circuit, params, r, s, worker, &mut FileStore::new(dir), observer.clone(), &cancel
+            ),
+            None => create_proof_with_observer(circuit, params, r, s, worker, observer.clone(), &cancel)
+        }.unwrap();
+        total += start.elapsed();
+
+        for (i, &(phase, peak)) in observer.peaks.lock().unwrap().iter().enumerate() {
+            if peaks.len() <= i {
+                peaks.push((phase, 0));
+            }
+            peaks[i].1 = peaks[i].1.max(peak);
+        }
+
+        assert!(verify_proof(pvk, &proof, &[out]).unwrap());
+    }
+
+    let avg = total / PROOFS;
+    println!(
+        "Average proving time: {}.{:03} seconds",
+        avg.as_secs(),
+        avg.subsec_millis()
+    );
+    for (phase, peak) in peaks {
+        println!("Peak memory during {:?}: {:.1} MiB", phase, peak as f64 / (1 << 20) as f64);
+    }
+
+    if let Some(dir) = store_dir {
+        fs::remove_dir(dir).unwrap();
+    }
+}
@@ -9,6 +9,10 @@
 //! roots to be the powers of a 2^n root of unity in the field.
 //! This allows us to perform polynomial operations in O(n)
 //! by performing an O(n log n) FFT over such a domain.
+//!
+//! Domains of size 2^n * 3^m are also supported when the scalar
+//! field has such a subgroup, so that circuits just above a power
+//! of two do not need a domain twice their size.
 
 #[cfg(not(feature = "std"))]
 use alloc::string::String;
@@ -18,6 +22,7 @@ use pairing::{
     Engine,
     Field,
     PrimeField,
+    PrimeFieldRepr,
     CurveProjective
 };
 
@@ -27,13 +32,102 @@ use super::{
 
 use super::multicore::Worker;
 
+/// How the size of an evaluation domain is picked from the number of
+/// coefficients it must hold.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum DomainKind {
+    /// The next power of two.
+    Radix2,
+    /// The smallest `2^k * 3^m` that the scalar field has a subgroup of.
+    MixedRadix
+}
+
+impl Default for DomainKind {
+    fn default() -> Self {
+        DomainKind::Radix2
+    }
+}
+
+impl DomainKind {
+    /// The size of the domain for `num_coeffs` coefficients.
+    pub fn size<E: Engine>(self, num_coeffs: usize) -> Result<usize, SynthesisError> {
+        let max_exp3 = match self {
+            DomainKind::Radix2 => 0,
+            DomainKind::MixedRadix => three_adicity::<E>()
+        };
+
+        let mut best = None;
+        let mut pow3 = 1usize;
+        for _ in 0..max_exp3 + 1 {
+            // The smallest power of two times `pow3` holding the coeffs
+            let mut m = pow3;
+            let mut exp = 0;
+            while m < num_coeffs && exp < E::Fr::S {
+                m *= 2;
+                exp += 1;
+            }
+
+            // The pairing-friendly curve may not be able to support
+            // large enough (radix2) evaluation domains.
+            if m >= num_coeffs && exp < E::Fr::S && best.map_or(true, |best| m < best) {
+                best = Some(m);
+            }
+
+            pow3 = match pow3.checked_mul(3) {
+                Some(pow3) => pow3,
+                None => break
+            };
+        }
+
+        best.ok_or(SynthesisError::PolynomialDegreeTooLarge)
+    }
+}
+
+// Divides `n` by 3 in place, returning the remainder.
+fn div3<R: PrimeFieldRepr>(n: &mut R) -> u64 {
+    let mut rem = 0u64;
+    for limb in n.as_mut().iter_mut().rev() {
+        let cur = (u128::from(rem) << 64) | u128::from(*limb);
+        *limb = (cur / 3) as u64;
+        rem = (cur % 3) as u64;
+    }
+
+    rem
+}
+
+// The largest `m` such that `3^m` divides `r - 1`.
+fn three_adicity<E: Engine>() -> u32 {
+    let mut n = E::Fr::char();
+    n.sub_noborrow(&1.into());
+
+    let mut m = 0;
+    while div3(&mut n) == 0 {
+        m += 1;
+    }
+
+    m
+}
+
+// A primitive `3^m`th root of unity, where `3^m` divides `r - 1`.
+fn root_of_unity_3<E: Engine>(m: u32) -> E::Fr {
+    let mut n = E::Fr::char();
+    n.sub_noborrow(&1.into());
+    for _ in 0..m {
+        assert_eq!(div3(&mut n), 0);
+    }
+
+    E::Fr::multiplicative_generator().pow(n)
+}
+
 pub struct EvaluationDomain<E: Engine, G: Group<E>> {
     coeffs: Vec<G>,
     exp: u32,
+    exp3: u32,
     omega: E::Fr,
     omegainv: E::Fr,
     geninv: E::Fr,
-    minv: E::Fr
+    minv: E::Fr,
+    in_place: bool
 }
 
 impl<E: Engine, G: Group<E>> EvaluationDomain<E, G> {
@@ -49,20 +143,46 @@ impl<E: Engine, G: Group<E>> EvaluationDomain<E, G> {
         self.coeffs
     }
 
-    pub fn from_coeffs(mut coeffs: Vec<G>) -> Result<EvaluationDomain<E, G>, SynthesisError>
+    pub fn from_coeffs(coeffs: Vec<G>) -> Result<EvaluationDomain<E, G>, SynthesisError>
+    {
+        Self::from_coeffs_with_kind(coeffs, DomainKind::Radix2)
+    }
+
+    /// Pads `coeffs` to the size of the domain of `kind` holding them.
+    pub fn from_coeffs_with_kind(
+        coeffs: Vec<G>,
+        kind: DomainKind
+    ) -> Result<EvaluationDomain<E, G>, SynthesisError>
+    {
+        let m = kind.size::<E>(coeffs.len())?;
+
+        Self::from_coeffs_with_size(coeffs, m)
+    }
+
+    /// Pads `coeffs` to a domain of size `m`, which must be `2^k * 3^j`
+    /// for a subgroup of the scalar field.
+    pub fn from_coeffs_with_size(
+        mut coeffs: Vec<G>,
+        m: usize
+    ) -> Result<EvaluationDomain<E, G>, SynthesisError>
     {
+        if m < coeffs.len() || m == 0 {
+            return Err(SynthesisError::PolynomialDegreeTooLarge)
+        }
+
         // Compute the size of our evaluation domain
-        let mut m = 1;
-        let mut exp = 0;
-        while m < coeffs.len() {
-            m *= 2;
-            exp += 1;
+        let exp = m.trailing_zeros();
+        let mut rest = m >> exp;
+        let mut exp3 = 0;
+        while rest % 3 == 0 {
+            rest /= 3;
+            exp3 += 1;
+        }
 
-            // The pairing-friendly curve may not be able to support
-            // large enough (radix2) evaluation domains.
-            if exp >= E::Fr::S {
-                return Err(SynthesisError::PolynomialDegreeTooLarge)
-            }
+        // The pairing-friendly curve may not be able to support
+        // large enough (radix2) evaluation domains.
+        if rest != 1 || exp >= E::Fr::S || (exp3 > 0 && exp3 > three_adicity::<E>()) {
+            return Err(SynthesisError::PolynomialDegreeTooLarge)
         }
 
         // Compute omega, the 2^exp primitive root of unity
@@ -71,27 +191,43 @@ impl<E: Engine, G: Group<E>> EvaluationDomain<E, G> {
             omega.square();
         }
 
+        // and multiply it by a 3^exp3 primitive root of unity.
+        if exp3 > 0 {
+            omega.mul_assign(&root_of_unity_3::<E>(exp3));
+        }
+
         // Extend the coeffs vector with zeroes if necessary
         coeffs.resize(m, G::group_zero());
 
         Ok(EvaluationDomain {
             coeffs: coeffs,
             exp: exp,
+            exp3: exp3,
             omega: omega,
             omegainv: omega.inverse().unwrap(),
             geninv: E::Fr::multiplicative_generator().inverse().unwrap(),
-            minv: E::Fr::from_str(&format!("{}", m)).unwrap().inverse().unwrap()
+            minv: E::Fr::from_str(&format!("{}", m)).unwrap().inverse().unwrap(),
+            in_place: false
         })
     }
 
+    /// Makes the radix-2 FFTs of this domain work in place, splitting the
+    /// butterflies of each stage across the worker's threads, instead of
+    /// using scratch space of the size of the domain to run sub-FFTs in
+    /// parallel. The radix-3 layers of mixed-radix domains still copy.
+    pub fn set_in_place_fft(&mut self, in_place: bool)
+    {
+        self.in_place = in_place;
+    }
+
     pub fn fft(&mut self, worker: &Worker)
     {
-        best_fft(&mut self.coeffs, worker, &self.omega, self.exp);
+        mixed_radix_fft(&mut self.coeffs, worker, &self.omega, self.exp, self.exp3, self.in_place);
     }
 
     pub fn ifft(&mut self, worker: &Worker)
     {
-        best_fft(&mut self.coeffs, worker, &self.omegainv, self.exp);
+        mixed_radix_fft(&mut self.coeffs, worker, &self.omegainv, self.exp, self.exp3, self.in_place);
 
         worker.scope(self.coeffs.len(), |scope, chunk| {
             let minv = self.minv;
@@ -136,7 +272,7 @@ impl<E: Engine, G: Group<E>> EvaluationDomain<E, G> {
     }
 
     /// This evaluates t(tau) for this domain, which is
-    /// tau^m - 1 for these radix-2 domains.
+    /// tau^m - 1 for these multiplicative subgroups.
     pub fn z(&self, tau: &E::Fr) -> E::Fr {
         let mut tmp = tau.pow(&[self.coeffs.len() as u64]);
         tmp.sub_assign(&E::Fr::one());
@@ -193,6 +329,67 @@ impl<E: Engine, G: Group<E>> EvaluationDomain<E, G> {
     }
 }
 
+impl<E: Engine> EvaluationDomain<E, Scalar<E>> {
+    /// Evaluates at `x` the polynomial whose evaluations over the domain
+    /// these are, without interpolating it, using the barycentric formula
+    ///
+    /// f(x) = (x^m - 1) / m * sum_i f(omega^i) omega^i / (x - omega^i).
+    pub fn evaluate_barycentric(&self, x: &E::Fr) -> E::Fr
+    {
+        let z = self.z(x);
+        let mut omega_i = E::Fr::one();
+
+        // x is a point of the domain.
+        if z.is_zero() {
+            for eval in &self.coeffs {
+                if omega_i == *x {
+                    return eval.0;
+                }
+                omega_i.mul_assign(&self.omega);
+            }
+
+            unreachable!("x^m = 1 but x is not a power of omega");
+        }
+
+        // Invert every x - omega^i with a single inversion.
+        let mut denoms = Vec::with_capacity(self.coeffs.len());
+        let mut products = Vec::with_capacity(self.coeffs.len());
+        let mut acc = E::Fr::one();
+        for _ in 0..self.coeffs.len() {
+            let mut denom = *x;
+            denom.sub_assign(&omega_i);
+            acc.mul_assign(&denom);
+            denoms.push(denom);
+            products.push(acc);
+            omega_i.mul_assign(&self.omega);
+        }
+
+        let mut inv = acc.inverse().unwrap();
+        let mut sum = E::Fr::zero();
+        for i in (0..self.coeffs.len()).rev() {
+            // The inverse of denoms[i], and of the product before it
+            let mut denom_inv = inv;
+            if i > 0 {
+                denom_inv.mul_assign(&products[i - 1]);
+            }
+            inv.mul_assign(&denoms[i]);
+
+            // omega^i, walking the powers back down
+            omega_i.mul_assign(&self.omegainv);
+
+            let mut term = self.coeffs[i].0;
+            term.mul_assign(&omega_i);
+            term.mul_assign(&denom_inv);
+            sum.add_assign(&term);
+        }
+
+        sum.mul_assign(&z);
+        sum.mul_assign(&self.minv);
+
+        sum
+    }
+}
+
 pub trait Group<E: Engine>: Sized + Copy + Clone + Send + Sync {
     fn group_zero() -> Self;
     fn group_mul_assign(&mut self, by: &E::Fr);
@@ -262,6 +459,93 @@ impl<E: Engine> Group<E> for Scalar<E> {
     }
 }
 
+// Splits the FFT of size `2^log_n * 3^log3_n` into three FFTs over
+// every third element, down to radix-2 FFTs, and combines them.
+fn mixed_radix_fft<E: Engine, T: Group<E>>(
+    a: &mut [T],
+    worker: &Worker,
+    omega: &E::Fr,
+    log_n: u32,
+    log3_n: u32,
+    in_place: bool
+)
+{
+    if log3_n == 0 {
+        if in_place {
+            in_place_fft(a, worker, omega, log_n);
+        } else {
+            best_fft(a, worker, omega, log_n);
+        }
+        return;
+    }
+
+    let m = a.len() / 3;
+    assert_eq!(m * 3, a.len());
+
+    let new_omega = omega.pow(&[3]);
+    let mut parts = (0..3).map(|r| {
+        let mut part = a.iter().skip(r).step_by(3).cloned().collect::<Vec<_>>();
+        mixed_radix_fft(&mut part, worker, &new_omega, log_n, log3_n - 1, in_place);
+        part
+    }).collect::<Vec<_>>();
+
+    let p2 = parts.pop().unwrap();
+    let p1 = parts.pop().unwrap();
+    let p0 = parts.pop().unwrap();
+
+    // A primitive cube root of unity
+    let zeta = omega.pow(&[m as u64]);
+
+    let (a0, rest) = a.split_at_mut(m);
+    let (a1, a2) = rest.split_at_mut(m);
+
+    worker.scope(m, |scope, chunk| {
+        let (p0, p1, p2) = (&p0, &p1, &p2);
+
+        for (i, ((a0, a1), a2)) in a0.chunks_mut(chunk)
+                                      .zip(a1.chunks_mut(chunk))
+                                      .zip(a2.chunks_mut(chunk))
+                                      .enumerate()
+        {
+            scope.spawn(move || {
+                let start = i * chunk;
+                let mut w = omega.pow(&[start as u64]);
+
+                for (j, ((a0, a1), a2)) in a0.iter_mut().zip(a1.iter_mut()).zip(a2.iter_mut()).enumerate() {
+                    let mut w2 = w;
+                    w2.square();
+
+                    // t0 + t1 + t2, t0 + zeta t1 + zeta^2 t2 and
+                    // t0 + zeta^2 t1 + zeta t2, using 1 + zeta + zeta^2 = 0
+                    let t0 = p0[start + j];
+                    let mut t1 = p1[start + j];
+                    t1.group_mul_assign(&w);
+                    let mut t2 = p2[start + j];
+                    t2.group_mul_assign(&w2);
+
+                    let mut d = t1;
+                    d.group_sub_assign(&t2);
+                    d.group_mul_assign(&zeta);
+
+                    *a0 = t0;
+                    a0.group_add_assign(&t1);
+                    a0.group_add_assign(&t2);
+
+                    *a1 = t0;
+                    a1.group_sub_assign(&t2);
+                    a1.group_add_assign(&d);
+
+                    *a2 = t0;
+                    a2.group_sub_assign(&t1);
+                    a2.group_sub_assign(&d);
+
+                    w.mul_assign(omega);
+                }
+            });
+        }
+    });
+}
+
 fn best_fft<E: Engine, T: Group<E>>(a: &mut [T], worker: &Worker, omega: &E::Fr, log_n: u32)
 {
     let log_cpus = worker.log_num_cpus();
@@ -273,17 +557,17 @@ fn best_fft<E: Engine, T: Group<E>>(a: &mut [T], worker: &Worker, omega: &E::Fr,
     }
 }
 
-fn serial_fft<E: Engine, T: Group<E>>(a: &mut [T], omega: &E::Fr, log_n: u32)
-{
-    fn bitreverse(mut n: u32, l: u32) -> u32 {
-        let mut r = 0;
-        for _ in 0..l {
-            r = (r << 1) | (n & 1);
-            n >>= 1;
-        }
-        r
+fn bitreverse(mut n: u32, l: u32) -> u32 {
+    let mut r = 0;
+    for _ in 0..l {
+        r = (r << 1) | (n & 1);
+        n >>= 1;
     }
+    r
+}
 
+fn serial_fft<E: Engine, T: Group<E>>(a: &mut [T], omega: &E::Fr, log_n: u32)
+{
     let n = a.len() as u32;
     assert_eq!(n, 1 << log_n);
 
@@ -318,6 +602,75 @@ fn serial_fft<E: Engine, T: Group<E>>(a: &mut [T], omega: &E::Fr, log_n: u32)
     }
 }
 
+// The iterative FFT of `serial_fft`, with the butterflies of every stage
+// split across the worker's threads so that no scratch space is needed.
+fn in_place_fft<E: Engine, T: Group<E>>(a: &mut [T], worker: &Worker, omega: &E::Fr, log_n: u32)
+{
+    let log_cpus = worker.log_num_cpus();
+
+    if log_n <= log_cpus || log_cpus == 0 {
+        serial_fft(a, omega, log_n);
+        return;
+    }
+
+    let n = a.len() as u32;
+    assert_eq!(n, 1 << log_n);
+
+    for k in 0..n {
+        let rk = bitreverse(k, log_n);
+        if k < rk {
+            a.swap(rk as usize, k as usize);
+        }
+    }
+
+    // Butterflies per thread in every stage
+    let chunk = 1usize << (log_n - 1 - log_cpus);
+
+    let mut m = 1usize;
+    for _ in 0..log_n {
+        let w_m = omega.pow(&[(n as usize / (2*m)) as u64]);
+
+        worker.scope(0, |scope, _| {
+            if m <= chunk {
+                // Each thread takes whole groups of butterflies.
+                for a in a.chunks_mut(2 * chunk) {
+                    scope.spawn(move || {
+                        for a in a.chunks_mut(2*m) {
+                            let (lo, hi) = a.split_at_mut(m);
+                            butterflies(lo, hi, E::Fr::one(), &w_m);
+                        }
+                    });
+                }
+            } else {
+                // Each group of butterflies is split across threads.
+                for a in a.chunks_mut(2*m) {
+                    let (lo, hi) = a.split_at_mut(m);
+                    for (i, (lo, hi)) in lo.chunks_mut(chunk).zip(hi.chunks_mut(chunk)).enumerate() {
+                        scope.spawn(move || {
+                            let w = w_m.pow(&[(i * chunk) as u64]);
+                            butterflies(lo, hi, w, &w_m);
+                        });
+                    }
+                }
+            }
+        });
+
+        m *= 2;
+    }
+}
+
+fn butterflies<E: Engine, T: Group<E>>(lo: &mut [T], hi: &mut [T], mut w: E::Fr, w_m: &E::Fr)
+{
+    for (lo, hi) in lo.iter_mut().zip(hi.iter_mut()) {
+        let mut t = *hi;
+        t.group_mul_assign(&w);
+        *hi = *lo;
+        hi.group_sub_assign(&t);
+        lo.group_add_assign(&t);
+        w.mul_assign(w_m);
+    }
+}
+
 fn parallel_fft<E: Engine, T: Group<E>>(
     a: &mut [T],
     worker: &Worker,
@@ -497,3 +850,81 @@ fn parallel_fft_consistency() {
 
     test_consistency::<Bls12, _>(rng);
 }
+
+#[test]
+fn in_place_fft_consistency() {
+    use pairing::bls12_381::{Bls12, Fr};
+    use rand::{self, Rand};
+
+    let rng = &mut rand::thread_rng();
+    let worker = Worker::builder().num_threads(4).build();
+
+    for log_d in 0..10 {
+        let d = 1 << log_d;
+
+        let v = (0..d).map(|_| Scalar::<Bls12>(Fr::rand(rng))).collect::<Vec<_>>();
+        let mut v1 = EvaluationDomain::from_coeffs(v.clone()).unwrap();
+        let mut v2 = EvaluationDomain::from_coeffs(v.clone()).unwrap();
+
+        in_place_fft(&mut v1.coeffs, &worker, &v1.omega, log_d);
+        serial_fft(&mut v2.coeffs, &v2.omega, log_d);
+        assert!(v1.coeffs == v2.coeffs);
+
+        v1.set_in_place_fft(true);
+        v1.ifft(&worker);
+        assert!(v1.coeffs == v);
+    }
+}
+
+#[test]
+fn mixed_radix_domains() {
+    use pairing::bls12_381::{Bls12, Fr};
+    use rand::{self, Rand};
+
+    let rng = &mut rand::thread_rng();
+    let worker = Worker::new();
+
+    // r - 1 has a single factor of 3 for BLS12-381.
+    assert_eq!(three_adicity::<Bls12>(), 1);
+    assert_eq!(DomainKind::Radix2.size::<Bls12>(65).unwrap(), 128);
+    assert_eq!(DomainKind::MixedRadix.size::<Bls12>(65).unwrap(), 96);
+    assert_eq!(DomainKind::MixedRadix.size::<Bls12>(64).unwrap(), 64);
+    assert_eq!(DomainKind::MixedRadix.size::<Bls12>(0).unwrap(), 1);
+    assert_eq!(DomainKind::MixedRadix.size::<Bls12>(3).unwrap(), 3);
+    assert!(EvaluationDomain::<Bls12, _>::from_coeffs_with_size(vec![Scalar::<Bls12>(Fr::zero()); 4], 9).is_err());
+    assert!(EvaluationDomain::<Bls12, _>::from_coeffs_with_size(vec![Scalar::<Bls12>(Fr::zero()); 4], 3).is_err());
+
+    for &size in &[3, 6, 12, 48, 96 * 4] {
+        let v = (0..size).map(|_| Scalar::<Bls12>(Fr::rand(rng))).collect::<Vec<_>>();
+        let mut domain = EvaluationDomain::<Bls12, _>::from_coeffs_with_size(v.clone(), size).unwrap();
+
+        // omega is a primitive root of unity of the order of the domain.
+        assert_eq!(domain.omega.pow(&[size as u64]), Fr::one());
+        assert!(domain.omega.pow(&[(size / 2) as u64]) != Fr::one());
+        assert!(domain.omega.pow(&[(size / 3) as u64]) != Fr::one());
+
+        domain.fft(&worker);
+
+        // Naive evaluation at the powers of omega
+        for (i, eval) in domain.coeffs.iter().enumerate() {
+            let x = domain.omega.pow(&[i as u64]);
+            let mut acc = Fr::zero();
+            for c in v.iter().rev() {
+                acc.mul_assign(&x);
+                acc.add_assign(&c.0);
+            }
+
+            assert!(acc == eval.0);
+        }
+
+        domain.ifft(&worker);
+        assert!(v == domain.coeffs);
+        domain.coset_fft(&worker);
+        domain.icoset_fft(&worker);
+        assert!(v == domain.coeffs);
+
+        // z vanishes on the domain but not on the coset.
+        assert!(domain.z(&domain.omega).is_zero());
+        assert!(!domain.z(&Fr::multiplicative_generator()).is_zero());
+    }
+}
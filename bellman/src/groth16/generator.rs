@@ -33,6 +33,7 @@ use ::{
 };
 
 use ::domain::{
+    DomainKind,
     EvaluationDomain,
     Scalar
 };
@@ -71,16 +72,55 @@ pub fn generate_random_parameters<E, C, R>(
 
 /// This is our assembly structure that we'll use to synthesize the
 /// circuit into a QAP.
-struct KeypairAssembly<E: Engine> {
-    num_inputs: usize,
-    num_aux: usize,
-    num_constraints: usize,
-    at_inputs: Vec<Vec<(E::Fr, usize)>>,
-    bt_inputs: Vec<Vec<(E::Fr, usize)>>,
-    ct_inputs: Vec<Vec<(E::Fr, usize)>>,
-    at_aux: Vec<Vec<(E::Fr, usize)>>,
-    bt_aux: Vec<Vec<(E::Fr, usize)>>,
-    ct_aux: Vec<Vec<(E::Fr, usize)>>
+pub(super) struct KeypairAssembly<E: Engine> {
+    pub(super) num_inputs: usize,
+    pub(super) num_aux: usize,
+    pub(super) num_constraints: usize,
+    pub(super) at_inputs: Vec<Vec<(E::Fr, usize)>>,
+    pub(super) bt_inputs: Vec<Vec<(E::Fr, usize)>>,
+    pub(super) ct_inputs: Vec<Vec<(E::Fr, usize)>>,
+    pub(super) at_aux: Vec<Vec<(E::Fr, usize)>>,
+    pub(super) bt_aux: Vec<Vec<(E::Fr, usize)>>,
+    pub(super) ct_aux: Vec<Vec<(E::Fr, usize)>>
+}
+
+impl<E: Engine> KeypairAssembly<E> {
+    /// Synthesizes the circuit into a QAP, including the "one" input
+    /// variable and the input constraints shared by every CRS.
+    pub(super) fn synthesize<C: Circuit<E>>(
+        circuit: C
+    ) -> Result<Self, SynthesisError>
+    {
+        let mut assembly = KeypairAssembly {
+            num_inputs: 0,
+            num_aux: 0,
+            num_constraints: 0,
+            at_inputs: vec![],
+            bt_inputs: vec![],
+            ct_inputs: vec![],
+            at_aux: vec![],
+            bt_aux: vec![],
+            ct_aux: vec![]
+        };
+
+        // Allocate the "one" input variable
+        assembly.alloc_input(|| "", || Ok(E::Fr::one()))?;
+
+        // Synthesize the circuit.
+        circuit.synthesize(&mut assembly)?;
+
+        // Input constraints to ensure full density of IC query
+        // x * 0 = 0
+        for i in 0..assembly.num_inputs {
+            assembly.enforce(|| "",
+                |lc| lc + Variable(Index::Input(i)),
+                |lc| lc,
+                |lc| lc,
+            );
+        }
+
+        Ok(assembly)
+    }
 }
 
 impl<E: Engine> ConstraintSystem<E> for KeypairAssembly<E> {
@@ -189,37 +229,70 @@ pub fn generate_parameters<E, C>(
 ) -> Result<Parameters<E>, SynthesisError>
     where E: Engine, C: Circuit<E>
 {
-    let mut assembly = KeypairAssembly {
-        num_inputs: 0,
-        num_aux: 0,
-        num_constraints: 0,
-        at_inputs: vec![],
-        bt_inputs: vec![],
-        ct_inputs: vec![],
-        at_aux: vec![],
-        bt_aux: vec![],
-        ct_aux: vec![]
-    };
-
-    // Allocate the "one" input variable
-    assembly.alloc_input(|| "", || Ok(E::Fr::one()))?;
+    generate_parameters_with_worker::<E, C>(
+        circuit,
+        g1,
+        g2,
+        alpha,
+        beta,
+        gamma,
+        delta,
+        tau,
+        &Worker::new()
+    )
+}
 
-    // Synthesize the circuit.
-    circuit.synthesize(&mut assembly)?;
+/// Like `generate_parameters`, but runs on `worker` instead of a new
+/// thread pool.
+pub fn generate_parameters_with_worker<E, C>(
+    circuit: C,
+    g1: E::G1,
+    g2: E::G2,
+    alpha: E::Fr,
+    beta: E::Fr,
+    gamma: E::Fr,
+    delta: E::Fr,
+    tau: E::Fr,
+    worker: &Worker
+) -> Result<Parameters<E>, SynthesisError>
+    where E: Engine, C: Circuit<E>
+{
+    generate_parameters_with_domain::<E, C>(
+        circuit,
+        g1,
+        g2,
+        alpha,
+        beta,
+        gamma,
+        delta,
+        tau,
+        worker,
+        DomainKind::Radix2
+    )
+}
 
-    // Input constraints to ensure full density of IC query
-    // x * 0 = 0
-    for i in 0..assembly.num_inputs {
-        assembly.enforce(|| "",
-            |lc| lc + Variable(Index::Input(i)),
-            |lc| lc,
-            |lc| lc,
-        );
-    }
+/// Like `generate_parameters_with_worker`, but with an evaluation domain
+/// of the given `kind`. The prover picks up the size of the domain from
+/// the H query of the parameters.
+pub fn generate_parameters_with_domain<E, C>(
+    circuit: C,
+    g1: E::G1,
+    g2: E::G2,
+    alpha: E::Fr,
+    beta: E::Fr,
+    gamma: E::Fr,
+    delta: E::Fr,
+    tau: E::Fr,
+    worker: &Worker,
+    kind: DomainKind
+) -> Result<Parameters<E>, SynthesisError>
+    where E: Engine, C: Circuit<E>
+{
+    let assembly = KeypairAssembly::synthesize(circuit)?;
 
     // Create bases for blind evaluation of polynomials at tau
     let powers_of_tau = vec![Scalar::<E>(E::Fr::zero()); assembly.num_constraints];
-    let mut powers_of_tau = EvaluationDomain::from_coeffs(powers_of_tau)?;
+    let mut powers_of_tau = EvaluationDomain::from_coeffs_with_kind(powers_of_tau, kind)?;
 
     // Compute G1 window table
     let mut g1_wnaf = Wnaf::new();
@@ -244,8 +317,6 @@ pub fn generate_parameters<E, C>(
     let gamma_inverse = gamma.inverse().ok_or(SynthesisError::UnexpectedIdentity)?;
     let delta_inverse = delta.inverse().ok_or(SynthesisError::UnexpectedIdentity)?;
 
-    let worker = Worker::new();
-
     let mut h = vec![E::G1::zero(); powers_of_tau.as_ref().len() - 1];
     {
         // Compute powers of tau
@@ -296,7 +367,7 @@ pub fn generate_parameters<E, C>(
     }
 
     // Use inverse FFT to convert powers of tau to Lagrange coefficients
-    powers_of_tau.ifft(&worker);
+    powers_of_tau.ifft(worker);
     let powers_of_tau = powers_of_tau.into_coeffs();
 
     let mut a = vec![E::G1::zero(); assembly.num_inputs + assembly.num_aux];
@@ -434,7 +505,7 @@ pub fn generate_parameters<E, C>(
         &gamma_inverse,
         &alpha,
         &beta,
-        &worker
+        worker
     );
 
     // Evaluate for auxillary variables.
@@ -452,7 +523,7 @@ pub fn generate_parameters<E, C>(
         &delta_inverse,
         &alpha,
         &beta,
-        &worker
+        worker
     );
 
     // Don't allow any elements be unconstrained, so that
@@ -0,0 +1,376 @@
+//! A `ParameterSource` backed by a memory-mapped parameter file.
+//!
+//! `Parameters` keeps every query in memory for as long as it is alive,
+//! which makes the memory of a proving node scale with the size of the
+//! circuit. `MappedParameters` instead maps the file written by
+//! `Parameters::write` and only records where each query starts, so bases
+//! are decoded straight from the mapping while the multiexps run.
+
+use pairing::{
+    Engine,
+    CurveAffine,
+    CurveProjective,
+    EncodedPoint
+};
+
+use std::fs::File;
+use std::io;
+use std::marker::PhantomData;
+use std::path::Path;
+use std::sync::Arc;
+
+use memmap::Mmap;
+
+use ::{
+    SynthesisError
+};
+
+use multiexp::{
+    Source,
+    SourceBuilder
+};
+
+use super::{
+    ParameterSource,
+    VerifyingKey,
+    Read
+};
+
+/// Groth16 parameters whose queries are lazily decoded from a
+/// memory-mapped file in the format produced by `Parameters::write`.
+pub struct MappedParameters<E: Engine> {
+    params: Arc<Mmap>,
+
+    // This is always loaded, as it is small and needed up front.
+    vk: VerifyingKey<E>,
+
+    // Byte offset of the first element and number of elements of
+    // each query.
+    h: (usize, usize),
+    l: (usize, usize),
+    a: (usize, usize),
+    b_g1: (usize, usize),
+    b_g2: (usize, usize)
+}
+
+impl<E: Engine> MappedParameters<E> {
+    /// Maps the parameter file at `path`.
+    ///
+    /// If `checked` is set, every point is decoded once while opening and
+    /// checked to be on the curve, in the prime order subgroup and not the
+    /// point at infinity. Otherwise only the layout of the file is checked,
+    /// which is only safe for trusted local files, since proving multiplies
+    /// the points by scalars and that assumes that they are in the
+    /// subgroup.
+    ///
+    /// The file must not be modified while it is mapped.
+    pub fn open<P: AsRef<Path>>(
+        path: P,
+        checked: bool
+    ) -> io::Result<Self>
+    {
+        let file = File::open(path)?;
+        let params = unsafe { Mmap::map(&file)? };
+
+        Self::from_mmap(params, checked)
+    }
+
+    /// Uses an existing mapping of a parameter file. See `open`.
+    pub fn from_mmap(
+        params: Mmap,
+        checked: bool
+    ) -> io::Result<Self>
+    {
+        fn invalid_data<T: ::std::error::Error + Send + Sync + 'static>(e: T) -> io::Error {
+            io::Error::new(io::ErrorKind::InvalidData, e)
+        }
+
+        // Finds the next length-prefixed query and returns its offset and
+        // length, advancing `offset` past it.
+        fn query<G: CurveAffine>(
+            params: &[u8],
+            offset: &mut usize,
+            checked: bool
+        ) -> io::Result<(usize, usize)>
+        {
+            let mut reader = params.get(*offset..).unwrap_or(&[]);
+            let len = reader.read_u32().map_err(invalid_data)? as usize;
+            let start = *offset + 4;
+            let size = <G::Uncompressed as EncodedPoint>::size();
+
+            let end = len.checked_mul(size)
+                .and_then(|n| n.checked_add(start))
+                .filter(|end| *end <= params.len())
+                .ok_or_else(|| io::Error::new(io::ErrorKind::UnexpectedEof, "parameter file is truncated"))?;
+
+            if checked {
+                for bytes in params[start..end].chunks(size) {
+                    let mut repr = G::Uncompressed::empty();
+                    repr.as_mut().copy_from_slice(bytes);
+
+                    if repr.into_affine().map_err(invalid_data)?.is_zero() {
+                        return Err(io::Error::new(io::ErrorKind::InvalidData, "point at infinity"));
+                    }
+                }
+            }
+
+            *offset = end;
+
+            Ok((start, len))
+        }
+
+        let (vk, mut offset) = {
+            let mut reader = &params[..];
+            let vk = VerifyingKey::<E>::read(&mut reader).map_err(invalid_data)?;
+
+            (vk, params.len() - reader.len())
+        };
+
+        let h = query::<E::G1Affine>(&params, &mut offset, checked)?;
+        let l = query::<E::G1Affine>(&params, &mut offset, checked)?;
+        let a = query::<E::G1Affine>(&params, &mut offset, checked)?;
+        let b_g1 = query::<E::G1Affine>(&params, &mut offset, checked)?;
+        let b_g2 = query::<E::G2Affine>(&params, &mut offset, checked)?;
+
+        Ok(MappedParameters {
+            params: Arc::new(params),
+            vk: vk,
+            h: h,
+            l: l,
+            a: a,
+            b_g1: b_g1,
+            b_g2: b_g2
+        })
+    }
+
+    pub fn vk(&self) -> &VerifyingKey<E> {
+        &self.vk
+    }
+
+    fn source<G: CurveAffine>(&self, query: (usize, usize), skip: usize) -> MappedSource<G> {
+        MappedSource {
+            params: self.params.clone(),
+            start: query.0,
+            len: query.1,
+            index: skip,
+            _marker: PhantomData
+        }
+    }
+}
+
+/// A source of bases that decodes each base from the mapped parameter
+/// file as it is consumed. Points were validated when the file was opened
+/// (or trusted), so decoding here is unchecked.
+pub struct MappedSource<G: CurveAffine> {
+    params: Arc<Mmap>,
+    start: usize,
+    len: usize,
+    index: usize,
+    _marker: PhantomData<G>
+}
+
+impl<G: CurveAffine> Clone for MappedSource<G> {
+    fn clone(&self) -> Self {
+        MappedSource {
+            params: self.params.clone(),
+            start: self.start,
+            len: self.len,
+            index: self.index,
+            _marker: PhantomData
+        }
+    }
+}
+
+impl<G: CurveAffine> SourceBuilder<G> for MappedSource<G> {
+    type Source = MappedSource<G>;
+
+    fn new(self) -> MappedSource<G> {
+        self
+    }
+}
+
+impl<G: CurveAffine> Source<G> for MappedSource<G> {
+    fn add_assign_mixed(&mut self, to: &mut <G as CurveAffine>::Projective) -> Result<(), SynthesisError> {
+        to.add_assign_mixed(&self.next_base()?);
+
+        Ok(())
+    }
+
+    fn next_base(&mut self) -> Result<G, SynthesisError> {
+        if self.len <= self.index {
+            return Err(io::Error::new(io::ErrorKind::UnexpectedEof, "expected more bases from source").into());
+        }
+
+        let size = <G::Uncompressed as EncodedPoint>::size();
+        let offset = self.start + self.index * size;
+
+        let mut repr = G::Uncompressed::empty();
+        repr.as_mut().copy_from_slice(&self.params[offset..offset + size]);
+
+        let base = repr.into_affine_unchecked()
+            .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+
+        if base.is_zero() {
+            return Err(SynthesisError::UnexpectedIdentity)
+        }
+
+        self.index += 1;
+
+        Ok(base)
+    }
+
+    fn skip(&mut self, amt: usize) -> Result<(), SynthesisError> {
+        if self.len <= self.index {
+            return Err(io::Error::new(io::ErrorKind::UnexpectedEof, "expected more bases from source").into());
+        }
+
+        self.index += amt;
+
+        Ok(())
+    }
+}
+
+impl<'a, E: Engine> ParameterSource<E> for &'a MappedParameters<E> {
+    type G1Builder = MappedSource<E::G1Affine>;
+    type G2Builder = MappedSource<E::G2Affine>;
+
+    fn get_vk(
+        &mut self,
+        _: usize
+    ) -> Result<VerifyingKey<E>, SynthesisError>
+    {
+        Ok(self.vk.clone())
+    }
+
+    fn get_h(
+        &mut self,
+        _: usize
+    ) -> Result<Self::G1Builder, SynthesisError>
+    {
+        Ok(self.source(self.h, 0))
+    }
+
+    fn get_l(
+        &mut self,
+        _: usize
+    ) -> Result<Self::G1Builder, SynthesisError>
+    {
+        Ok(self.source(self.l, 0))
+    }
+
+    fn get_a(
+        &mut self,
+        num_inputs: usize,
+        _: usize
+    ) -> Result<(Self::G1Builder, Self::G1Builder), SynthesisError>
+    {
+        Ok((self.source(self.a, 0), self.source(self.a, num_inputs)))
+    }
+
+    fn get_b_g1(
+        &mut self,
+        num_inputs: usize,
+        _: usize
+    ) -> Result<(Self::G1Builder, Self::G1Builder), SynthesisError>
+    {
+        Ok((self.source(self.b_g1, 0), self.source(self.b_g1, num_inputs)))
+    }
+
+    fn get_b_g2(
+        &mut self,
+        num_inputs: usize,
+        _: usize
+    ) -> Result<(Self::G2Builder, Self::G2Builder), SynthesisError>
+    {
+        Ok((self.source(self.b_g2, 0), self.source(self.b_g2, num_inputs)))
+    }
+    fn get_domain_size(
+        &mut self,
+        _: usize
+    ) -> Result<usize, SynthesisError>
+    {
+        // The H query has one element less than the domain.
+        Ok(self.h.1 + 1)
+    }
+}
+
+#[cfg(test)]
+mod test_with_bls12_381 {
+    use super::*;
+    use super::super::{
+        Parameters,
+        generate_random_parameters,
+        create_proof,
+        prepare_verifying_key,
+        verify_proof
+    };
+
+    use rand::{Rand, thread_rng};
+    use pairing::Field;
+    use pairing::bls12_381::{Bls12, Fr};
+    use super::super::tests::MySillyCircuit;
+    use std::fs;
+    use std::io::Write;
+
+    #[test]
+    fn mapped_parameters() {
+        let rng = &mut thread_rng();
+
+        let params = generate_random_parameters::<Bls12, _, _>(
+            MySillyCircuit { a: None, b: None },
+            rng
+        ).unwrap();
+
+        let mut v = vec![];
+        params.write(&mut v).unwrap();
+
+        let path = ::std::env::temp_dir().join(format!("bellman-mapped-params-{}", Fr::rand(rng)));
+        fs::File::create(&path).unwrap().write_all(&v).unwrap();
+
+        for &checked in &[true, false] {
+            let mapped = MappedParameters::<Bls12>::open(&path, checked).unwrap();
+            assert!(*mapped.vk() == params.vk);
+
+            let pvk = prepare_verifying_key(mapped.vk());
+
+            let a = Fr::rand(rng);
+            let b = Fr::rand(rng);
+            let mut c = a;
+            c.mul_assign(&b);
+
+            let r = Fr::rand(rng);
+            let s = Fr::rand(rng);
+
+            let proof = create_proof(
+                MySillyCircuit { a: Some(a), b: Some(b) },
+                &mapped,
+                r,
+                s
+            ).unwrap();
+
+            let expected = create_proof(
+                MySillyCircuit { a: Some(a), b: Some(b) },
+                &params,
+                r,
+                s
+            ).unwrap();
+
+            assert!(proof == expected);
+            assert!(verify_proof(&pvk, &proof, &[c]).unwrap());
+        }
+
+        // A truncated file is rejected when opening.
+        fs::File::create(&path).unwrap().write_all(&v[..v.len() - 1]).unwrap();
+        assert!(MappedParameters::<Bls12>::open(&path, false).is_err());
+
+        // So is a corrupted point, if checked.
+        let mut corrupted = v.clone();
+        let last = corrupted.len() - 1;
+        corrupted[last] ^= 1;
+        fs::File::create(&path).unwrap().write_all(&corrupted).unwrap();
+        assert!(MappedParameters::<Bls12>::open(&path, true).is_err());
+        assert!(Parameters::<Bls12>::read(&corrupted[..], true).is_err());
+
+        fs::remove_file(&path).unwrap();
+    }
+}
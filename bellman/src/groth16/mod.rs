@@ -1,7 +1,9 @@
 use pairing::{
     Engine,
+    Field,
     CurveAffine,
     EncodedPoint,
+    CanonicalEncoding,
     IoError,
     GroupDecodingError,
 };
@@ -17,23 +19,37 @@ use alloc::sync::Arc;
 #[cfg(not(feature = "std"))]
 use core::marker::Sized;
 
-use multiexp::SourceBuilder;
-// use byteorder::{BigEndian, WriteBytesExt, ReadBytesExt};
-use byteorder::{BigEndian, ByteOrder};
+use domain::DomainKind;
+use multiexp::{SourceBuilder, BucketAccumulation};
 use rstd::prelude::*;
+use rstd::mem::size_of;
 #[cfg(feature = "std")]
 use std::fmt::Debug;
 
 #[cfg(test)]
-mod tests;
+pub(crate) mod tests;
 
 mod generator;
 mod prover;
 mod verifier;
+#[cfg(feature = "std")]
+mod mapped_params;
+mod precomputed;
+mod store;
+#[cfg(feature = "std")]
+mod mpc;
+#[cfg(feature = "std")]
+pub mod powersoftau;
 
 pub use self::generator::*;
 pub use self::prover::*;
 pub use self::verifier::*;
+#[cfg(feature = "std")]
+pub use self::mapped_params::*;
+pub use self::precomputed::*;
+pub use self::store::*;
+#[cfg(feature = "std")]
+pub use self::mpc::*;
 pub use pairing::utils::*;
 
 #[cfg_attr(feature = "std", derive(Debug))]
@@ -160,70 +176,50 @@ impl<E: Engine> PartialEq for VerifyingKey<E> {
 }
 
 impl<E: Engine> VerifyingKey<E> {
-    pub fn write(
+    pub fn write<W: Write>(
         &self,
-        writer: &mut Vec<u8>
+        mut writer: W
     ) -> Result<(), IoError>
-    {        
-        writer.write_all(self.alpha_g1.into_uncompressed().as_ref())?;        
+    {
+        writer.write_all(self.alpha_g1.into_uncompressed().as_ref())?;
         writer.write_all(self.beta_g1.into_uncompressed().as_ref())?;
         writer.write_all(self.beta_g2.into_uncompressed().as_ref())?;
         writer.write_all(self.gamma_g2.into_uncompressed().as_ref())?;
         writer.write_all(self.delta_g1.into_uncompressed().as_ref())?;
         writer.write_all(self.delta_g2.into_uncompressed().as_ref())?;
-        writer.write_u32(self.ic.len() as u32)?;
-        for ic in &self.ic {
-            writer.write_all(ic.into_uncompressed().as_ref())?;
-        }
+        write_points(&mut writer, &self.ic)?;
 
         Ok(())
     }
 
-    pub fn read(
-        mut reader: &[u8]
+    /// Reads a verifying key, consuming exactly the bytes written by
+    /// `write` so that further data can be read from the same reader.
+    pub fn read<R: Read>(
+        mut reader: R
     ) -> Result<Self, IoError>
-    {        
-        let mut g1_repr = <E::G1Affine as CurveAffine>::Uncompressed::empty();        
+    {
+        let mut g1_repr = <E::G1Affine as CurveAffine>::Uncompressed::empty();
         let mut g2_repr = <E::G2Affine as CurveAffine>::Uncompressed::empty();
 
-        reader.read_exact(g1_repr.as_mut())?;               
-        
-        let alpha_g1 = g1_repr.into_affine().map_err(|e| Err(e))?;                    
+        reader.read_exact(g1_repr.as_mut())?;
+        let alpha_g1 = g1_repr.into_affine()?;
 
         reader.read_exact(g1_repr.as_mut())?;
-        let beta_g1 = g1_repr.into_affine().map_err(|e| Err(e))?;        
+        let beta_g1 = g1_repr.into_affine()?;
 
         reader.read_exact(g2_repr.as_mut())?;
-        let beta_g2 = g2_repr.into_affine().map_err(|e| Err(e))?;
+        let beta_g2 = g2_repr.into_affine()?;
 
         reader.read_exact(g2_repr.as_mut())?;
-        let gamma_g2 = g2_repr.into_affine().map_err(|e| Err(e))?;
+        let gamma_g2 = g2_repr.into_affine()?;
 
         reader.read_exact(g1_repr.as_mut())?;
-        let delta_g1 = g1_repr.into_affine().map_err(|e| Err(e))?;
+        let delta_g1 = g1_repr.into_affine()?;
 
         reader.read_exact(g2_repr.as_mut())?;
-        let delta_g2 = g2_repr.into_affine().map_err(|e| Err(e))?;
-            
-        let ic_len = reader.read_u32().unwrap() as usize;        
+        let delta_g2 = g2_repr.into_affine()?;
 
-        let mut ic = vec![];
-        
-        for _ in 0..ic_len {
-            reader.read_exact(g1_repr.as_mut())?;            
-            
-            let g1 = g1_repr
-                     .into_affine()
-                    //  .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))
-                     .and_then(|e| if e.is_zero() {                         
-                        //  Err(io::Error::new(io::ErrorKind::InvalidData, "point at infinity"))
-                         Err(GroupDecodingError::NotOnCurve)
-                     } else {
-                         Ok(e)
-                     })?;
-
-            ic.push(g1);
-        }                
+        let ic = read_points::<E::G1Affine, _>(&mut reader, true)?;
 
         Ok(VerifyingKey {
             alpha_g1: alpha_g1,
@@ -237,6 +233,72 @@ impl<E: Engine> VerifyingKey<E> {
     }
 }
 
+/// Reads `len` elements with `read_one`. The length comes from the input
+/// and is untrusted, so it does not dictate the allocation: at most 1 MiB
+/// is reserved up front, and the vector only grows beyond that as elements
+/// are actually read.
+pub(crate) fn read_vec<T, F>(
+    len: usize,
+    mut read_one: F
+) -> Result<Vec<T>, IoError>
+    where F: FnMut() -> Result<T, IoError>
+{
+    let mut elements = Vec::with_capacity(len.min((1 << 20) / size_of::<T>().max(1)));
+
+    for _ in 0..len {
+        elements.push(read_one()?);
+    }
+
+    Ok(elements)
+}
+
+/// Reads a single encoded point. The point at infinity is rejected.
+pub(crate) fn read_point<E: EncodedPoint, R: Read>(
+    mut reader: R,
+    checked: bool
+) -> Result<E::Affine, IoError>
+{
+    let mut repr = E::empty();
+    reader.read_exact(repr.as_mut())?;
+
+    let point = if checked {
+        repr.into_affine()
+    } else {
+        repr.into_affine_unchecked()
+    }?;
+
+    if point.is_zero() {
+        return Err(IoError::Infinity);
+    }
+
+    Ok(point)
+}
+
+/// Reads a length-prefixed vector of uncompressed points in a single pass.
+/// Points at infinity are rejected.
+fn read_points<G: CurveAffine, R: Read>(
+    mut reader: R,
+    checked: bool
+) -> Result<Vec<G>, IoError>
+{
+    let len = reader.read_u32()? as usize;
+
+    read_vec(len, || read_point::<G::Uncompressed, _>(&mut reader, checked))
+}
+
+fn write_points<G: CurveAffine, W: Write>(
+    mut writer: W,
+    points: &[G]
+) -> Result<(), IoError>
+{
+    writer.write_u32(points.len() as u32)?;
+    for g in points {
+        writer.write_all(g.into_uncompressed().as_ref())?;
+    }
+
+    Ok(())
+}
+
 #[cfg_attr(feature = "std", derive(Debug))]
 #[derive(Clone)]
 pub struct Parameters<E: Engine> {
@@ -275,150 +337,39 @@ impl<E: Engine> PartialEq for Parameters<E> {
 }
 
 impl<E: Engine> Parameters<E> {
-    pub fn write(
+    pub fn write<W: Write>(
         &self,
-        writer: &mut Vec<u8>
+        mut writer: W
     ) -> Result<(), IoError>
-    {        
-        self.vk.write(writer)?;                
-
-        writer.write_u32(self.h.len() as u32)?;
-        for g in &self.h[..] {
-            writer.write_all(g.into_uncompressed().as_ref())?;
-        }
-
-        writer.write_u32(self.l.len() as u32)?;
-        for g in &self.l[..] {
-            writer.write_all(g.into_uncompressed().as_ref())?;
-        }
-
-        writer.write_u32(self.a.len() as u32)?;
-        for g in &self.a[..] {
-            writer.write_all(g.into_uncompressed().as_ref())?;
-        }
-
-        writer.write_u32(self.b_g1.len() as u32)?;
-        for g in &self.b_g1[..] {
-            writer.write_all(g.into_uncompressed().as_ref())?;
-        }
+    {
+        self.vk.write(&mut writer)?;
 
-        writer.write_u32(self.b_g2.len() as u32)?;
-        for g in &self.b_g2[..] {
-            writer.write_all(g.into_uncompressed().as_ref())?;
-        }        
+        write_points(&mut writer, &self.h[..])?;
+        write_points(&mut writer, &self.l[..])?;
+        write_points(&mut writer, &self.a[..])?;
+        write_points(&mut writer, &self.b_g1[..])?;
+        write_points(&mut writer, &self.b_g2[..])?;
 
         Ok(())
     }
 
-    pub fn read(
-        mut reader: &[u8],
+    /// Reads parameters written by `write`, streaming each query from the
+    /// reader in a single pass. If `checked` is false, points are not checked
+    /// to be on the curve or in the prime order subgroup, which is only safe
+    /// for trusted parameters: proving multiplies them by scalars, which
+    /// assumes that they are in the subgroup.
+    pub fn read<R: Read>(
+        mut reader: R,
         checked: bool
-    ) -> Result<Self, GroupDecodingError>
-    {                
-        let read_g1 = |mut reader: &[u8]| -> Result<E::G1Affine, GroupDecodingError> {
-            let mut repr = <E::G1Affine as CurveAffine>::Uncompressed::empty();
-            reader.read_exact(repr.as_mut())?;                        
-
-            if checked {
-                repr
-                .into_affine()
-            } else {
-                repr
-                .into_affine_unchecked()
-            }
-            // .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))
-            .and_then(|e| if e.is_zero() {                
-                // Err(io::Error::new(io::ErrorKind::InvalidData, "point at infinity"))
-                Err(GroupDecodingError::NotOnCurve)
-            } else {                
-                Ok(e)
-            })
-        };
-
-        let read_g2 = |mut reader: &[u8]| -> Result<E::G2Affine, GroupDecodingError> {
-            let mut repr = <E::G2Affine as CurveAffine>::Uncompressed::empty();
-            reader.read_exact(repr.as_mut())?;
-
-            if checked {
-                repr
-                .into_affine()
-            } else {
-                repr
-                .into_affine_unchecked()
-            }
-            // .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))
-            .and_then(|e| if e.is_zero() {
-                // Err(io::Error::new(io::ErrorKind::InvalidData, "point at infinity"))
-                Err(GroupDecodingError::NotOnCurve)
-            } else {
-                Ok(e)
-            })
-        };        
-                        
+    ) -> Result<Self, IoError>
+    {
         let vk = VerifyingKey::<E>::read(&mut reader)?;
-        let mut reader = &reader[1060..];    
-        
-        let mut h = vec![];
-        let mut l = vec![];
-        let mut a = vec![];
-        let mut b_g1 = vec![];
-        let mut b_g2 = vec![];
-        
-        let mut reader_g1 = vec![];
-        let mut reader_g2 = vec![];
-        let mut reader_g3 = vec![];
-        let mut reader_g4 = vec![];           
-
-        let len1 = reader.read_u32().unwrap() as usize;
-        
-        for i in 0..len1 {     
-            reader_g1 = reader[96*i..].to_vec();
-            h.push(read_g1(&mut reader_g1)?);     
-            if i == len1 - 1 {
-                reader_g1 = reader[96*(i+1)..].to_vec();            
-            }            
-        }                
-    
-        let len2 = reader_g1.as_slice().read_u32().unwrap() as usize;
-        reader_g1 = reader_g1[4..].to_vec();
-    
-        for i in 0..len2 {
-            reader_g2 = reader_g1[96*i..].to_vec();
-            l.push(read_g1(&mut reader_g2)?);
-            if i == len2 - 1 {
-                reader_g2 = reader_g1[96*(i+1)..].to_vec();            
-            }   
-        }        
-    
-        let len3 = reader_g2.as_slice().read_u32().unwrap() as usize;
-        reader_g2 = reader_g2[4..].to_vec();
-
-        for i in 0..len3 {
-            reader_g3 = reader_g2[96*i..].to_vec();
-            a.push(read_g1(&mut reader_g3)?);
-            if i == len3 - 1 {
-                reader_g3 = reader_g2[96*(i+1)..].to_vec();            
-            }   
-        }
-    
-        let len4 = reader_g3.as_slice().read_u32().unwrap() as usize;
-        reader_g3 = reader_g3[4..].to_vec();
-
-        for i in 0..len4 {
-            reader_g4 = reader_g3[96*i..].to_vec();
-            b_g1.push(read_g1(&mut reader_g4)?);
-            if i == len4 - 1 {
-                reader_g4 = reader_g3[96*(i+1)..].to_vec();            
-            }   
-        }
-    
-        let len5 = reader_g4.as_slice().read_u32().unwrap() as usize;
-        reader_g4 = reader_g4[4..].to_vec();
 
-        for i in 0..len5 {
-            let mut reader_g5 = reader_g4[192*i..].to_vec();
-            b_g2.push(read_g2(&mut reader_g5)?);            
-        }        
+        let h = read_points::<E::G1Affine, _>(&mut reader, checked)?;
+        let l = read_points::<E::G1Affine, _>(&mut reader, checked)?;
+        let a = read_points::<E::G1Affine, _>(&mut reader, checked)?;
+        let b_g1 = read_points::<E::G1Affine, _>(&mut reader, checked)?;
+        let b_g2 = read_points::<E::G2Affine, _>(&mut reader, checked)?;
 
         Ok(Parameters {
             vk: vk,
@@ -444,6 +395,100 @@ pub struct PreparedVerifyingKey<E: Engine> {
     ic: Vec<E::G1Affine>
 }
 
+impl<E: Engine> PreparedVerifyingKey<E> {
+    /// Builds a prepared verifying key from parts computed elsewhere, e.g.
+    /// kept in storage, so that the G2 line coefficients need not be
+    /// recomputed. Every part is checked to be well-formed: field elements
+    /// must be in the field, the prepared points must have the full set of
+    /// line coefficients and not be the point at infinity, and `ic` must be
+    /// non-empty and contain only points in the prime order subgroup other
+    /// than the point at infinity. The parts are not checked to come from
+    /// the same verifying key.
+    pub fn from_parts(
+        alpha_g1_beta_g2: E::Fqk,
+        neg_gamma_g2: <E::G2Affine as CurveAffine>::Prepared,
+        neg_delta_g2: <E::G2Affine as CurveAffine>::Prepared,
+        ic: Vec<E::G1Affine>
+    ) -> Result<Self, SynthesisError>
+    {
+        let pvk = PreparedVerifyingKey {
+            alpha_g1_beta_g2: alpha_g1_beta_g2,
+            neg_gamma_g2: neg_gamma_g2,
+            neg_delta_g2: neg_delta_g2,
+            ic: ic
+        };
+
+        let ic_is_valid = pvk.ic.iter().all(|p: &E::G1Affine| {
+            !p.is_zero() && p.into_uncompressed().into_affine().is_ok()
+        });
+
+        if pvk.is_well_formed() && ic_is_valid {
+            Ok(pvk)
+        } else {
+            Err(SynthesisError::MalformedVerifyingKey)
+        }
+    }
+
+    /// Checks everything `from_parts` does except the IC points.
+    fn is_well_formed(&self) -> bool {
+        let infinity = E::G2Affine::zero().prepare();
+
+        self.alpha_g1_beta_g2.is_well_formed() &&
+        !self.alpha_g1_beta_g2.is_zero() &&
+        self.alpha_g1_beta_g2 != E::Fqk::one() &&
+        self.neg_gamma_g2.is_well_formed() &&
+        self.neg_gamma_g2 != infinity &&
+        self.neg_delta_g2.is_well_formed() &&
+        self.neg_delta_g2 != infinity &&
+        !self.ic.is_empty()
+    }
+
+    /// Writes the prepared verifying key in a stable format, unlike the
+    /// derived `Encode` which follows the in-memory representation:
+    ///
+    /// * `alpha_g1_beta_g2`, then `neg_gamma_g2` and `neg_delta_g2`, each in
+    ///   its `CanonicalEncoding`,
+    /// * the number of IC elements as a big-endian `u32`, followed by each
+    ///   element in its uncompressed encoding.
+    pub fn write<W: Write>(
+        &self,
+        mut writer: W
+    ) -> Result<(), IoError>
+    {
+        self.alpha_g1_beta_g2.write_canonical(&mut writer)?;
+        self.neg_gamma_g2.write_canonical(&mut writer)?;
+        self.neg_delta_g2.write_canonical(&mut writer)?;
+        write_points(&mut writer, &self.ic)?;
+
+        Ok(())
+    }
+
+    /// Reads a prepared verifying key written by `write`, with the same
+    /// checks as `from_parts`.
+    pub fn read<R: Read>(
+        mut reader: R
+    ) -> Result<Self, IoError>
+    {
+        let alpha_g1_beta_g2 = E::Fqk::read_canonical(&mut reader)?;
+        let neg_gamma_g2 = <E::G2Affine as CurveAffine>::Prepared::read_canonical(&mut reader)?;
+        let neg_delta_g2 = <E::G2Affine as CurveAffine>::Prepared::read_canonical(&mut reader)?;
+        let ic = read_points::<E::G1Affine, _>(&mut reader, true)?;
+
+        let pvk = PreparedVerifyingKey {
+            alpha_g1_beta_g2: alpha_g1_beta_g2,
+            neg_gamma_g2: neg_gamma_g2,
+            neg_delta_g2: neg_delta_g2,
+            ic: ic
+        };
+
+        if pvk.is_well_formed() {
+            Ok(pvk)
+        } else {
+            Err(IoError::Error)
+        }
+    }
+}
+
 pub trait ParameterSource<E: Engine> {
     type G1Builder: SourceBuilder<E::G1Affine>;
     type G2Builder: SourceBuilder<E::G2Affine>;
@@ -475,6 +520,24 @@ pub trait ParameterSource<E: Engine> {
         num_inputs: usize,
         num_aux: usize
     ) -> Result<(Self::G2Builder, Self::G2Builder), SynthesisError>;
+
+    /// Returns the size of the evaluation domain of the parameters, for a
+    /// circuit with `num_constraints` constraints (including those on
+    /// the inputs). Defaults to the radix-2 domain of that size.
+    fn get_domain_size(
+        &mut self,
+        num_constraints: usize
+    ) -> Result<usize, SynthesisError>
+    {
+        DomainKind::Radix2.size::<E>(num_constraints)
+    }
+
+    /// Returns how the multiexps of the prover accumulate their buckets.
+    /// Defaults to `BucketAccumulation::Projective`; sources of
+    /// precomputed bases always use projective buckets.
+    fn get_accumulation(&mut self) -> BucketAccumulation {
+        BucketAccumulation::default()
+    }
 }
 
 impl<'a, E: Engine> ParameterSource<E> for &'a Parameters<E> {
@@ -531,6 +594,15 @@ impl<'a, E: Engine> ParameterSource<E> for &'a Parameters<E> {
     {
         Ok(((self.b_g2.clone(), 0), (self.b_g2.clone(), num_inputs)))
     }
+
+    fn get_domain_size(
+        &mut self,
+        _: usize
+    ) -> Result<usize, SynthesisError>
+    {
+        // The H query has one element less than the domain.
+        Ok(self.h.len() + 1)
+    }
 }
 
 #[cfg(test)]
@@ -538,44 +610,13 @@ mod test_with_bls12_381 {
     use super::*;
     use {Circuit, SynthesisError, ConstraintSystem};
 
-    use rand::{Rand, thread_rng};
+    use rand::{Rand, Rng, thread_rng};
     use pairing::{Field};
     use pairing::bls12_381::{Bls12, Fr};
+    use super::tests::{Cube, MySillyCircuit};
 
     #[test]
     fn serialization() {
-        struct MySillyCircuit<E: Engine> {
-            a: Option<E::Fr>,
-            b: Option<E::Fr>
-        }
-
-        impl<E: Engine> Circuit<E> for MySillyCircuit<E> {
-            fn synthesize<CS: ConstraintSystem<E>>(
-                self,
-                cs: &mut CS
-            ) -> Result<(), SynthesisError>
-            {
-                let a = cs.alloc(|| "a", || self.a.ok_or(SynthesisError::AssignmentMissing))?;
-                let b = cs.alloc(|| "b", || self.b.ok_or(SynthesisError::AssignmentMissing))?;
-                let c = cs.alloc_input(|| "c", || {
-                    let mut a = self.a.ok_or(SynthesisError::AssignmentMissing)?;
-                    let b = self.b.ok_or(SynthesisError::AssignmentMissing)?;
-
-                    a.mul_assign(&b);
-                    Ok(a)
-                })?;
-
-                cs.enforce(
-                    || "a*b=c",
-                    |lc| lc + a,
-                    |lc| lc + b,
-                    |lc| lc + c
-                );
-
-                Ok(())
-            }
-        }
-
         let rng = &mut thread_rng();
 
         let params = generate_random_parameters::<Bls12, _, _>(
@@ -595,6 +636,46 @@ mod test_with_bls12_381 {
 
         let pvk = prepare_verifying_key::<Bls12>(&params.vk);
 
+        {
+            let mut v = vec![];
+
+            pvk.write(&mut v).unwrap();
+            // alpha * beta, two prepared points with 68 line coefficients
+            // each, and two IC elements.
+            assert_eq!(v.len(), 576 + 2 * (1 + 68 * 288) + 4 + 2 * 96);
+
+            let de_pvk = PreparedVerifyingKey::<Bls12>::read(&v[..]).unwrap();
+            assert!(pvk == de_pvk);
+            assert!(PreparedVerifyingKey::<Bls12>::read(&v[..v.len() - 1]).is_err());
+
+            // Elements of Fq are checked to be in the field.
+            let mut bad = v.clone();
+            bad[0] = 0xff;
+            assert!(PreparedVerifyingKey::<Bls12>::read(&bad[..]).is_err());
+
+            let parts = || (
+                pvk.alpha_g1_beta_g2,
+                pvk.neg_gamma_g2.clone(),
+                pvk.neg_delta_g2.clone(),
+                pvk.ic.clone()
+            );
+
+            let (alpha_g1_beta_g2, neg_gamma_g2, neg_delta_g2, ic) = parts();
+            let from_parts = PreparedVerifyingKey::<Bls12>::from_parts(alpha_g1_beta_g2, neg_gamma_g2, neg_delta_g2, ic).unwrap();
+            assert!(pvk == from_parts);
+
+            let (_, neg_gamma_g2, neg_delta_g2, ic) = parts();
+            assert!(PreparedVerifyingKey::<Bls12>::from_parts(Field::one(), neg_gamma_g2, neg_delta_g2, ic).is_err());
+
+            let (alpha_g1_beta_g2, _, neg_delta_g2, ic) = parts();
+            let infinity = <Bls12 as Engine>::G2Affine::zero().prepare();
+            assert!(PreparedVerifyingKey::<Bls12>::from_parts(alpha_g1_beta_g2, infinity, neg_delta_g2, ic).is_err());
+
+            let (alpha_g1_beta_g2, neg_gamma_g2, neg_delta_g2, mut ic) = parts();
+            ic[1] = <Bls12 as Engine>::G1Affine::zero();
+            assert!(PreparedVerifyingKey::<Bls12>::from_parts(alpha_g1_beta_g2, neg_gamma_g2, neg_delta_g2, ic).is_err());
+        }
+
         // for _ in 0..100 {
             let a = Fr::rand(rng);
             let b = Fr::rand(rng);
@@ -622,4 +703,461 @@ mod test_with_bls12_381 {
             assert!(!verify_proof(&pvk, &proof, &[a]).unwrap());
         // }
     }
+
+    #[test]
+    fn serialization_streaming() {
+        use pairing::utils::{IoReader, IoWriter};
+        use std::io::Cursor;
+
+        // Exposes every witness as a public input so the verifying key
+        // has a variable-length IC query.
+        struct ManyInputs<E: Engine> {
+            inputs: Vec<Option<E::Fr>>
+        }
+
+        impl<E: Engine> Circuit<E> for ManyInputs<E> {
+            fn synthesize<CS: ConstraintSystem<E>>(
+                self,
+                cs: &mut CS
+            ) -> Result<(), SynthesisError>
+            {
+                for (i, value) in self.inputs.into_iter().enumerate() {
+                    let a = cs.alloc(|| format!("a {}", i), || value.ok_or(SynthesisError::AssignmentMissing))?;
+                    let b = cs.alloc_input(|| format!("b {}", i), || value.ok_or(SynthesisError::AssignmentMissing))?;
+
+                    cs.enforce(
+                        || format!("a = b {}", i),
+                        |lc| lc + a,
+                        |lc| lc + CS::one(),
+                        |lc| lc + b
+                    );
+                }
+
+                Ok(())
+            }
+        }
+
+        let rng = &mut thread_rng();
+
+        for num_inputs in 0..4 {
+            let params = generate_random_parameters::<Bls12, _, _>(
+                ManyInputs { inputs: vec![None; num_inputs] },
+                rng
+            ).unwrap();
+            assert_eq!(params.vk.ic.len(), num_inputs + 1);
+
+            let mut v = vec![];
+            params.write(&mut v).unwrap();
+
+            let de_params = Parameters::<Bls12>::read(&v[..], true).unwrap();
+            assert!(params == de_params);
+
+            let de_params = Parameters::<Bls12>::read(&v[..], false).unwrap();
+            assert!(params == de_params);
+
+            let de_params = Parameters::<Bls12>::read(IoReader(Cursor::new(&v)), true).unwrap();
+            assert!(params == de_params);
+
+            let mut w = IoWriter(Cursor::new(vec![]));
+            params.write(&mut w).unwrap();
+            assert_eq!((w.0).into_inner(), v);
+
+            // Truncated parameters are an error rather than a panic.
+            for len in &[0, 4, 96, v.len() / 2, v.len() - 1] {
+                assert!(Parameters::<Bls12>::read(&v[..*len], true).is_err());
+            }
+        }
+    }
+
+    #[test]
+    fn rerandomization() {
+        let rng = &mut thread_rng();
+
+        let params = generate_random_parameters::<Bls12, _, _>(Cube { x: None }, rng).unwrap();
+        let pvk = prepare_verifying_key(&params.vk);
+
+        let x = Fr::rand(rng);
+        let mut y = x;
+        y.square();
+        y.mul_assign(&x);
+
+        let proof = create_random_proof(Cube { x: Some(x) }, &params, rng).unwrap();
+        assert!(verify_proof(&pvk, &proof, &[y]).unwrap());
+
+        let rerandomized = rerandomize_proof(&params.vk, &proof, rng).unwrap();
+        assert!(verify_proof(&pvk, &rerandomized, &[y]).unwrap());
+        assert!(!verify_proof(&pvk, &rerandomized, &[x]).unwrap());
+        assert!(rerandomized.a != proof.a);
+        assert!(rerandomized.b != proof.b);
+        assert!(rerandomized.c != proof.c);
+
+        // Rerandomized proofs can be rerandomized again.
+        let again = rerandomize_proof(&params.vk, &rerandomized, rng).unwrap();
+        assert!(verify_proof(&pvk, &again, &[y]).unwrap());
+        assert!(again != rerandomized);
+        assert!(again != proof);
+
+        let mut vk = params.vk.clone();
+        vk.delta_g2 = <Bls12 as Engine>::G2Affine::zero();
+        assert!(rerandomize_proof(&vk, &proof, rng).is_err());
+    }
+
+    #[test]
+    fn shared_worker() {
+        use multicore::Worker;
+
+        let rng = &mut thread_rng();
+        let worker = Worker::builder().num_threads(2).build();
+
+        let g1 = rng.gen();
+        let g2 = rng.gen();
+        let toxic = (0..5).map(|_| Fr::rand(rng)).collect::<Vec<_>>();
+
+        let params = generate_parameters_with_worker::<Bls12, _>(
+            Cube { x: None }, g1, g2, toxic[0], toxic[1], toxic[2], toxic[3], toxic[4], &worker
+        ).unwrap();
+        let expected = generate_parameters::<Bls12, _>(
+            Cube { x: None }, g1, g2, toxic[0], toxic[1], toxic[2], toxic[3], toxic[4]
+        ).unwrap();
+        assert!(params == expected);
+
+        let pvk = prepare_verifying_key(&params.vk);
+
+        // The same worker is reused across proofs.
+        for _ in 0..2 {
+            let x = Fr::rand(rng);
+            let mut y = x;
+            y.square();
+            y.mul_assign(&x);
+
+            let r = Fr::rand(rng);
+            let s = Fr::rand(rng);
+
+            let proof = create_proof_with_worker(Cube { x: Some(x) }, &params, r, s, &worker).unwrap();
+            assert!(proof == create_proof(Cube { x: Some(x) }, &params, r, s).unwrap());
+            assert!(verify_proof(&pvk, &proof, &[y]).unwrap());
+        }
+    }
+
+    #[test]
+    fn batch_affine_accumulation() {
+        use multiexp::BucketAccumulation;
+
+        // Proves with the parameters of `.0`, accumulating the buckets of
+        // the multiexps in affine coordinates.
+        struct BatchAffine<'a>(&'a Parameters<Bls12>);
+
+        impl<'a> ParameterSource<Bls12> for BatchAffine<'a> {
+            type G1Builder = <&'a Parameters<Bls12> as ParameterSource<Bls12>>::G1Builder;
+            type G2Builder = <&'a Parameters<Bls12> as ParameterSource<Bls12>>::G2Builder;
+
+            fn get_vk(&mut self, num_ic: usize) -> Result<VerifyingKey<Bls12>, SynthesisError> {
+                self.0.get_vk(num_ic)
+            }
+
+            fn get_h(&mut self, num_h: usize) -> Result<Self::G1Builder, SynthesisError> {
+                self.0.get_h(num_h)
+            }
+
+            fn get_l(&mut self, num_l: usize) -> Result<Self::G1Builder, SynthesisError> {
+                self.0.get_l(num_l)
+            }
+
+            fn get_a(&mut self, num_inputs: usize, num_aux: usize) -> Result<(Self::G1Builder, Self::G1Builder), SynthesisError> {
+                self.0.get_a(num_inputs, num_aux)
+            }
+
+            fn get_b_g1(&mut self, num_inputs: usize, num_aux: usize) -> Result<(Self::G1Builder, Self::G1Builder), SynthesisError> {
+                self.0.get_b_g1(num_inputs, num_aux)
+            }
+
+            fn get_b_g2(&mut self, num_inputs: usize, num_aux: usize) -> Result<(Self::G2Builder, Self::G2Builder), SynthesisError> {
+                self.0.get_b_g2(num_inputs, num_aux)
+            }
+
+            fn get_domain_size(&mut self, num_constraints: usize) -> Result<usize, SynthesisError> {
+                self.0.get_domain_size(num_constraints)
+            }
+
+            fn get_accumulation(&mut self) -> BucketAccumulation {
+                BucketAccumulation::BatchAffine
+            }
+        }
+
+        let rng = &mut thread_rng();
+
+        let params = generate_random_parameters::<Bls12, _, _>(Cube { x: None }, rng).unwrap();
+        let pvk = prepare_verifying_key(&params.vk);
+
+        let x = Fr::rand(rng);
+        let mut y = x;
+        y.square();
+        y.mul_assign(&x);
+
+        let r = Fr::rand(rng);
+        let s = Fr::rand(rng);
+        let proof = create_proof(Cube { x: Some(x) }, BatchAffine(&params), r, s).unwrap();
+        assert!(proof == create_proof(Cube { x: Some(x) }, &params, r, s).unwrap());
+        assert!(verify_proof(&pvk, &proof, &[y]).unwrap());
+    }
+
+    #[test]
+    fn mixed_radix_domain() {
+        use domain::DomainKind;
+        use multicore::Worker;
+
+        // x^(2^squarings) = y
+        struct Squarings<E: Engine> {
+            x: Option<E::Fr>,
+            squarings: usize
+        }
+
+        impl<E: Engine> Circuit<E> for Squarings<E> {
+            fn synthesize<CS: ConstraintSystem<E>>(
+                self,
+                cs: &mut CS
+            ) -> Result<(), SynthesisError>
+            {
+                let mut x_val = self.x;
+                let mut x = cs.alloc(|| "x", || x_val.ok_or(SynthesisError::AssignmentMissing))?;
+
+                for i in 0..self.squarings {
+                    let y_val = x_val.map(|mut x| { x.square(); x });
+                    let y = if i == self.squarings - 1 {
+                        cs.alloc_input(|| "y", || y_val.ok_or(SynthesisError::AssignmentMissing))?
+                    } else {
+                        cs.alloc(|| format!("y {}", i), || y_val.ok_or(SynthesisError::AssignmentMissing))?
+                    };
+
+                    cs.enforce(|| format!("square {}", i), |lc| lc + x, |lc| lc + x, |lc| lc + y);
+                    x = y;
+                    x_val = y_val;
+                }
+
+                Ok(())
+            }
+        }
+
+        let rng = &mut thread_rng();
+        let worker = Worker::new();
+
+        // Two more constraints bind the public inputs.
+        for &(squarings, size) in &[(3, 6), (22, 24)] {
+            let g1 = rng.gen();
+            let g2 = rng.gen();
+            let toxic = (0..5).map(|_| Fr::rand(rng)).collect::<Vec<_>>();
+
+            let params = generate_parameters_with_domain::<Bls12, _>(
+                Squarings { x: None, squarings: squarings },
+                g1, g2, toxic[0], toxic[1], toxic[2], toxic[3], toxic[4],
+                &worker,
+                DomainKind::MixedRadix
+            ).unwrap();
+            assert_eq!(params.h.len() + 1, size);
+
+            let pvk = prepare_verifying_key(&params.vk);
+
+            let x = Fr::rand(rng);
+            let mut y = x;
+            for _ in 0..squarings {
+                y.square();
+            }
+
+            let proof = create_proof(
+                Squarings { x: Some(x), squarings: squarings },
+                &params,
+                Fr::rand(rng),
+                Fr::rand(rng)
+            ).unwrap();
+            assert!(verify_proof(&pvk, &proof, &[y]).unwrap());
+            assert!(!verify_proof(&pvk, &proof, &[x]).unwrap());
+        }
+    }
+
+    #[test]
+    fn low_memory_prover() {
+        use std::fs;
+        use multicore::Worker;
+
+        let rng = &mut thread_rng();
+        let worker = Worker::new();
+
+        let params = generate_random_parameters::<Bls12, _, _>(Cube { x: None }, rng).unwrap();
+        let pvk = prepare_verifying_key(&params.vk);
+
+        let x = Fr::rand(rng);
+        let mut y = x;
+        y.square();
+        y.mul_assign(&x);
+
+        let r = Fr::rand(rng);
+        let s = Fr::rand(rng);
+        let expected = create_proof(Cube { x: Some(x) }, &params, r, s).unwrap();
+
+        let mut store = MemoryStore::new();
+        let proof = create_proof_low_memory(Cube { x: Some(x) }, &params, r, s, &worker, &mut store).unwrap();
+        assert!(proof == expected);
+        assert!(verify_proof(&pvk, &proof, &[y]).unwrap());
+
+        let dir = ::std::env::temp_dir().join(format!("bellman-store-{}", Fr::rand(rng)));
+        fs::create_dir(&dir).unwrap();
+        {
+            let mut store = FileStore::new(&dir);
+
+            // The store can be reused across proofs.
+            for _ in 0..2 {
+                let proof = create_proof_low_memory(Cube { x: Some(x) }, &params, r, s, &worker, &mut store).unwrap();
+                assert!(proof == expected);
+            }
+            assert_eq!(fs::read_dir(&dir).unwrap().count(), 5);
+        }
+        assert_eq!(fs::read_dir(&dir).unwrap().count(), 0);
+        fs::remove_dir(&dir).unwrap();
+
+        // Leftovers of a failed proof are discarded.
+        store.append(StoreSlot::A, &[x]).unwrap();
+        assert!(create_proof_low_memory(Cube { x: None }, &params, r, s, &worker, &mut store).is_err());
+        let proof = create_proof_low_memory(Cube { x: Some(x) }, &params, r, s, &worker, &mut store).unwrap();
+        assert!(proof == expected);
+    }
+
+    #[test]
+    fn observer_and_cancellation() {
+        use std::sync::{Arc, Mutex};
+        use multicore::Worker;
+
+        #[derive(Default)]
+        struct Recorder {
+            phases: Mutex<Vec<(ProverPhase, bool)>>,
+            progress: Mutex<Vec<(usize, usize)>>
+        }
+
+        impl ProverObserver for Recorder {
+            fn phase_started(&self, phase: ProverPhase) {
+                self.phases.lock().unwrap().push((phase, true));
+            }
+
+            fn phase_finished(&self, phase: ProverPhase) {
+                self.phases.lock().unwrap().push((phase, false));
+            }
+
+            fn multiexp_chunk_completed(&self, completed: usize, total: usize) {
+                self.progress.lock().unwrap().push((completed, total));
+            }
+        }
+
+        // Cancels the proof once the first chunk is done.
+        struct Canceller(CancellationToken);
+
+        impl ProverObserver for Canceller {
+            fn multiexp_chunk_completed(&self, _: usize, _: usize) {
+                self.0.cancel();
+            }
+        }
+
+        let rng = &mut thread_rng();
+        let worker = Worker::new();
+
+        let params = generate_random_parameters::<Bls12, _, _>(Cube { x: None }, rng).unwrap();
+        let pvk = prepare_verifying_key(&params.vk);
+
+        let x = Fr::rand(rng);
+        let mut y = x;
+        y.square();
+        y.mul_assign(&x);
+
+        let recorder = Arc::new(Recorder::default());
+        let proof = create_proof_with_observer(
+            Cube { x: Some(x) },
+            &params,
+            Fr::rand(rng),
+            Fr::rand(rng),
+            &worker,
+            recorder.clone(),
+            &CancellationToken::new()
+        ).unwrap();
+        assert!(verify_proof(&pvk, &proof, &[y]).unwrap());
+
+        assert_eq!(*recorder.phases.lock().unwrap(), vec![
+            (ProverPhase::Synthesis, true),
+            (ProverPhase::Synthesis, false),
+            (ProverPhase::Fft, true),
+            (ProverPhase::Fft, false),
+            (ProverPhase::Multiexp, true),
+            (ProverPhase::Multiexp, false)
+        ]);
+
+        let progress = recorder.progress.lock().unwrap();
+        let total = progress[0].1;
+        assert_eq!(progress.len(), total);
+        assert!(progress.iter().all(|&(_, t)| t == total));
+        let mut completed = progress.iter().map(|&(c, _)| c).collect::<Vec<_>>();
+        completed.sort();
+        assert_eq!(completed, (1..total + 1).collect::<Vec<_>>());
+
+        let cancel = CancellationToken::new();
+        let result = create_proof_with_observer(
+            Cube { x: Some(x) },
+            &params,
+            Fr::rand(rng),
+            Fr::rand(rng),
+            &worker,
+            Arc::new(Canceller(cancel.clone())),
+            &cancel
+        );
+        assert!(cancel.is_cancelled());
+        match result {
+            Err(SynthesisError::Cancelled) => {},
+            _ => panic!("the proof should have been cancelled")
+        }
+
+        // A token cancelled up front stops the prover before synthesis.
+        let recorder = Arc::new(Recorder::default());
+        let result = create_proof_with_observer(
+            Cube { x: Some(x) },
+            &params,
+            Fr::rand(rng),
+            Fr::rand(rng),
+            &worker,
+            recorder.clone(),
+            &cancel
+        );
+        match result {
+            Err(SynthesisError::Cancelled) => {},
+            _ => panic!("the proof should have been cancelled")
+        }
+        assert!(recorder.phases.lock().unwrap().is_empty());
+    }
+}
+
+#[cfg(test)]
+mod test_with_bn256 {
+    use super::*;
+
+    use rand::{Rand, thread_rng};
+    use pairing::Field;
+    use pairing::bn256::{Bn256, Fr};
+    use super::tests::Cube;
+
+    #[test]
+    fn bn256() {
+        let rng = &mut thread_rng();
+
+        let params = generate_random_parameters::<Bn256, _, _>(Cube { x: None }, rng).unwrap();
+        let pvk = prepare_verifying_key(&params.vk);
+
+        let x = Fr::rand(rng);
+        let mut y = x;
+        y.square();
+        y.mul_assign(&x);
+
+        let proof = create_random_proof(Cube { x: Some(x) }, &params, rng).unwrap();
+        assert!(verify_proof(&pvk, &proof, &[y]).unwrap());
+        assert!(!verify_proof(&pvk, &proof, &[x]).unwrap());
+
+        let mut v = vec![];
+        params.write(&mut v).unwrap();
+        let de_params = Parameters::<Bn256>::read(&v[..], true).unwrap();
+        assert!(params == de_params);
+    }
 }
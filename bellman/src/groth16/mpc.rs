@@ -0,0 +1,1001 @@
+//! Multi-party computation of Groth16 parameters.
+//!
+//! `generate_parameters` needs every trapdoor in the clear. Instead, the
+//! circuit-independent part of the setup can come from a powers-of-tau
+//! ceremony (`Phase1Parameters`), leaving only `delta` to be sampled for
+//! the circuit itself. `MPCParameters` starts from `delta = 1` and lets any
+//! number of participants multiply in a secret `delta` of their own. Each
+//! participant publishes a `PublicKey` proving knowledge of their `delta`,
+//! and the resulting parameters are secure as long as one participant
+//! destroyed theirs.
+//!
+//! Anyone can check a transcript offline with `verify_contribution`, one
+//! contribution at a time, or `MPCParameters::verify`, for the whole
+//! transcript from the circuit and the phase 1 parameters.
+
+use rand::{Rng, SeedableRng, thread_rng};
+use rand::chacha::ChaChaRng;
+
+use std::error::Error;
+use std::fmt;
+use std::sync::{Arc, Mutex};
+
+use byteorder::{BigEndian, ByteOrder};
+
+use blake2_rfc::blake2b::Blake2b;
+
+use pairing::{
+    Engine,
+    PrimeField,
+    Field,
+    Wnaf,
+    CurveProjective,
+    CurveAffine,
+    EncodedPoint,
+    IoError
+};
+
+use super::{
+    Parameters,
+    VerifyingKey,
+    Read,
+    Write,
+    read_vec
+};
+
+use super::generator::KeypairAssembly;
+use super::powersoftau::Accumulator;
+
+use ::{
+    SynthesisError,
+    Circuit
+};
+
+use ::domain::{
+    EvaluationDomain,
+    Scalar
+};
+
+use ::multicore::{
+    Worker
+};
+
+/// The output of a powers-of-tau ceremony for a radix-2 evaluation domain
+/// of size `m`, with the powers of tau converted to the Lagrange basis.
+#[derive(Clone)]
+pub struct Phase1Parameters<E: Engine> {
+    pub alpha_g1: E::G1Affine,
+    pub beta_g1: E::G1Affine,
+    pub beta_g2: E::G2Affine,
+
+    // Lagrange coefficients L_i(tau) for i between 0 and m-1 inclusive.
+    pub coeffs_g1: Vec<E::G1Affine>,
+    pub coeffs_g2: Vec<E::G2Affine>,
+
+    // alpha * L_i(tau) and beta * L_i(tau), in G1.
+    pub alpha_coeffs_g1: Vec<E::G1Affine>,
+    pub beta_coeffs_g1: Vec<E::G1Affine>,
+
+    // tau^i * t(tau) for i between 0 and m-2 inclusive.
+    pub h: Vec<E::G1Affine>
+}
+
+/// An error while creating or verifying MPC parameters.
+#[derive(Debug)]
+pub enum MPCError {
+    /// Synthesizing the circuit failed.
+    Synthesis(SynthesisError),
+    /// The phase 1 parameters are for a different domain size than the
+    /// circuit needs.
+    Phase1SizeMismatch,
+    /// The parameters were not derived from this circuit and phase 1
+    /// parameters.
+    ParametersMismatch,
+    /// A contribution does not commit to the transcript before it.
+    InvalidTranscript,
+    /// A proof of knowledge or a delta update failed a pairing check.
+    InvalidContribution
+}
+
+impl From<SynthesisError> for MPCError {
+    fn from(e: SynthesisError) -> MPCError {
+        MPCError::Synthesis(e)
+    }
+}
+
+impl MPCError {
+    #[inline]
+    fn description_str(&self) -> &'static str {
+        match *self {
+            MPCError::Synthesis(ref e) => e.description_str(),
+            MPCError::Phase1SizeMismatch => "phase 1 parameters have the wrong domain size",
+            MPCError::ParametersMismatch => "parameters do not match the circuit",
+            MPCError::InvalidTranscript => "contribution does not match the transcript",
+            MPCError::InvalidContribution => "contribution is invalid"
+        }
+    }
+}
+
+impl Error for MPCError {
+    fn description(&self) -> &str {
+        self.description_str()
+    }
+}
+
+impl fmt::Display for MPCError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> Result<(), fmt::Error> {
+        if let MPCError::Synthesis(ref e) = *self {
+            write!(f, "synthesis error: ")?;
+            e.fmt(f)
+        } else {
+            write!(f, "{}", self.description_str())
+        }
+    }
+}
+
+/// Groth16 parameters together with the transcript of every contribution
+/// made to them so far.
+#[derive(Clone)]
+pub struct MPCParameters<E: Engine> {
+    params: Parameters<E>,
+
+    // Hash of the parameters before any contribution, which pins down
+    // the circuit and the phase 1 parameters.
+    cs_hash: [u8; 64],
+
+    contributions: Vec<PublicKey<E>>
+}
+
+impl<E: Engine> PartialEq for MPCParameters<E> {
+    fn eq(&self, other: &Self) -> bool {
+        self.params == other.params &&
+        self.cs_hash[..] == other.cs_hash[..] &&
+        self.contributions == other.contributions
+    }
+}
+
+impl<E: Engine> MPCParameters<E> {
+    /// Creates the initial parameters for `circuit`, with `delta = 1`, from
+    /// phase 1 parameters of exactly the circuit's domain size.
+    pub fn new<C: Circuit<E>>(
+        circuit: C,
+        phase1: &Phase1Parameters<E>
+    ) -> Result<Self, MPCError>
+    {
+        let assembly = KeypairAssembly::synthesize(circuit)?;
+
+        Self::from_assembly(assembly, phase1)
+    }
+
+    /// Creates the initial parameters for `circuit`, with `delta = 1`,
+    /// converting a powers-of-tau accumulator to the Lagrange basis of the
+    /// circuit's domain.
+    pub fn from_accumulator<C: Circuit<E>>(
+        circuit: C,
+        accumulator: &Accumulator<E>
+    ) -> Result<Self, MPCError>
+    {
+        let assembly = KeypairAssembly::synthesize(circuit)?;
+        let phase1 = accumulator.lagrange(assembly.num_constraints)?;
+
+        Self::from_assembly(assembly, &phase1)
+    }
+
+    fn from_assembly(
+        assembly: KeypairAssembly<E>,
+        phase1: &Phase1Parameters<E>
+    ) -> Result<Self, MPCError>
+    {
+        // The prover uses a domain of this size, so the Lagrange basis
+        // must be over the same domain.
+        let m = EvaluationDomain::<E, Scalar<E>>::from_coeffs(
+            vec![Scalar::<E>(E::Fr::zero()); assembly.num_constraints]
+        )?.as_ref().len();
+
+        if phase1.coeffs_g1.len() != m ||
+           phase1.coeffs_g2.len() != m ||
+           phase1.alpha_coeffs_g1.len() != m ||
+           phase1.beta_coeffs_g1.len() != m ||
+           phase1.h.len() != m - 1
+        {
+            return Err(MPCError::Phase1SizeMismatch);
+        }
+
+        let mut a = vec![E::G1::zero(); assembly.num_inputs + assembly.num_aux];
+        let mut b_g1 = vec![E::G1::zero(); assembly.num_inputs + assembly.num_aux];
+        let mut b_g2 = vec![E::G2::zero(); assembly.num_inputs + assembly.num_aux];
+        let mut ic = vec![E::G1::zero(); assembly.num_inputs];
+        let mut l = vec![E::G1::zero(); assembly.num_aux];
+
+        fn eval<E: Engine>(
+            // Phase 1 parameters in the Lagrange basis
+            phase1: &Phase1Parameters<E>,
+
+            // QAP polynomials
+            at: &[Vec<(E::Fr, usize)>],
+            bt: &[Vec<(E::Fr, usize)>],
+            ct: &[Vec<(E::Fr, usize)>],
+
+            // Resulting evaluated QAP polynomials
+            a: &mut [E::G1],
+            b_g1: &mut [E::G1],
+            b_g2: &mut [E::G2],
+            ext: &mut [E::G1],
+
+            // Worker
+            worker: &Worker
+        )
+        {
+            // Sanity check
+            assert_eq!(a.len(), at.len());
+            assert_eq!(a.len(), bt.len());
+            assert_eq!(a.len(), ct.len());
+            assert_eq!(a.len(), b_g1.len());
+            assert_eq!(a.len(), b_g2.len());
+            assert_eq!(a.len(), ext.len());
+
+            // Evaluate polynomials in multiple threads
+            worker.scope(a.len(), |scope, chunk| {
+                for ((((((a, b_g1), b_g2), ext), at), bt), ct) in a.chunks_mut(chunk)
+                                                                   .zip(b_g1.chunks_mut(chunk))
+                                                                   .zip(b_g2.chunks_mut(chunk))
+                                                                   .zip(ext.chunks_mut(chunk))
+                                                                   .zip(at.chunks(chunk))
+                                                                   .zip(bt.chunks(chunk))
+                                                                   .zip(ct.chunks(chunk))
+                {
+                    scope.spawn(move || {
+                        for ((((((a, b_g1), b_g2), ext), at), bt), ct) in a.iter_mut()
+                                                                           .zip(b_g1.iter_mut())
+                                                                           .zip(b_g2.iter_mut())
+                                                                           .zip(ext.iter_mut())
+                                                                           .zip(at.iter())
+                                                                           .zip(bt.iter())
+                                                                           .zip(ct.iter())
+                        {
+                            for &(coeff, lag) in at {
+                                a.add_assign(&phase1.coeffs_g1[lag].mul(coeff));
+                                ext.add_assign(&phase1.beta_coeffs_g1[lag].mul(coeff));
+                            }
+
+                            for &(coeff, lag) in bt {
+                                b_g1.add_assign(&phase1.coeffs_g1[lag].mul(coeff));
+                                b_g2.add_assign(&phase1.coeffs_g2[lag].mul(coeff));
+                                ext.add_assign(&phase1.alpha_coeffs_g1[lag].mul(coeff));
+                            }
+
+                            for &(coeff, lag) in ct {
+                                ext.add_assign(&phase1.coeffs_g1[lag].mul(coeff));
+                            }
+                        }
+
+                        // Batch normalize
+                        E::G1::batch_normalization(a);
+                        E::G1::batch_normalization(b_g1);
+                        E::G2::batch_normalization(b_g2);
+                        E::G1::batch_normalization(ext);
+                    });
+                }
+            });
+        }
+
+        let worker = Worker::new();
+
+        // Evaluate for inputs.
+        eval(
+            phase1,
+            &assembly.at_inputs,
+            &assembly.bt_inputs,
+            &assembly.ct_inputs,
+            &mut a[0..assembly.num_inputs],
+            &mut b_g1[0..assembly.num_inputs],
+            &mut b_g2[0..assembly.num_inputs],
+            &mut ic,
+            &worker
+        );
+
+        // Evaluate for auxillary variables.
+        eval(
+            phase1,
+            &assembly.at_aux,
+            &assembly.bt_aux,
+            &assembly.ct_aux,
+            &mut a[assembly.num_inputs..],
+            &mut b_g1[assembly.num_inputs..],
+            &mut b_g2[assembly.num_inputs..],
+            &mut l,
+            &worker
+        );
+
+        // Don't allow any elements be unconstrained, so that
+        // the L query is always fully dense.
+        for e in l.iter() {
+            if e.is_zero() {
+                return Err(SynthesisError::UnconstrainedVariable.into());
+            }
+        }
+
+        // gamma and delta start out as one.
+        let vk = VerifyingKey::<E> {
+            alpha_g1: phase1.alpha_g1,
+            beta_g1: phase1.beta_g1,
+            beta_g2: phase1.beta_g2,
+            gamma_g2: E::G2Affine::one(),
+            delta_g1: E::G1Affine::one(),
+            delta_g2: E::G2Affine::one(),
+            ic: ic.into_iter().map(|e| e.into_affine()).collect()
+        };
+
+        let params = Parameters {
+            vk: vk,
+            h: Arc::new(phase1.h.clone()),
+            l: Arc::new(l.into_iter().map(|e| e.into_affine()).collect()),
+
+            // Filter points at infinity away from A/B queries
+            a: Arc::new(a.into_iter().filter(|e| !e.is_zero()).map(|e| e.into_affine()).collect()),
+            b_g1: Arc::new(b_g1.into_iter().filter(|e| !e.is_zero()).map(|e| e.into_affine()).collect()),
+            b_g2: Arc::new(b_g2.into_iter().filter(|e| !e.is_zero()).map(|e| e.into_affine()).collect())
+        };
+
+        let cs_hash = {
+            let mut sink = HashWriter::new();
+            params.write(&mut sink).expect("hashing never fails");
+
+            sink.into_hash()
+        };
+
+        Ok(MPCParameters {
+            params: params,
+            cs_hash: cs_hash,
+            contributions: vec![]
+        })
+    }
+
+    /// The parameters as they stand after the contributions so far.
+    pub fn get_params(&self) -> &Parameters<E> {
+        &self.params
+    }
+
+    /// The public keys of the contributions so far, in order.
+    pub fn contributions(&self) -> &[PublicKey<E>] {
+        &self.contributions
+    }
+
+    /// Contributes a fresh random `delta` to the parameters and returns the
+    /// hash of the contribution, which the participant should publish. The
+    /// randomness must be destroyed afterwards.
+    pub fn contribute<R: Rng>(
+        &mut self,
+        rng: &mut R
+    ) -> [u8; 64]
+    {
+        let delta = loop {
+            let delta: E::Fr = rng.gen();
+
+            if !delta.is_zero() {
+                break delta;
+            }
+        };
+        let delta_inverse = delta.inverse().expect("delta is nonzero");
+
+        // Prove knowledge of delta, bound to the transcript so far.
+        let s = loop {
+            let s = rng.gen::<E::G1>().into_affine();
+
+            if !s.is_zero() {
+                break s;
+            }
+        };
+        let s_delta = s.mul(delta).into_affine();
+        let transcript = transcript_hash(&self.cs_hash, &self.contributions, &s, &s_delta);
+        let r_delta = hash_to_g2::<E>(&transcript).into_affine().mul(delta).into_affine();
+
+        batch_exp::<E>(&mut Arc::make_mut(&mut self.params.h)[..], delta_inverse);
+        batch_exp::<E>(&mut Arc::make_mut(&mut self.params.l)[..], delta_inverse);
+
+        self.params.vk.delta_g1 = self.params.vk.delta_g1.mul(delta).into_affine();
+        self.params.vk.delta_g2 = self.params.vk.delta_g2.mul(delta).into_affine();
+
+        let pubkey = PublicKey {
+            delta_after: self.params.vk.delta_g1,
+            s: s,
+            s_delta: s_delta,
+            r_delta: r_delta,
+            transcript: transcript
+        };
+        let hash = pubkey.hash();
+
+        self.contributions.push(pubkey);
+
+        hash
+    }
+
+    /// Verifies the whole transcript against the circuit and the phase 1
+    /// parameters it was started from, returning the hash of every
+    /// contribution in order.
+    pub fn verify<C: Circuit<E>>(
+        &self,
+        circuit: C,
+        phase1: &Phase1Parameters<E>
+    ) -> Result<Vec<[u8; 64]>, MPCError>
+    {
+        let initial = MPCParameters::new(circuit, phase1)?;
+
+        if !same_circuit(&initial, self) ||
+           initial.params.h.len() != self.params.h.len() ||
+           initial.params.l.len() != self.params.l.len()
+        {
+            return Err(MPCError::ParametersMismatch);
+        }
+
+        let mut result = vec![];
+        let mut delta_g1 = initial.params.vk.delta_g1;
+
+        for (i, pubkey) in self.contributions.iter().enumerate() {
+            verify_public_key(&self.cs_hash, &self.contributions[0..i], pubkey, &delta_g1)?;
+
+            delta_g1 = pubkey.delta_after;
+            result.push(pubkey.hash());
+        }
+
+        if self.params.vk.delta_g1 != delta_g1 {
+            return Err(MPCError::InvalidContribution);
+        }
+
+        verify_delta(&initial.params, &self.params)?;
+
+        Ok(result)
+    }
+
+    pub fn write<W: Write>(
+        &self,
+        mut writer: W
+    ) -> Result<(), IoError>
+    {
+        self.params.write(&mut writer)?;
+        writer.write_all(&self.cs_hash)?;
+
+        writer.write_u32(self.contributions.len() as u32)?;
+        for pubkey in &self.contributions {
+            pubkey.write(&mut writer)?;
+        }
+
+        Ok(())
+    }
+
+    /// Reads parameters written by `write`. `checked` applies to the
+    /// parameters as in `Parameters::read`; the public keys are always
+    /// checked.
+    pub fn read<R: Read>(
+        mut reader: R,
+        checked: bool
+    ) -> Result<Self, IoError>
+    {
+        let params = Parameters::read(&mut reader, checked)?;
+
+        let mut cs_hash = [0u8; 64];
+        reader.read_exact(&mut cs_hash)?;
+
+        let len = reader.read_u32()? as usize;
+        let contributions = read_vec(len, || PublicKey::read(&mut reader))?;
+
+        Ok(MPCParameters {
+            params: params,
+            cs_hash: cs_hash,
+            contributions: contributions
+        })
+    }
+}
+
+/// Verifies that `after` is `before` with exactly one more valid
+/// contribution, and returns the hash of that contribution.
+pub fn verify_contribution<E: Engine>(
+    before: &MPCParameters<E>,
+    after: &MPCParameters<E>
+) -> Result<[u8; 64], MPCError>
+{
+    if !same_circuit(before, after) ||
+       before.params.h.len() != after.params.h.len() ||
+       before.params.l.len() != after.params.l.len() ||
+       after.contributions.len() != before.contributions.len() + 1 ||
+       after.contributions[0..before.contributions.len()] != before.contributions[..]
+    {
+        return Err(MPCError::ParametersMismatch);
+    }
+
+    let pubkey = after.contributions.last().expect("checked length above");
+
+    verify_public_key(&before.cs_hash, &before.contributions, pubkey, &before.params.vk.delta_g1)?;
+
+    if after.params.vk.delta_g1 != pubkey.delta_after {
+        return Err(MPCError::InvalidContribution);
+    }
+
+    verify_delta(&before.params, &after.params)?;
+
+    Ok(pubkey.hash())
+}
+
+/// A participant's proof that they know the `delta` they contributed.
+#[derive(Clone)]
+pub struct PublicKey<E: Engine> {
+    // delta_g1 after this contribution.
+    delta_after: E::G1Affine,
+
+    // A random element chosen by the contributor, and it multiplied by
+    // delta.
+    s: E::G1Affine,
+    s_delta: E::G1Affine,
+
+    // An element of G2 derived from the transcript, multiplied by delta.
+    r_delta: E::G2Affine,
+
+    // Hash of the transcript, which determines r.
+    transcript: [u8; 64]
+}
+
+impl<E: Engine> PartialEq for PublicKey<E> {
+    fn eq(&self, other: &Self) -> bool {
+        self.delta_after == other.delta_after &&
+        self.s == other.s &&
+        self.s_delta == other.s_delta &&
+        self.r_delta == other.r_delta &&
+        self.transcript[..] == other.transcript[..]
+    }
+}
+
+impl<E: Engine> PublicKey<E> {
+    pub fn write<W: Write>(
+        &self,
+        mut writer: W
+    ) -> Result<(), IoError>
+    {
+        writer.write_all(self.delta_after.into_uncompressed().as_ref())?;
+        writer.write_all(self.s.into_uncompressed().as_ref())?;
+        writer.write_all(self.s_delta.into_uncompressed().as_ref())?;
+        writer.write_all(self.r_delta.into_uncompressed().as_ref())?;
+        writer.write_all(&self.transcript)?;
+
+        Ok(())
+    }
+
+    pub fn read<R: Read>(
+        mut reader: R
+    ) -> Result<Self, IoError>
+    {
+        let mut g1_repr = <E::G1Affine as CurveAffine>::Uncompressed::empty();
+        let mut g2_repr = <E::G2Affine as CurveAffine>::Uncompressed::empty();
+
+        reader.read_exact(g1_repr.as_mut())?;
+        let delta_after = g1_repr.into_affine()?;
+
+        reader.read_exact(g1_repr.as_mut())?;
+        let s = g1_repr.into_affine()?;
+
+        reader.read_exact(g1_repr.as_mut())?;
+        let s_delta = g1_repr.into_affine()?;
+
+        reader.read_exact(g2_repr.as_mut())?;
+        let r_delta = g2_repr.into_affine()?;
+
+        if delta_after.is_zero() || s.is_zero() || s_delta.is_zero() || r_delta.is_zero() {
+            return Err(IoError::Infinity);
+        }
+
+        let mut transcript = [0u8; 64];
+        reader.read_exact(&mut transcript)?;
+
+        Ok(PublicKey {
+            delta_after: delta_after,
+            s: s,
+            s_delta: s_delta,
+            r_delta: r_delta,
+            transcript: transcript
+        })
+    }
+
+    /// The hash identifying this contribution.
+    pub fn hash(&self) -> [u8; 64] {
+        let mut sink = HashWriter::new();
+        self.write(&mut sink).expect("hashing never fails");
+
+        sink.into_hash()
+    }
+}
+
+/// Checks everything except delta, H and L, which contributions change.
+fn same_circuit<E: Engine>(
+    a: &MPCParameters<E>,
+    b: &MPCParameters<E>
+) -> bool
+{
+    a.cs_hash[..] == b.cs_hash[..] &&
+    a.params.vk.alpha_g1 == b.params.vk.alpha_g1 &&
+    a.params.vk.beta_g1 == b.params.vk.beta_g1 &&
+    a.params.vk.beta_g2 == b.params.vk.beta_g2 &&
+    a.params.vk.gamma_g2 == b.params.vk.gamma_g2 &&
+    a.params.vk.ic == b.params.vk.ic &&
+    a.params.a == b.params.a &&
+    a.params.b_g1 == b.params.b_g1 &&
+    a.params.b_g2 == b.params.b_g2
+}
+
+/// Checks a contribution's proof of knowledge of its delta, and that it
+/// was applied on top of `delta_before`.
+fn verify_public_key<E: Engine>(
+    cs_hash: &[u8; 64],
+    previous: &[PublicKey<E>],
+    pubkey: &PublicKey<E>,
+    delta_before: &E::G1Affine
+) -> Result<(), MPCError>
+{
+    let transcript = transcript_hash(cs_hash, previous, &pubkey.s, &pubkey.s_delta);
+
+    if transcript[..] != pubkey.transcript[..] {
+        return Err(MPCError::InvalidTranscript);
+    }
+
+    let r = hash_to_g2::<E>(&transcript).into_affine();
+
+    // s_delta = s * delta and r_delta = r * delta
+    if !same_ratio::<E::G1Affine>((pubkey.s, pubkey.s_delta), (r, pubkey.r_delta)) {
+        return Err(MPCError::InvalidContribution);
+    }
+
+    // delta_after = delta_before * delta
+    if !same_ratio::<E::G1Affine>((*delta_before, pubkey.delta_after), (r, pubkey.r_delta)) {
+        return Err(MPCError::InvalidContribution);
+    }
+
+    Ok(())
+}
+
+/// Checks that `after` has a consistent delta in G1 and G2, and that its
+/// H and L queries were divided by the change in delta since `before`.
+fn verify_delta<E: Engine>(
+    before: &Parameters<E>,
+    after: &Parameters<E>
+) -> Result<(), MPCError>
+{
+    if !same_ratio::<E::G1Affine>(
+        (E::G1Affine::one(), after.vk.delta_g1),
+        (E::G2Affine::one(), after.vk.delta_g2)
+    ) {
+        return Err(MPCError::InvalidContribution);
+    }
+
+    if !same_ratio::<E::G1Affine>(
+        merge_pairs::<E::G1Affine>(&before.h, &after.h),
+        (after.vk.delta_g2, before.vk.delta_g2)
+    ) {
+        return Err(MPCError::InvalidContribution);
+    }
+
+    if !same_ratio::<E::G1Affine>(
+        merge_pairs::<E::G1Affine>(&before.l, &after.l),
+        (after.vk.delta_g2, before.vk.delta_g2)
+    ) {
+        return Err(MPCError::InvalidContribution);
+    }
+
+    Ok(())
+}
+
+/// Checks that g1.1 / g1.0 = g2.1 / g2.0 in the exponent.
+pub(super) fn same_ratio<G: CurveAffine>(
+    g1: (G, G),
+    g2: (G::Pair, G::Pair)
+) -> bool
+{
+    g1.0.pairing_with(&g2.1) == g1.1.pairing_with(&g2.0)
+}
+
+/// Computes a random linear combination of each side of the pairs
+/// (v1[i], v2[i]), so that if every pair has the same ratio, so does
+/// the result, and otherwise the result almost certainly doesn't.
+pub(super) fn merge_pairs<G: CurveAffine>(
+    v1: &[G],
+    v2: &[G]
+) -> (G, G)
+{
+    assert_eq!(v1.len(), v2.len());
+
+    let s = Mutex::new(G::Projective::zero());
+    let sx = Mutex::new(G::Projective::zero());
+
+    let worker = Worker::new();
+
+    worker.scope(v1.len(), |scope, chunk| {
+        for (v1, v2) in v1.chunks(chunk).zip(v2.chunks(chunk)) {
+            let s = &s;
+            let sx = &sx;
+
+            scope.spawn(move || {
+                let rng = &mut thread_rng();
+
+                let mut wnaf = Wnaf::new();
+                let mut local_s = G::Projective::zero();
+                let mut local_sx = G::Projective::zero();
+
+                for (v1, v2) in v1.iter().zip(v2.iter()) {
+                    let rho = rng.gen::<G::Scalar>();
+                    let mut wnaf = wnaf.scalar(rho.into_repr());
+
+                    local_s.add_assign(&wnaf.base(v1.into_projective()));
+                    local_sx.add_assign(&wnaf.base(v2.into_projective()));
+                }
+
+                s.lock().unwrap().add_assign(&local_s);
+                sx.lock().unwrap().add_assign(&local_sx);
+            });
+        }
+    });
+
+    let s = s.into_inner().unwrap().into_affine();
+    let sx = sx.into_inner().unwrap().into_affine();
+
+    (s, sx)
+}
+
+/// Multiplies every element of `bases` by `coeff` in place.
+fn batch_exp<E: Engine>(
+    bases: &mut [E::G1Affine],
+    coeff: E::Fr
+)
+{
+    let coeff = coeff.into_repr();
+
+    let worker = Worker::new();
+
+    worker.scope(bases.len(), |scope, chunk| {
+        for bases in bases.chunks_mut(chunk) {
+            scope.spawn(move || {
+                let mut wnaf = Wnaf::new();
+                let mut wnaf = wnaf.scalar(coeff);
+
+                let mut projective = bases.iter()
+                                          .map(|base| wnaf.base(base.into_projective()))
+                                          .collect::<Vec<_>>();
+
+                E::G1::batch_normalization(&mut projective);
+
+                for (base, projective) in bases.iter_mut().zip(projective.iter()) {
+                    *base = projective.into_affine();
+                }
+            });
+        }
+    });
+}
+
+/// Hashes the transcript a new contribution commits to.
+fn transcript_hash<E: Engine>(
+    cs_hash: &[u8; 64],
+    previous: &[PublicKey<E>],
+    s: &E::G1Affine,
+    s_delta: &E::G1Affine
+) -> [u8; 64]
+{
+    let mut sink = HashWriter::new();
+
+    sink.write_all(&cs_hash[..]).expect("hashing never fails");
+    for pubkey in previous {
+        pubkey.write(&mut sink).expect("hashing never fails");
+    }
+    sink.write_all(s.into_uncompressed().as_ref()).expect("hashing never fails");
+    sink.write_all(s_delta.into_uncompressed().as_ref()).expect("hashing never fails");
+
+    sink.into_hash()
+}
+
+/// Deterministically maps a digest to an element of G2 whose discrete
+/// logarithm nobody knows.
+pub(super) fn hash_to_g2<E: Engine>(digest: &[u8]) -> E::G2 {
+    assert!(digest.len() >= 32);
+
+    let mut seed = [0u32; 8];
+    for (i, seed) in seed.iter_mut().enumerate() {
+        *seed = BigEndian::read_u32(&digest[i * 4..]);
+    }
+
+    ChaChaRng::from_seed(&seed[..]).gen()
+}
+
+/// A writer that hashes everything written to it with BLAKE2b.
+pub(super) struct HashWriter {
+    hasher: Blake2b
+}
+
+impl HashWriter {
+    pub(super) fn new() -> Self {
+        HashWriter {
+            hasher: Blake2b::new(64)
+        }
+    }
+
+    pub(super) fn into_hash(self) -> [u8; 64] {
+        let mut hash = [0u8; 64];
+        hash.copy_from_slice(self.hasher.finalize().as_ref());
+
+        hash
+    }
+}
+
+impl Write for HashWriter {
+    fn write(&mut self, buf: &[u8]) -> Result<usize, IoError> {
+        self.hasher.update(buf);
+
+        Ok(buf.len())
+    }
+}
+
+#[cfg(test)]
+mod test_with_bls12_381 {
+    use super::*;
+    use super::super::{
+        create_random_proof,
+        prepare_verifying_key,
+        verify_proof
+    };
+
+    use rand::Rand;
+    use pairing::bls12_381::{Bls12, Fr};
+    use super::super::tests::MySillyCircuit;
+
+    /// Phase 1 parameters for a domain of size `m`, from known trapdoors.
+    fn phase1_from_trapdoors<R: Rng>(m: usize, rng: &mut R) -> Phase1Parameters<Bls12> {
+        let tau = Fr::rand(rng);
+        let alpha = Fr::rand(rng);
+        let beta = Fr::rand(rng);
+
+        let worker = Worker::new();
+
+        let mut powers_of_tau = EvaluationDomain::<Bls12, _>::from_coeffs(
+            vec![Scalar::<Bls12>(Fr::zero()); m]
+        ).unwrap();
+        assert_eq!(powers_of_tau.as_ref().len(), m);
+
+        let mut acc = Fr::one();
+        for p in powers_of_tau.as_mut() {
+            p.0 = acc;
+            acc.mul_assign(&tau);
+        }
+
+        let z = powers_of_tau.z(&tau);
+        let h = powers_of_tau.as_ref()[0..m - 1].iter().map(|p| {
+            let mut e = p.0;
+            e.mul_assign(&z);
+            <Bls12 as Engine>::G1Affine::one().mul(e).into_affine()
+        }).collect();
+
+        powers_of_tau.ifft(&worker);
+
+        let g1 = |e: Fr, by: Fr| {
+            let mut e = e;
+            e.mul_assign(&by);
+            <Bls12 as Engine>::G1Affine::one().mul(e).into_affine()
+        };
+        let lagrange = powers_of_tau.into_coeffs();
+
+        Phase1Parameters {
+            alpha_g1: g1(Fr::one(), alpha),
+            beta_g1: g1(Fr::one(), beta),
+            beta_g2: <Bls12 as Engine>::G2Affine::one().mul(beta).into_affine(),
+            coeffs_g1: lagrange.iter().map(|l| g1(l.0, Fr::one())).collect(),
+            coeffs_g2: lagrange.iter().map(|l| <Bls12 as Engine>::G2Affine::one().mul(l.0).into_affine()).collect(),
+            alpha_coeffs_g1: lagrange.iter().map(|l| g1(l.0, alpha)).collect(),
+            beta_coeffs_g1: lagrange.iter().map(|l| g1(l.0, beta)).collect(),
+            h: h
+        }
+    }
+
+    #[test]
+    fn mpc_contributions() {
+        let rng = &mut thread_rng();
+
+        // One constraint and two input constraints.
+        let phase1 = phase1_from_trapdoors(4, rng);
+
+        let mut params = MPCParameters::new(MySillyCircuit::<Bls12> { a: None, b: None }, &phase1).unwrap();
+        assert!(params.verify(MySillyCircuit { a: None, b: None }, &phase1).unwrap().is_empty());
+
+        let mut hashes = vec![];
+        for _ in 0..3 {
+            let before = params.clone();
+            let hash = params.contribute(rng);
+
+            // Round trip through the serialized form participants exchange.
+            let mut v = vec![];
+            params.write(&mut v).unwrap();
+            params = MPCParameters::read(&v[..], true).unwrap();
+
+            assert_eq!(&verify_contribution(&before, &params).unwrap()[..], &hash[..]);
+            assert!(verify_contribution(&params, &before).is_err());
+            hashes.push(hash);
+        }
+
+        let verified = params.verify(MySillyCircuit { a: None, b: None }, &phase1).unwrap();
+        assert_eq!(verified.len(), hashes.len());
+        for (a, b) in verified.iter().zip(hashes.iter()) {
+            assert_eq!(&a[..], &b[..]);
+        }
+
+        let pvk = prepare_verifying_key(&params.get_params().vk);
+
+        for _ in 0..5 {
+            let a = Fr::rand(rng);
+            let b = Fr::rand(rng);
+            let mut c = a;
+            c.mul_assign(&b);
+
+            let proof = create_random_proof(
+                MySillyCircuit { a: Some(a), b: Some(b) },
+                params.get_params(),
+                rng
+            ).unwrap();
+
+            assert!(verify_proof(&pvk, &proof, &[c]).unwrap());
+        }
+
+        // Phase 1 parameters for another domain size are rejected.
+        match MPCParameters::new(MySillyCircuit::<Bls12> { a: None, b: None }, &phase1_from_trapdoors(8, rng)) {
+            Err(MPCError::Phase1SizeMismatch) => {},
+            _ => panic!("expected a size mismatch")
+        }
+
+        // So are parameters started from other phase 1 parameters.
+        match params.verify(MySillyCircuit { a: None, b: None }, &phase1_from_trapdoors(4, rng)) {
+            Err(MPCError::ParametersMismatch) => {},
+            _ => panic!("expected a parameter mismatch")
+        }
+    }
+
+    #[test]
+    fn mpc_rejects_tampering() {
+        let rng = &mut thread_rng();
+
+        let phase1 = phase1_from_trapdoors(4, rng);
+
+        let before = MPCParameters::new(MySillyCircuit::<Bls12> { a: None, b: None }, &phase1).unwrap();
+        let mut after = before.clone();
+        after.contribute(rng);
+        assert!(verify_contribution(&before, &after).is_ok());
+
+        // Changing delta without updating H and L.
+        {
+            let mut after = before.clone();
+            after.contribute(rng);
+            let delta = Fr::rand(rng);
+            after.params.vk.delta_g1 = after.params.vk.delta_g1.mul(delta).into_affine();
+            after.params.vk.delta_g2 = after.params.vk.delta_g2.mul(delta).into_affine();
+            after.contributions[0].delta_after = after.params.vk.delta_g1;
+            assert!(verify_contribution(&before, &after).is_err());
+            assert!(after.verify(MySillyCircuit { a: None, b: None }, &phase1).is_err());
+        }
+
+        // Changing one element of H.
+        {
+            let mut after = after.clone();
+            Arc::make_mut(&mut after.params.h)[0] = <Bls12 as Engine>::G1Affine::one();
+            match verify_contribution(&before, &after) {
+                Err(MPCError::InvalidContribution) => {},
+                _ => panic!("expected an invalid contribution")
+            }
+        }
+
+        // Reusing a proof of knowledge made for another transcript.
+        {
+            let mut other = before.clone();
+            other.contribute(rng);
+            let mut next = after.clone();
+            next.contribute(rng);
+            next.contributions[1].s = other.contributions[0].s;
+            next.contributions[1].s_delta = other.contributions[0].s_delta;
+            next.contributions[1].r_delta = other.contributions[0].r_delta;
+            next.contributions[1].transcript = other.contributions[0].transcript;
+            match verify_contribution(&after, &next) {
+                Err(MPCError::InvalidTranscript) => {},
+                _ => panic!("expected an invalid transcript")
+            }
+        }
+    }
+}
@@ -0,0 +1,668 @@
+//! Powers-of-tau accumulator for the circuit-independent phase of a
+//! Groth16 setup ceremony.
+//!
+//! An `Accumulator` of size `m` holds
+//!
+//! * `tau^i` in G1 for i between 0 and 2m-2 inclusive,
+//! * `tau^i` in G2, and `alpha * tau^i` and `beta * tau^i` in G1, for i
+//!   between 0 and m-1 inclusive,
+//! * `beta` in G2,
+//!
+//! starting from `tau = alpha = beta = 1`. Each participant multiplies in
+//! secret values of their own with `Accumulator::contribute` and publishes
+//! the returned `PublicKey`, which anyone can check against the
+//! accumulators before and after with `verify_transform`. The final
+//! accumulator is converted to the Lagrange basis of a circuit's domain
+//! with `Accumulator::lagrange`, which is what `MPCParameters` and
+//! `generate_parameters_from_accumulator` consume.
+
+use rand::Rng;
+
+use pairing::{
+    Engine,
+    PrimeField,
+    Field,
+    Wnaf,
+    CurveProjective,
+    CurveAffine,
+    EncodedPoint,
+    IoError
+};
+
+use super::{
+    Parameters,
+    MPCParameters,
+    MPCError,
+    Phase1Parameters,
+    Read,
+    Write,
+    read_vec,
+    read_point
+};
+
+use super::mpc::{
+    HashWriter,
+    hash_to_g2,
+    merge_pairs,
+    same_ratio
+};
+
+use ::{
+    SynthesisError,
+    Circuit
+};
+
+use ::domain::{
+    EvaluationDomain,
+    Point
+};
+
+use ::multicore::{
+    Worker
+};
+
+/// The powers of tau, alpha and beta accumulated so far.
+#[derive(Clone)]
+pub struct Accumulator<E: Engine> {
+    // The size of the largest evaluation domain this can be used for.
+    size: usize,
+
+    // tau^i in G1 for i between 0 and 2 * size - 2 inclusive.
+    tau_powers_g1: Vec<E::G1Affine>,
+
+    // tau^i in G2 for i between 0 and size - 1 inclusive.
+    tau_powers_g2: Vec<E::G2Affine>,
+
+    // alpha * tau^i and beta * tau^i in G1 for i between 0 and size - 1
+    // inclusive.
+    alpha_tau_powers_g1: Vec<E::G1Affine>,
+    beta_tau_powers_g1: Vec<E::G1Affine>,
+
+    beta_g2: E::G2Affine
+}
+
+impl<E: Engine> PartialEq for Accumulator<E> {
+    fn eq(&self, other: &Self) -> bool {
+        self.size == other.size &&
+        self.tau_powers_g1 == other.tau_powers_g1 &&
+        self.tau_powers_g2 == other.tau_powers_g2 &&
+        self.alpha_tau_powers_g1 == other.alpha_tau_powers_g1 &&
+        self.beta_tau_powers_g1 == other.beta_tau_powers_g1 &&
+        self.beta_g2 == other.beta_g2
+    }
+}
+
+impl<E: Engine> Accumulator<E> {
+    /// Creates the initial accumulator, for evaluation domains of up to
+    /// `size` elements. `size` must be a power of two, and at least 2 so that
+    /// contributions can be verified.
+    pub fn new(size: usize) -> Self {
+        assert!(size >= 2 && size.is_power_of_two(), "size must be a power of two and at least 2");
+
+        Accumulator {
+            size: size,
+            tau_powers_g1: vec![E::G1Affine::one(); 2 * size - 1],
+            tau_powers_g2: vec![E::G2Affine::one(); size],
+            alpha_tau_powers_g1: vec![E::G1Affine::one(); size],
+            beta_tau_powers_g1: vec![E::G1Affine::one(); size],
+            beta_g2: E::G2Affine::one()
+        }
+    }
+
+    /// The size of the largest evaluation domain this can be used for.
+    pub fn size(&self) -> usize {
+        self.size
+    }
+
+    /// Hash of the accumulator, which each contribution commits to.
+    pub fn hash(&self) -> [u8; 64] {
+        let mut sink = HashWriter::new();
+        self.write(&mut sink, false).expect("hashing never fails");
+
+        sink.into_hash()
+    }
+
+    /// Multiplies fresh random tau, alpha and beta into the accumulator and
+    /// returns the public key proving it. The randomness is dropped before
+    /// returning, but `rng` must not be reused to derive it again.
+    pub fn contribute<R: Rng>(
+        &mut self,
+        rng: &mut R
+    ) -> PublicKey<E>
+    {
+        let digest = self.hash();
+        let (pubkey, privkey) = keypair::<E, R>(rng, &digest);
+
+        self.transform(&privkey);
+
+        pubkey
+    }
+
+    fn transform(&mut self, key: &PrivateKey<E>) {
+        let worker = Worker::new();
+
+        // Compute tau^i for every power we hold.
+        let mut taupowers = vec![E::Fr::zero(); self.tau_powers_g1.len()];
+        worker.scope(taupowers.len(), |scope, chunk| {
+            for (i, taupowers) in taupowers.chunks_mut(chunk).enumerate() {
+                scope.spawn(move || {
+                    let mut acc = key.tau.pow(&[(i * chunk) as u64]);
+
+                    for t in taupowers {
+                        *t = acc;
+                        acc.mul_assign(&key.tau);
+                    }
+                });
+            }
+        });
+
+        batch_exp(&mut self.tau_powers_g1, &taupowers, None, &worker);
+        batch_exp(&mut self.tau_powers_g2, &taupowers[0..self.size], None, &worker);
+        batch_exp(&mut self.alpha_tau_powers_g1, &taupowers[0..self.size], Some(&key.alpha), &worker);
+        batch_exp(&mut self.beta_tau_powers_g1, &taupowers[0..self.size], Some(&key.beta), &worker);
+        self.beta_g2 = self.beta_g2.mul(key.beta).into_affine();
+    }
+
+    /// Converts the accumulator to the Lagrange basis of the smallest
+    /// radix-2 evaluation domain with at least `size` elements.
+    pub fn lagrange(&self, size: usize) -> Result<Phase1Parameters<E>, SynthesisError> {
+        fn lagrange<G: CurveAffine>(
+            powers: &[G],
+            size: usize,
+            worker: &Worker
+        ) -> Result<Vec<G>, SynthesisError>
+        {
+            let mut domain = EvaluationDomain::from_coeffs(
+                powers[0..size].iter().map(|p| Point(p.into_projective())).collect()
+            )?;
+            domain.ifft(worker);
+
+            let mut coeffs = domain.into_coeffs().into_iter().map(|p| p.0).collect::<Vec<_>>();
+            G::Projective::batch_normalization(&mut coeffs);
+
+            Ok(coeffs.into_iter().map(|p| p.into_affine()).collect())
+        }
+
+        // Round up to the size of the domain the prover will use.
+        let m = EvaluationDomain::<E, Point<E::G1>>::from_coeffs(
+            vec![Point(E::G1::zero()); size]
+        )?.as_ref().len();
+
+        if m > self.size {
+            return Err(SynthesisError::PolynomialDegreeTooLarge);
+        }
+
+        let worker = Worker::new();
+
+        // tau^i * t(tau) = tau^(i + m) - tau^i
+        let mut h = self.tau_powers_g1[m..2 * m - 1].iter()
+                        .zip(self.tau_powers_g1[0..m - 1].iter())
+                        .map(|(hi, lo)| {
+                            let mut h = hi.into_projective();
+                            h.sub_assign(&lo.into_projective());
+                            h
+                        })
+                        .collect::<Vec<_>>();
+        E::G1::batch_normalization(&mut h);
+
+        Ok(Phase1Parameters {
+            alpha_g1: self.alpha_tau_powers_g1[0],
+            beta_g1: self.beta_tau_powers_g1[0],
+            beta_g2: self.beta_g2,
+            coeffs_g1: lagrange(&self.tau_powers_g1, m, &worker)?,
+            coeffs_g2: lagrange(&self.tau_powers_g2, m, &worker)?,
+            alpha_coeffs_g1: lagrange(&self.alpha_tau_powers_g1, m, &worker)?,
+            beta_coeffs_g1: lagrange(&self.beta_tau_powers_g1, m, &worker)?,
+            h: h.into_iter().map(|e| e.into_affine()).collect()
+        })
+    }
+
+    /// Writes the accumulator, with points in their compressed encoding if
+    /// `compressed` is set.
+    pub fn write<W: Write>(
+        &self,
+        mut writer: W,
+        compressed: bool
+    ) -> Result<(), IoError>
+    {
+        fn write_all<G: CurveAffine, W: Write>(
+            mut writer: W,
+            points: &[G],
+            compressed: bool
+        ) -> Result<(), IoError>
+        {
+            for p in points {
+                if compressed {
+                    writer.write_all(p.into_compressed().as_ref())?;
+                } else {
+                    writer.write_all(p.into_uncompressed().as_ref())?;
+                }
+            }
+
+            Ok(())
+        }
+
+        writer.write_u32(self.size as u32)?;
+        write_all(&mut writer, &self.tau_powers_g1, compressed)?;
+        write_all(&mut writer, &self.tau_powers_g2, compressed)?;
+        write_all(&mut writer, &self.alpha_tau_powers_g1, compressed)?;
+        write_all(&mut writer, &self.beta_tau_powers_g1, compressed)?;
+        write_all(&mut writer, &[self.beta_g2], compressed)?;
+
+        Ok(())
+    }
+
+    /// Reads an accumulator written by `write` with the same `compressed`.
+    /// If `checked` is false, points are not checked to be on the curve or
+    /// in the prime order subgroup, which is only safe for accumulators
+    /// that have already been verified: `contribute` and `lagrange` multiply
+    /// the points by scalars, which assumes that they are in the subgroup.
+    pub fn read<R: Read>(
+        mut reader: R,
+        compressed: bool,
+        checked: bool
+    ) -> Result<Self, IoError>
+    {
+        fn read_all<G: CurveAffine, R: Read>(
+            mut reader: R,
+            len: usize,
+            compressed: bool,
+            checked: bool
+        ) -> Result<Vec<G>, IoError>
+        {
+            read_vec(len, || if compressed {
+                read_point::<G::Compressed, _>(&mut reader, checked)
+            } else {
+                read_point::<G::Uncompressed, _>(&mut reader, checked)
+            })
+        }
+
+        let size = reader.read_u32()? as usize;
+        if size < 2 || !size.is_power_of_two() {
+            return Err(IoError::Error);
+        }
+
+        let tau_powers_g1 = read_all(&mut reader, 2 * size - 1, compressed, checked)?;
+        let tau_powers_g2 = read_all(&mut reader, size, compressed, checked)?;
+        let alpha_tau_powers_g1 = read_all(&mut reader, size, compressed, checked)?;
+        let beta_tau_powers_g1 = read_all(&mut reader, size, compressed, checked)?;
+        let beta_g2 = read_all(&mut reader, 1, compressed, checked)?[0];
+
+        Ok(Accumulator {
+            size: size,
+            tau_powers_g1: tau_powers_g1,
+            tau_powers_g2: tau_powers_g2,
+            alpha_tau_powers_g1: alpha_tau_powers_g1,
+            beta_tau_powers_g1: beta_tau_powers_g1,
+            beta_g2: beta_g2
+        })
+    }
+}
+
+/// Multiplies each base by the corresponding exponent, and by `coeff` if
+/// given, in place.
+fn batch_exp<G: CurveAffine>(
+    bases: &mut [G],
+    exps: &[G::Scalar],
+    coeff: Option<&G::Scalar>,
+    worker: &Worker
+)
+{
+    assert_eq!(bases.len(), exps.len());
+
+    worker.scope(bases.len(), |scope, chunk| {
+        for (bases, exps) in bases.chunks_mut(chunk).zip(exps.chunks(chunk)) {
+            scope.spawn(move || {
+                let mut wnaf = Wnaf::new();
+
+                let mut projective = bases.iter().zip(exps.iter()).map(|(base, exp)| {
+                    let mut exp = *exp;
+                    if let Some(coeff) = coeff {
+                        exp.mul_assign(coeff);
+                    }
+
+                    wnaf.scalar(exp.into_repr()).base(base.into_projective())
+                }).collect::<Vec<_>>();
+
+                G::Projective::batch_normalization(&mut projective);
+
+                for (base, projective) in bases.iter_mut().zip(projective.iter()) {
+                    *base = projective.into_affine();
+                }
+            });
+        }
+    });
+}
+
+/// Proof that a contribution multiplied known tau, alpha and beta into the
+/// accumulator. For each of them, it holds a random s in G1 with s
+/// multiplied by the secret, and the secret in G2 multiplied into a point
+/// derived from the accumulator hash and s.
+#[derive(Clone)]
+pub struct PublicKey<E: Engine> {
+    tau_g1: (E::G1Affine, E::G1Affine),
+    alpha_g1: (E::G1Affine, E::G1Affine),
+    beta_g1: (E::G1Affine, E::G1Affine),
+    tau_g2: E::G2Affine,
+    alpha_g2: E::G2Affine,
+    beta_g2: E::G2Affine
+}
+
+impl<E: Engine> PartialEq for PublicKey<E> {
+    fn eq(&self, other: &Self) -> bool {
+        self.tau_g1 == other.tau_g1 &&
+        self.alpha_g1 == other.alpha_g1 &&
+        self.beta_g1 == other.beta_g1 &&
+        self.tau_g2 == other.tau_g2 &&
+        self.alpha_g2 == other.alpha_g2 &&
+        self.beta_g2 == other.beta_g2
+    }
+}
+
+impl<E: Engine> PublicKey<E> {
+    pub fn write<W: Write>(
+        &self,
+        mut writer: W
+    ) -> Result<(), IoError>
+    {
+        for p in &[self.tau_g1.0, self.tau_g1.1, self.alpha_g1.0, self.alpha_g1.1, self.beta_g1.0, self.beta_g1.1] {
+            writer.write_all(p.into_uncompressed().as_ref())?;
+        }
+        for p in &[self.tau_g2, self.alpha_g2, self.beta_g2] {
+            writer.write_all(p.into_uncompressed().as_ref())?;
+        }
+
+        Ok(())
+    }
+
+    pub fn read<R: Read>(
+        mut reader: R
+    ) -> Result<Self, IoError>
+    {
+        let mut g1 = [E::G1Affine::zero(); 6];
+        let mut g2 = [E::G2Affine::zero(); 3];
+
+        let mut g1_repr = <E::G1Affine as CurveAffine>::Uncompressed::empty();
+        for p in g1.iter_mut() {
+            reader.read_exact(g1_repr.as_mut())?;
+            *p = g1_repr.into_affine()?;
+        }
+
+        let mut g2_repr = <E::G2Affine as CurveAffine>::Uncompressed::empty();
+        for p in g2.iter_mut() {
+            reader.read_exact(g2_repr.as_mut())?;
+            *p = g2_repr.into_affine()?;
+        }
+
+        if g1.iter().any(|p| p.is_zero()) || g2.iter().any(|p| p.is_zero()) {
+            return Err(IoError::Infinity);
+        }
+
+        Ok(PublicKey {
+            tau_g1: (g1[0], g1[1]),
+            alpha_g1: (g1[2], g1[3]),
+            beta_g1: (g1[4], g1[5]),
+            tau_g2: g2[0],
+            alpha_g2: g2[1],
+            beta_g2: g2[2]
+        })
+    }
+}
+
+struct PrivateKey<E: Engine> {
+    tau: E::Fr,
+    alpha: E::Fr,
+    beta: E::Fr
+}
+
+fn keypair<E: Engine, R: Rng>(
+    rng: &mut R,
+    digest: &[u8; 64]
+) -> (PublicKey<E>, PrivateKey<E>)
+{
+    let tau = nonzero::<E::Fr, R>(rng);
+    let alpha = nonzero::<E::Fr, R>(rng);
+    let beta = nonzero::<E::Fr, R>(rng);
+
+    let mut op = |x: E::Fr, personalization: u8| {
+        let g1_s = loop {
+            let s = rng.gen::<E::G1>();
+
+            if !s.is_zero() {
+                break s.into_affine();
+            }
+        };
+        let g1_s_x = g1_s.mul(x).into_affine();
+        let g2_s_x = compute_g2_s::<E>(digest, &g1_s, &g1_s_x, personalization).mul(x).into_affine();
+
+        ((g1_s, g1_s_x), g2_s_x)
+    };
+
+    let (tau_g1, tau_g2) = op(tau, 0);
+    let (alpha_g1, alpha_g2) = op(alpha, 1);
+    let (beta_g1, beta_g2) = op(beta, 2);
+
+    (
+        PublicKey {
+            tau_g1: tau_g1,
+            alpha_g1: alpha_g1,
+            beta_g1: beta_g1,
+            tau_g2: tau_g2,
+            alpha_g2: alpha_g2,
+            beta_g2: beta_g2
+        },
+        PrivateKey {
+            tau: tau,
+            alpha: alpha,
+            beta: beta
+        }
+    )
+}
+
+fn nonzero<F: PrimeField, R: Rng>(rng: &mut R) -> F {
+    loop {
+        let x = rng.gen::<F>();
+
+        if !x.is_zero() {
+            return x;
+        }
+    }
+}
+
+/// Derives the G2 point a public key multiplies its secret into, from the
+/// accumulator hash and the G1 pair.
+fn compute_g2_s<E: Engine>(
+    digest: &[u8; 64],
+    g1_s: &E::G1Affine,
+    g1_s_x: &E::G1Affine,
+    personalization: u8
+) -> E::G2Affine
+{
+    let mut sink = HashWriter::new();
+
+    sink.write_all(&[personalization]).expect("hashing never fails");
+    sink.write_all(&digest[..]).expect("hashing never fails");
+    sink.write_all(g1_s.into_uncompressed().as_ref()).expect("hashing never fails");
+    sink.write_all(g1_s_x.into_uncompressed().as_ref()).expect("hashing never fails");
+
+    hash_to_g2::<E>(&sink.into_hash()).into_affine()
+}
+
+/// Checks that `after` is `before` with the contribution proven by `key`
+/// multiplied in, and that `after` is a well-formed accumulator.
+pub fn verify_transform<E: Engine>(
+    before: &Accumulator<E>,
+    after: &Accumulator<E>,
+    key: &PublicKey<E>
+) -> bool
+{
+    if before.size != after.size {
+        return false;
+    }
+
+    let digest = before.hash();
+
+    let tau_g2_s = compute_g2_s::<E>(&digest, &key.tau_g1.0, &key.tau_g1.1, 0);
+    let alpha_g2_s = compute_g2_s::<E>(&digest, &key.alpha_g1.0, &key.alpha_g1.1, 1);
+    let beta_g2_s = compute_g2_s::<E>(&digest, &key.beta_g1.0, &key.beta_g1.1, 2);
+
+    // Does the public key prove knowledge of each secret?
+    if !same_ratio(key.tau_g1, (tau_g2_s, key.tau_g2)) ||
+       !same_ratio(key.alpha_g1, (alpha_g2_s, key.alpha_g2)) ||
+       !same_ratio(key.beta_g1, (beta_g2_s, key.beta_g2))
+    {
+        return false;
+    }
+
+    // The first power of tau is always the generator.
+    if after.tau_powers_g1[0] != E::G1Affine::one() || after.tau_powers_g2[0] != E::G2Affine::one() {
+        return false;
+    }
+
+    // Were those secrets multiplied into the accumulator?
+    if !same_ratio((before.tau_powers_g1[1], after.tau_powers_g1[1]), (tau_g2_s, key.tau_g2)) ||
+       !same_ratio((before.alpha_tau_powers_g1[0], after.alpha_tau_powers_g1[0]), (alpha_g2_s, key.alpha_g2)) ||
+       !same_ratio((before.beta_tau_powers_g1[0], after.beta_tau_powers_g1[0]), (beta_g2_s, key.beta_g2)) ||
+       !same_ratio(key.beta_g1, (before.beta_g2, after.beta_g2))
+    {
+        return false;
+    }
+
+    // Are the powers consecutive powers of the same tau?
+    let tau_g1 = (after.tau_powers_g1[0], after.tau_powers_g1[1]);
+    let tau_g2 = (after.tau_powers_g2[0], after.tau_powers_g2[1]);
+
+    same_ratio(power_pairs(&after.tau_powers_g1), tau_g2) &&
+    same_ratio(power_pairs(&after.tau_powers_g2), tau_g1) &&
+    same_ratio(power_pairs(&after.alpha_tau_powers_g1), tau_g2) &&
+    same_ratio(power_pairs(&after.beta_tau_powers_g1), tau_g2)
+}
+
+/// Merges (v[i], v[i + 1]) into a single pair with the same ratio, if
+/// they all share it.
+fn power_pairs<G: CurveAffine>(v: &[G]) -> (G, G) {
+    merge_pairs(&v[0..v.len() - 1], &v[1..])
+}
+
+/// Creates parameters for a circuit from a finished accumulator, sampling
+/// only `delta` locally. This trusts whoever runs it with `delta`; use
+/// `MPCParameters::from_accumulator` to compute it in a ceremony instead.
+pub fn generate_parameters_from_accumulator<E, C, R>(
+    circuit: C,
+    accumulator: &Accumulator<E>,
+    rng: &mut R
+) -> Result<Parameters<E>, MPCError>
+    where E: Engine, C: Circuit<E>, R: Rng
+{
+    let mut params = MPCParameters::from_accumulator(circuit, accumulator)?;
+    params.contribute(rng);
+
+    Ok(params.get_params().clone())
+}
+
+#[cfg(test)]
+mod test_with_bls12_381 {
+    use super::*;
+    use super::super::{
+        create_random_proof,
+        prepare_verifying_key,
+        verify_proof
+    };
+
+    use rand::{Rand, thread_rng};
+    use pairing::bls12_381::{Bls12, Fr, G1Affine, G2Affine};
+    use super::super::tests::MySillyCircuit;
+
+    #[test]
+    fn powers_of_tau() {
+        let rng = &mut thread_rng();
+
+        let mut acc = Accumulator::<Bls12>::new(8);
+
+        for &compressed in &[false, true] {
+            let before = acc.clone();
+            let key = acc.contribute(rng);
+
+            let mut v = vec![];
+            acc.write(&mut v, compressed).unwrap();
+            acc = Accumulator::read(&v[..], compressed, true).unwrap();
+
+            let mut v = vec![];
+            key.write(&mut v).unwrap();
+            let key = PublicKey::read(&v[..]).unwrap();
+
+            assert!(verify_transform(&before, &acc, &key));
+            assert!(!verify_transform(&acc, &before, &key));
+
+            // A key is bound to the accumulator it was made for.
+            let mut other = before.clone();
+            let other_key = other.contribute(rng);
+            assert!(!verify_transform(&before, &acc, &other_key));
+        }
+
+        // Breaking the chain of powers is detected.
+        {
+            let before = acc.clone();
+            let key = acc.contribute(rng);
+            let mut bad = acc.clone();
+            bad.tau_powers_g1[5] = bad.tau_powers_g1[4];
+            assert!(verify_transform(&before, &acc, &key));
+            assert!(!verify_transform(&before, &bad, &key));
+        }
+
+        // The circuit needs a domain of size 4, which fits in the accumulator.
+        assert!(acc.lagrange(9).is_err());
+
+        let mut mpc = MPCParameters::from_accumulator(MySillyCircuit::<Bls12> { a: None, b: None }, &acc).unwrap();
+        mpc.contribute(rng);
+        let phase1 = acc.lagrange(3).unwrap();
+        assert_eq!(phase1.coeffs_g1.len(), 4);
+        assert_eq!(mpc.verify(MySillyCircuit { a: None, b: None }, &phase1).unwrap().len(), 1);
+
+        let direct = generate_parameters_from_accumulator(MySillyCircuit { a: None, b: None }, &acc, rng).unwrap();
+
+        for params in &[mpc.get_params(), &direct] {
+            let pvk = prepare_verifying_key(&params.vk);
+
+            let a = Fr::rand(rng);
+            let b = Fr::rand(rng);
+            let mut c = a;
+            c.mul_assign(&b);
+
+            let proof = create_random_proof(
+                MySillyCircuit { a: Some(a), b: Some(b) },
+                *params,
+                rng
+            ).unwrap();
+
+            assert!(verify_proof(&pvk, &proof, &[c]).unwrap());
+            assert!(!verify_proof(&pvk, &proof, &[a]).unwrap());
+        }
+    }
+
+    #[test]
+    fn too_small() {
+        // A size-1 accumulator has no tau^1 for verify_transform to check
+        // against, so it must not be readable.
+        let acc = Accumulator::<Bls12> {
+            size: 1,
+            tau_powers_g1: vec![G1Affine::one()],
+            tau_powers_g2: vec![G2Affine::one()],
+            alpha_tau_powers_g1: vec![G1Affine::one()],
+            beta_tau_powers_g1: vec![G1Affine::one()],
+            beta_g2: G2Affine::one()
+        };
+
+        for &compressed in &[false, true] {
+            let mut v = vec![];
+            acc.write(&mut v, compressed).unwrap();
+            assert!(Accumulator::<Bls12>::read(&v[..], compressed, true).is_err());
+        }
+    }
+
+    #[test]
+    #[should_panic]
+    fn new_too_small() {
+        Accumulator::<Bls12>::new(1);
+    }
+}
@@ -0,0 +1,235 @@
+//! A `ParameterSource` with fixed-base window tables for every query.
+//!
+//! The bases of a proving key never change between proofs, so the shifted
+//! copies of each base used by a windowed multiexp can be computed once.
+//! `PrecomputedParameters` keeps such a table for every query of a
+//! `Parameters`, trading `ceil(NUM_BITS / window)` times its memory for
+//! multiexps that sum their buckets once and need no doublings.
+
+use pairing::{
+    CurveAffine,
+    Engine
+};
+
+#[cfg(feature = "std")]
+use std::sync::Arc;
+#[cfg(not(feature = "std"))]
+use alloc::sync::Arc;
+
+use ::{
+    SynthesisError
+};
+
+use multicore::Worker;
+
+use multiexp::{
+    PrecomputedBases,
+    precomputed_window_size
+};
+
+use super::{
+    ParameterSource,
+    Parameters,
+    VerifyingKey
+};
+
+/// Groth16 parameters whose queries are extended with fixed-base window
+/// tables.
+pub struct PrecomputedParameters<E: Engine> {
+    pub vk: VerifyingKey<E>,
+
+    h: Arc<PrecomputedBases<E::G1Affine>>,
+    l: Arc<PrecomputedBases<E::G1Affine>>,
+    a: Arc<PrecomputedBases<E::G1Affine>>,
+    b_g1: Arc<PrecomputedBases<E::G1Affine>>,
+    b_g2: Arc<PrecomputedBases<E::G2Affine>>
+}
+
+impl<E: Engine> PrecomputedParameters<E> {
+    /// Computes the tables of every query of `params`, with the window
+    /// size of each query picked by `precomputed_window_size`.
+    pub fn new(params: &Parameters<E>, worker: &Worker) -> Self {
+        Self::with_window_size(params, worker, precomputed_window_size)
+    }
+
+    /// Computes the tables of every query of `params`, with the window
+    /// size of each query given by `window` from its number of bases.
+    pub fn with_window_size<F>(params: &Parameters<E>, worker: &Worker, window: F) -> Self
+        where F: Fn(usize) -> u32
+    {
+        fn table<G: CurveAffine, F: Fn(usize) -> u32>(
+            worker: &Worker,
+            bases: &[G],
+            window: &F
+        ) -> Arc<PrecomputedBases<G>>
+        {
+            Arc::new(PrecomputedBases::new(worker, bases, window(bases.len())))
+        }
+
+        PrecomputedParameters {
+            vk: params.vk.clone(),
+            h: table(worker, &params.h, &window),
+            l: table(worker, &params.l, &window),
+            a: table(worker, &params.a, &window),
+            b_g1: table(worker, &params.b_g1, &window),
+            b_g2: table(worker, &params.b_g2, &window)
+        }
+    }
+}
+
+impl<'a, E: Engine> ParameterSource<E> for &'a PrecomputedParameters<E> {
+    type G1Builder = (Arc<PrecomputedBases<E::G1Affine>>, usize);
+    type G2Builder = (Arc<PrecomputedBases<E::G2Affine>>, usize);
+
+    fn get_vk(
+        &mut self,
+        _: usize
+    ) -> Result<VerifyingKey<E>, SynthesisError>
+    {
+        Ok(self.vk.clone())
+    }
+
+    fn get_h(
+        &mut self,
+        _: usize
+    ) -> Result<Self::G1Builder, SynthesisError>
+    {
+        Ok((self.h.clone(), 0))
+    }
+
+    fn get_l(
+        &mut self,
+        _: usize
+    ) -> Result<Self::G1Builder, SynthesisError>
+    {
+        Ok((self.l.clone(), 0))
+    }
+
+    fn get_a(
+        &mut self,
+        num_inputs: usize,
+        _: usize
+    ) -> Result<(Self::G1Builder, Self::G1Builder), SynthesisError>
+    {
+        Ok(((self.a.clone(), 0), (self.a.clone(), num_inputs)))
+    }
+
+    fn get_b_g1(
+        &mut self,
+        num_inputs: usize,
+        _: usize
+    ) -> Result<(Self::G1Builder, Self::G1Builder), SynthesisError>
+    {
+        Ok(((self.b_g1.clone(), 0), (self.b_g1.clone(), num_inputs)))
+    }
+
+    fn get_b_g2(
+        &mut self,
+        num_inputs: usize,
+        _: usize
+    ) -> Result<(Self::G2Builder, Self::G2Builder), SynthesisError>
+    {
+        Ok(((self.b_g2.clone(), 0), (self.b_g2.clone(), num_inputs)))
+    }
+    fn get_domain_size(
+        &mut self,
+        _: usize
+    ) -> Result<usize, SynthesisError>
+    {
+        // The H query has one element less than the domain.
+        Ok(self.h.len() + 1)
+    }
+}
+
+#[cfg(test)]
+mod test_with_bls12_381 {
+    use super::*;
+    use super::super::{
+        generate_random_parameters,
+        create_proof,
+        prepare_verifying_key,
+        verify_proof
+    };
+    use {Circuit, ConstraintSystem};
+
+    use rand::{Rand, thread_rng};
+    use pairing::Field;
+    use pairing::bls12_381::{Bls12, Fr};
+
+    // Proves knowledge of the product of a chain of factors. Every third
+    // variable is a copy of the product so far that only appears in C,
+    // so that the A and B queries are sparse.
+    struct Product<E: Engine> {
+        factors: Vec<Option<E::Fr>>
+    }
+
+    impl<E: Engine> Circuit<E> for Product<E> {
+        fn synthesize<CS: ConstraintSystem<E>>(
+            self,
+            cs: &mut CS
+        ) -> Result<(), SynthesisError>
+        {
+            let mut acc_val = Some(E::Fr::one());
+            let mut acc = CS::one();
+
+            for (i, factor) in self.factors.into_iter().enumerate() {
+                if i % 3 == 2 {
+                    let copy = cs.alloc(|| "copy", || acc_val.ok_or(SynthesisError::AssignmentMissing))?;
+                    cs.enforce(|| "copy", |lc| lc + acc, |lc| lc + CS::one(), |lc| lc + copy);
+                    continue;
+                }
+
+                let x = cs.alloc(|| "factor", || factor.ok_or(SynthesisError::AssignmentMissing))?;
+                acc_val = acc_val.and_then(|mut acc| factor.map(|f| { acc.mul_assign(&f); acc }));
+                let product = cs.alloc(|| "product", || acc_val.ok_or(SynthesisError::AssignmentMissing))?;
+
+                cs.enforce(|| "product", |lc| lc + acc, |lc| lc + x, |lc| lc + product);
+                acc = product;
+            }
+
+            let out = cs.alloc_input(|| "out", || acc_val.ok_or(SynthesisError::AssignmentMissing))?;
+            cs.enforce(|| "out", |lc| lc + acc, |lc| lc + CS::one(), |lc| lc + out);
+
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn precomputed_parameters() {
+        let rng = &mut thread_rng();
+        let worker = Worker::new();
+
+        let params = generate_random_parameters::<Bls12, _, _>(
+            Product { factors: vec![None; 50] },
+            rng
+        ).unwrap();
+        let pvk = prepare_verifying_key(&params.vk);
+
+        let factors = (0..50).map(|_| Some(Fr::rand(rng))).collect::<Vec<_>>();
+        let mut out = Fr::one();
+        for (i, f) in factors.iter().enumerate() {
+            if i % 3 != 2 {
+                out.mul_assign(&f.unwrap());
+            }
+        }
+
+        let r = Fr::rand(rng);
+        let s = Fr::rand(rng);
+        let expected = create_proof(Product { factors: factors.clone() }, &params, r, s).unwrap();
+
+        let precomputed = PrecomputedParameters::new(&params, &worker);
+        assert_eq!(precomputed.a.window(), precomputed_window_size(params.a.len()));
+        assert_eq!(precomputed.a.len(), params.a.len());
+
+        let proof = create_proof(Product { factors: factors.clone() }, &precomputed, r, s).unwrap();
+        assert!(proof == expected);
+        assert!(verify_proof(&pvk, &proof, &[out]).unwrap());
+
+        // Windows that do not divide the scalar size.
+        for &window in &[1, 5, 7] {
+            let precomputed = PrecomputedParameters::with_window_size(&params, &worker, |_| window);
+            let proof = create_proof(Product { factors: factors.clone() }, &precomputed, r, s).unwrap();
+            assert!(proof == expected);
+        }
+    }
+}
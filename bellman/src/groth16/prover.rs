@@ -8,6 +8,7 @@ use alloc::sync::Arc;
 #[cfg(not(feature = "std"))]
 use alloc::string::String;
 use rstd::prelude::*;
+use rstd::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
 
 use futures::Future;
 
@@ -21,9 +22,14 @@ use pairing::{
 
 use super::{
     ParameterSource,
-    Proof
+    Proof,
+    VerifyingKey,
+    ProverStore,
+    StoreSlot
 };
 
+use super::store::SLOTS;
+
 use ::{
     SynthesisError,
     Circuit,
@@ -39,9 +45,11 @@ use ::domain::{
 };
 
 use ::multiexp::{
+    ChunkObserver,
     DensityTracker,
     FullDensity,
-    multiexp
+    multiexp_chunks,
+    multiexp_observed
 };
 
 use ::multicore::{
@@ -87,7 +95,11 @@ fn eval<E: Engine>(
     acc
 }
 
-struct ProvingAssignment<E: Engine> {
+// Number of evaluations of each polynomial buffered before they are
+// appended to the store of a low-memory proof
+const STORE_CHUNK: usize = 1 << 12;
+
+struct ProvingAssignment<'a, E: Engine> {
     // Density of queries
     a_aux_density: DensityTracker,
     b_input_density: DensityTracker,
@@ -100,10 +112,38 @@ struct ProvingAssignment<E: Engine> {
 
     // Assignments of variables
     input_assignment: Vec<E::Fr>,
-    aux_assignment: Vec<E::Fr>
+    aux_assignment: Vec<E::Fr>,
+
+    num_constraints: usize,
+
+    // Where the evaluations are streamed in a low-memory proof, and the
+    // first error it returned
+    store: Option<&'a mut dyn ProverStore<E>>,
+    store_error: Option<SynthesisError>
+}
+
+impl<'a, E: Engine> ProvingAssignment<'a, E> {
+    // Moves the buffered evaluations to the store, if there is one.
+    fn flush(&mut self) {
+        if let Some(ref mut store) = self.store {
+            for &mut (slot, ref mut evals) in &mut [
+                (StoreSlot::A, &mut self.a),
+                (StoreSlot::B, &mut self.b),
+                (StoreSlot::C, &mut self.c)
+            ] {
+                if self.store_error.is_none() {
+                    let values = evals.iter().map(|s| s.0).collect::<Vec<_>>();
+                    if let Err(e) = store.append(slot, &values) {
+                        self.store_error = Some(e);
+                    }
+                }
+                evals.clear();
+            }
+        }
+    }
 }
 
-impl<E: Engine> ConstraintSystem<E> for ProvingAssignment<E> {
+impl<'a, E: Engine> ConstraintSystem<E> for ProvingAssignment<'a, E> {
     type Root = Self;
 
     fn alloc<F, A, AR>(
@@ -177,6 +217,11 @@ impl<E: Engine> ConstraintSystem<E> for ProvingAssignment<E> {
             &self.input_assignment,
             &self.aux_assignment
         )));
+
+        self.num_constraints += 1;
+        if self.store.is_some() && self.a.len() >= STORE_CHUNK {
+            self.flush();
+        }
     }
 
     fn push_namespace<NR, N>(&mut self, _: N)
@@ -208,14 +253,237 @@ pub fn create_random_proof<E, C, R, P: ParameterSource<E>>(
     create_proof::<E, C, P>(circuit, params, r, s)
 }
 
+/// Turns `proof` into a fresh-looking proof of the same statement,
+/// without knowledge of the witness, so that relayed proofs cannot be
+/// linked to the original.
+///
+/// For random r1 and r2, the new proof is
+/// (A / r1, r1 B + r1 r2 delta, C + r2 A).
+pub fn rerandomize_proof<E, R>(
+    vk: &VerifyingKey<E>,
+    proof: &Proof<E>,
+    rng: &mut R
+) -> Result<Proof<E>, SynthesisError>
+    where E: Engine, R: Rng
+{
+    if vk.delta_g2.is_zero() {
+        // If this element is zero, someone is trying to perform a
+        // subversion-CRS attack.
+        return Err(SynthesisError::UnexpectedIdentity);
+    }
+
+    let r1 = loop {
+        let r1: E::Fr = rng.gen();
+        if !r1.is_zero() {
+            break r1;
+        }
+    };
+    let r2: E::Fr = rng.gen();
+
+    let a = proof.a.mul(r1.inverse().unwrap());
+
+    let mut b = proof.b.into_projective();
+    b.add_assign(&vk.delta_g2.mul(r2));
+    b.mul_assign(r1);
+
+    let mut c = proof.a.mul(r2);
+    c.add_assign_mixed(&proof.c);
+
+    Ok(Proof {
+        a: a.into_affine(),
+        b: b.into_affine(),
+        c: c.into_affine()
+    })
+}
+
 pub fn create_proof<E, C, P: ParameterSource<E>>(
     circuit: C,
-    mut params: P,
+    params: P,
     r: E::Fr,
     s: E::Fr
 ) -> Result<Proof<E>, SynthesisError>
     where E: Engine, C: Circuit<E>
 {
+    create_proof_with_worker::<E, C, P>(circuit, params, r, s, &Worker::new())
+}
+
+/// Like `create_proof`, but runs the FFTs and multiexps on `worker`
+/// instead of a new thread pool, so that concurrent proofs can share or
+/// partition the available threads.
+pub fn create_proof_with_worker<E, C, P: ParameterSource<E>>(
+    circuit: C,
+    params: P,
+    r: E::Fr,
+    s: E::Fr,
+    worker: &Worker
+) -> Result<Proof<E>, SynthesisError>
+    where E: Engine, C: Circuit<E>
+{
+    create_proof_with_observer::<E, C, P>(
+        circuit,
+        params,
+        r,
+        s,
+        worker,
+        Arc::new(NoObserver),
+        &CancellationToken::new()
+    )
+}
+
+/// The phases of proof generation, in the order they run.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ProverPhase {
+    /// Synthesizing the circuit and evaluating its linear combinations.
+    Synthesis,
+    /// Computing the coefficients of `h(x)` with FFTs.
+    Fft,
+    /// Computing the multiexps of the proof elements.
+    Multiexp
+}
+
+/// Receives progress updates during proof generation. Chunk updates are
+/// delivered from the worker's threads.
+pub trait ProverObserver: Send + Sync {
+    fn phase_started(&self, _phase: ProverPhase) {}
+
+    fn phase_finished(&self, _phase: ProverPhase) {}
+
+    /// Called when a multiexp chunk is done, with the number of chunks
+    /// completed so far out of the `total` of the proof.
+    fn multiexp_chunk_completed(&self, _completed: usize, _total: usize) {}
+}
+
+struct NoObserver;
+
+impl ProverObserver for NoObserver {}
+
+/// Cancels proof generation from another thread. Cancellation is checked
+/// between phases and before each multiexp chunk, after which the proof
+/// fails with `SynthesisError::Cancelled`.
+#[derive(Clone, Debug, Default)]
+pub struct CancellationToken(Arc<AtomicBool>);
+
+impl CancellationToken {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn cancel(&self) {
+        self.0.store(true, Ordering::SeqCst);
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.0.load(Ordering::SeqCst)
+    }
+
+    fn check(&self) -> Result<(), SynthesisError> {
+        if self.is_cancelled() {
+            Err(SynthesisError::Cancelled)
+        } else {
+            Ok(())
+        }
+    }
+}
+
+struct MultiexpProgress {
+    observer: Arc<dyn ProverObserver>,
+    cancel: CancellationToken,
+    completed: AtomicUsize,
+    total: usize
+}
+
+impl ChunkObserver for MultiexpProgress {
+    fn before_chunk(&self) -> Result<(), SynthesisError> {
+        self.cancel.check()
+    }
+
+    fn chunk_completed(&self) {
+        let completed = self.completed.fetch_add(1, Ordering::SeqCst) + 1;
+        self.observer.multiexp_chunk_completed(completed, self.total);
+    }
+}
+
+/// Like `create_proof_with_worker`, but reports progress to `observer`
+/// and stops early once `cancel` is cancelled.
+pub fn create_proof_with_observer<E, C, P: ParameterSource<E>>(
+    circuit: C,
+    params: P,
+    r: E::Fr,
+    s: E::Fr,
+    worker: &Worker,
+    observer: Arc<dyn ProverObserver>,
+    cancel: &CancellationToken
+) -> Result<Proof<E>, SynthesisError>
+    where E: Engine, C: Circuit<E>
+{
+    prove(circuit, params, r, s, worker, observer, cancel, None)
+}
+
+/// Like `create_proof_with_worker`, but bounds how much is held in
+/// memory at once. The evaluations of the A, B and C polynomials are
+/// streamed to `store` during synthesis and the assignments are moved
+/// there while the FFTs run. At most two evaluation domains are alive at
+/// a time, and their FFTs run in place.
+pub fn create_proof_low_memory<E, C, P: ParameterSource<E>, S: ProverStore<E>>(
+    circuit: C,
+    params: P,
+    r: E::Fr,
+    s: E::Fr,
+    worker: &Worker,
+    store: &mut S
+) -> Result<Proof<E>, SynthesisError>
+    where E: Engine, C: Circuit<E>
+{
+    create_proof_low_memory_with_observer::<E, C, P, S>(
+        circuit,
+        params,
+        r,
+        s,
+        worker,
+        store,
+        Arc::new(NoObserver),
+        &CancellationToken::new()
+    )
+}
+
+/// Like `create_proof_low_memory`, but reports progress to `observer`
+/// and stops early once `cancel` is cancelled.
+pub fn create_proof_low_memory_with_observer<E, C, P: ParameterSource<E>, S: ProverStore<E>>(
+    circuit: C,
+    params: P,
+    r: E::Fr,
+    s: E::Fr,
+    worker: &Worker,
+    store: &mut S,
+    observer: Arc<dyn ProverObserver>,
+    cancel: &CancellationToken
+) -> Result<Proof<E>, SynthesisError>
+    where E: Engine, C: Circuit<E>
+{
+    prove(circuit, params, r, s, worker, observer, cancel, Some(store))
+}
+
+fn prove<E, C, P: ParameterSource<E>>(
+    circuit: C,
+    mut params: P,
+    r: E::Fr,
+    s: E::Fr,
+    worker: &Worker,
+    observer: Arc<dyn ProverObserver>,
+    cancel: &CancellationToken,
+    mut store: Option<&mut dyn ProverStore<E>>
+) -> Result<Proof<E>, SynthesisError>
+    where E: Engine, C: Circuit<E>
+{
+    cancel.check()?;
+
+    if let Some(ref mut store) = store {
+        for &slot in SLOTS.iter() {
+            store.clear(slot)?;
+        }
+    }
+    observer.phase_started(ProverPhase::Synthesis);
+
     let mut prover = ProvingAssignment {
         a_aux_density: DensityTracker::new(),
         b_input_density: DensityTracker::new(),
@@ -224,7 +492,10 @@ pub fn create_proof<E, C, P: ParameterSource<E>>(
         b: vec![],
         c: vec![],
         input_assignment: vec![],
-        aux_assignment: vec![]
+        aux_assignment: vec![],
+        num_constraints: 0,
+        store: store,
+        store_error: None
     };
 
     prover.alloc_input(|| "", || Ok(E::Fr::one()))?;
@@ -239,63 +510,140 @@ pub fn create_proof<E, C, P: ParameterSource<E>>(
         );
     }
 
-    let worker = Worker::new();
+    prover.flush();
+    if let Some(e) = prover.store_error.take() {
+        return Err(e);
+    }
+
+    observer.phase_finished(ProverPhase::Synthesis);
+    cancel.check()?;
+    observer.phase_started(ProverPhase::Fft);
 
     let vk = params.get_vk(prover.input_assignment.len())?;
+    let m = params.get_domain_size(prover.num_constraints)?;
+
+    let ProvingAssignment {
+        a_aux_density,
+        b_input_density,
+        b_aux_density,
+        a,
+        b,
+        c,
+        input_assignment,
+        aux_assignment,
+        store,
+        ..
+    } = prover;
+
+    let (h, input_assignment, aux_assignment) = match store {
+        None => {
+            let mut a = EvaluationDomain::from_coeffs_with_size(a, m)?;
+            let mut b = EvaluationDomain::from_coeffs_with_size(b, m)?;
+            let mut c = EvaluationDomain::from_coeffs_with_size(c, m)?;
+            a.ifft(worker);
+            a.coset_fft(worker);
+            b.ifft(worker);
+            b.coset_fft(worker);
+            c.ifft(worker);
+            c.coset_fft(worker);
+
+            a.mul_assign(worker, &b);
+            drop(b);
+            a.sub_assign(worker, &c);
+            drop(c);
+
+            (a, input_assignment, aux_assignment)
+        },
+        Some(store) => {
+            // The evaluations are all in the store by now, and only
+            // the buffers are left.
+            drop((a, b, c));
+
+            // Only the evaluations of one polynomial are loaded at a
+            // time, while the assignments wait in the store.
+            store.append(StoreSlot::Inputs, &input_assignment)?;
+            drop(input_assignment);
+            store.append(StoreSlot::Aux, &aux_assignment)?;
+            drop(aux_assignment);
+
+            let mut coset = |slot| -> Result<EvaluationDomain<E, Scalar<E>>, SynthesisError> {
+                let evals = store.take(slot)?.into_iter().map(Scalar).collect();
+                let mut domain = EvaluationDomain::from_coeffs_with_size(evals, m)?;
+                domain.set_in_place_fft(true);
+                domain.ifft(worker);
+                domain.coset_fft(worker);
+
+                Ok(domain)
+            };
+
+            let mut a = coset(StoreSlot::A)?;
+            a.mul_assign(worker, &coset(StoreSlot::B)?);
+            a.sub_assign(worker, &coset(StoreSlot::C)?);
+
+            (a, store.take(StoreSlot::Inputs)?, store.take(StoreSlot::Aux)?)
+        }
+    };
 
     let h = {
-        let mut a = EvaluationDomain::from_coeffs(prover.a)?;
-        let mut b = EvaluationDomain::from_coeffs(prover.b)?;
-        let mut c = EvaluationDomain::from_coeffs(prover.c)?;
-        a.ifft(&worker);
-        a.coset_fft(&worker);
-        b.ifft(&worker);
-        b.coset_fft(&worker);
-        c.ifft(&worker);
-        c.coset_fft(&worker);
-
-        a.mul_assign(&worker, &b);
-        drop(b);
-        a.sub_assign(&worker, &c);
-        drop(c);
-        a.divide_by_z_on_coset(&worker);
-        a.icoset_fft(&worker);
+        let mut a = h;
+        a.divide_by_z_on_coset(worker);
+        a.icoset_fft(worker);
         let mut a = a.into_coeffs();
         let a_len = a.len() - 1;
         a.truncate(a_len);
         // TODO: parallelize if it's even helpful
-        let a = Arc::new(a.into_iter().map(|s| s.0.into_repr()).collect::<Vec<_>>());
-
-        multiexp(&worker, params.get_h(a.len())?, FullDensity, a)
+        Arc::new(a.into_iter().map(|s| s.0.into_repr()).collect::<Vec<_>>())
     };
 
-    // TODO: parallelize if it's even helpful
-    let input_assignment = Arc::new(prover.input_assignment.into_iter().map(|s| s.into_repr()).collect::<Vec<_>>());
-    let aux_assignment = Arc::new(prover.aux_assignment.into_iter().map(|s| s.into_repr()).collect::<Vec<_>>());
+    observer.phase_finished(ProverPhase::Fft);
+    cancel.check()?;
+    observer.phase_started(ProverPhase::Multiexp);
 
-    let l = multiexp(&worker, params.get_l(aux_assignment.len())?, FullDensity, aux_assignment.clone());
+    // TODO: parallelize if it's even helpful
+    let input_assignment = Arc::new(input_assignment.into_iter().map(|s| s.into_repr()).collect::<Vec<_>>());
+    let aux_assignment = Arc::new(aux_assignment.into_iter().map(|s| s.into_repr()).collect::<Vec<_>>());
 
-    let a_aux_density_total = prover.a_aux_density.get_total_density();
+    let a_aux_density = Arc::new(a_aux_density);
+    let a_aux_density_total = a_aux_density.get_total_density();
+    let b_input_density = Arc::new(b_input_density);
+    let b_input_density_total = b_input_density.get_total_density();
+    let b_aux_density = Arc::new(b_aux_density);
+    let b_aux_density_total = b_aux_density.get_total_density();
 
+    let h_source = params.get_h(h.len())?;
+    let l_source = params.get_l(aux_assignment.len())?;
     let (a_inputs_source, a_aux_source) = params.get_a(input_assignment.len(), a_aux_density_total)?;
+    let (b_g1_inputs_source, b_g1_aux_source) = params.get_b_g1(b_input_density_total, b_aux_density_total)?;
+    let (b_g2_inputs_source, b_g2_aux_source) = params.get_b_g2(b_input_density_total, b_aux_density_total)?;
 
-    let a_inputs = multiexp(&worker, a_inputs_source, FullDensity, input_assignment.clone());
-    let a_aux = multiexp(&worker, a_aux_source, Arc::new(prover.a_aux_density), aux_assignment.clone());
+    let (num_inputs, num_aux) = (input_assignment.len(), aux_assignment.len());
+    let progress: Option<Arc<dyn ChunkObserver>> = Some(Arc::new(MultiexpProgress {
+        observer: observer.clone(),
+        cancel: cancel.clone(),
+        completed: AtomicUsize::new(0),
+        total: multiexp_chunks(worker, &h_source, h.len()) +
+               multiexp_chunks(worker, &l_source, num_aux) +
+               multiexp_chunks(worker, &a_inputs_source, num_inputs) +
+               multiexp_chunks(worker, &a_aux_source, num_aux) +
+               multiexp_chunks(worker, &b_g1_inputs_source, num_inputs) +
+               multiexp_chunks(worker, &b_g1_aux_source, num_aux) +
+               multiexp_chunks(worker, &b_g2_inputs_source, num_inputs) +
+               multiexp_chunks(worker, &b_g2_aux_source, num_aux)
+    }));
 
-    let b_input_density = Arc::new(prover.b_input_density);
-    let b_input_density_total = b_input_density.get_total_density();
-    let b_aux_density = Arc::new(prover.b_aux_density);
-    let b_aux_density_total = b_aux_density.get_total_density();
+    let accumulation = params.get_accumulation();
 
-    let (b_g1_inputs_source, b_g1_aux_source) = params.get_b_g1(b_input_density_total, b_aux_density_total)?;
+    let h = multiexp_observed(worker, h_source, FullDensity, h, accumulation, progress.clone());
+    let l = multiexp_observed(worker, l_source, FullDensity, aux_assignment.clone(), accumulation, progress.clone());
 
-    let b_g1_inputs = multiexp(&worker, b_g1_inputs_source, b_input_density.clone(), input_assignment.clone());
-    let b_g1_aux = multiexp(&worker, b_g1_aux_source, b_aux_density.clone(), aux_assignment.clone());
+    let a_inputs = multiexp_observed(worker, a_inputs_source, FullDensity, input_assignment.clone(), accumulation, progress.clone());
+    let a_aux = multiexp_observed(worker, a_aux_source, a_aux_density, aux_assignment.clone(), accumulation, progress.clone());
 
-    let (b_g2_inputs_source, b_g2_aux_source) = params.get_b_g2(b_input_density_total, b_aux_density_total)?;
-    
-    let b_g2_inputs = multiexp(&worker, b_g2_inputs_source, b_input_density, input_assignment);
-    let b_g2_aux = multiexp(&worker, b_g2_aux_source, b_aux_density, aux_assignment);
+    let b_g1_inputs = multiexp_observed(worker, b_g1_inputs_source, b_input_density.clone(), input_assignment.clone(), accumulation, progress.clone());
+    let b_g1_aux = multiexp_observed(worker, b_g1_aux_source, b_aux_density.clone(), aux_assignment.clone(), accumulation, progress.clone());
+
+    let b_g2_inputs = multiexp_observed(worker, b_g2_inputs_source, b_input_density, input_assignment, accumulation, progress.clone());
+    let b_g2_aux = multiexp_observed(worker, b_g2_aux_source, b_aux_density, aux_assignment, accumulation, progress.clone());
 
     if vk.delta_g1.is_zero() || vk.delta_g2.is_zero() {
         // If this element is zero, someone is trying to perform a
@@ -333,6 +681,8 @@ pub fn create_proof<E, C, P: ParameterSource<E>>(
     g_c.add_assign(&h.wait()?);
     g_c.add_assign(&l.wait()?);
 
+    observer.phase_finished(ProverPhase::Multiexp);
+
     Ok(Proof {
         a: g_a.into_affine(),
         b: g_b.into_affine(),
@@ -0,0 +1,219 @@
+//! Storage for the vectors the low-memory prover sets aside.
+//!
+//! `create_proof_low_memory` streams the evaluations of the A, B and C
+//! polynomials to a `ProverStore` while the circuit is synthesized, and
+//! moves the assignments there while the FFTs run, so that they never
+//! share memory with the evaluation domains. `MemoryStore` keeps them in
+//! memory and `FileStore` spills them to disk.
+
+use pairing::Engine;
+
+#[cfg(feature = "std")]
+use pairing::PrimeField;
+
+use rstd::prelude::*;
+
+#[cfg(feature = "std")]
+use std::fs::{self, File, OpenOptions};
+#[cfg(feature = "std")]
+use std::io::{self, BufReader, Seek, SeekFrom, Write};
+#[cfg(feature = "std")]
+use std::path::{Path, PathBuf};
+
+#[cfg(feature = "std")]
+use byteorder::{LittleEndian, ReadBytesExt, WriteBytesExt};
+
+use ::{
+    SynthesisError
+};
+
+/// The vectors stored by the low-memory prover.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum StoreSlot {
+    /// Evaluations of the A polynomial at every constraint.
+    A,
+    /// Evaluations of the B polynomial at every constraint.
+    B,
+    /// Evaluations of the C polynomial at every constraint.
+    C,
+    /// Assignments of the inputs, starting with the "one" input.
+    Inputs,
+    /// Assignments of the auxiliary variables.
+    Aux
+}
+
+const NUM_SLOTS: usize = 5;
+
+pub(crate) const SLOTS: [StoreSlot; NUM_SLOTS] = [
+    StoreSlot::A,
+    StoreSlot::B,
+    StoreSlot::C,
+    StoreSlot::Inputs,
+    StoreSlot::Aux
+];
+
+impl StoreSlot {
+    fn index(self) -> usize {
+        self as usize
+    }
+
+    #[cfg(feature = "std")]
+    fn name(self) -> &'static str {
+        match self {
+            StoreSlot::A => "a",
+            StoreSlot::B => "b",
+            StoreSlot::C => "c",
+            StoreSlot::Inputs => "inputs",
+            StoreSlot::Aux => "aux"
+        }
+    }
+}
+
+/// Where the low-memory prover puts vectors it does not need until a
+/// later phase.
+pub trait ProverStore<E: Engine> {
+    /// Appends `values` to the end of `slot`.
+    fn append(&mut self, slot: StoreSlot, values: &[E::Fr]) -> Result<(), SynthesisError>;
+
+    /// Removes everything appended to `slot` and returns it in order.
+    fn take(&mut self, slot: StoreSlot) -> Result<Vec<E::Fr>, SynthesisError>;
+
+    /// Discards everything appended to `slot`, such as what a failed
+    /// proof left behind.
+    fn clear(&mut self, slot: StoreSlot) -> Result<(), SynthesisError> {
+        self.take(slot).map(|_| ())
+    }
+}
+
+/// A `ProverStore` that keeps the vectors in memory, for targets
+/// without a filesystem.
+pub struct MemoryStore<E: Engine> {
+    slots: Vec<Vec<E::Fr>>
+}
+
+impl<E: Engine> MemoryStore<E> {
+    pub fn new() -> Self {
+        MemoryStore {
+            slots: vec![vec![]; NUM_SLOTS]
+        }
+    }
+}
+
+impl<E: Engine> Default for MemoryStore<E> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<E: Engine> ProverStore<E> for MemoryStore<E> {
+    fn append(&mut self, slot: StoreSlot, values: &[E::Fr]) -> Result<(), SynthesisError> {
+        self.slots[slot.index()].extend_from_slice(values);
+
+        Ok(())
+    }
+
+    fn take(&mut self, slot: StoreSlot) -> Result<Vec<E::Fr>, SynthesisError> {
+        Ok(::rstd::mem::take(&mut self.slots[slot.index()]))
+    }
+}
+
+/// A `ProverStore` that spills the vectors to files in a directory.
+/// The files are removed when the store is dropped.
+#[cfg(feature = "std")]
+pub struct FileStore {
+    dir: PathBuf,
+    files: Vec<Option<File>>
+}
+
+#[cfg(feature = "std")]
+impl FileStore {
+    /// Stores the vectors in `dir`, which must exist and must not be
+    /// used by another `FileStore` at the same time.
+    pub fn new<P: AsRef<Path>>(dir: P) -> Self {
+        FileStore {
+            dir: dir.as_ref().to_path_buf(),
+            files: (0..NUM_SLOTS).map(|_| None).collect()
+        }
+    }
+
+    fn path(&self, slot: StoreSlot) -> PathBuf {
+        self.dir.join(format!("bellman-{}.bin", slot.name()))
+    }
+
+    fn file(&mut self, slot: StoreSlot) -> io::Result<&mut File> {
+        if self.files[slot.index()].is_none() {
+            let file = OpenOptions::new()
+                .read(true)
+                .write(true)
+                .create(true)
+                .truncate(true)
+                .open(self.path(slot))?;
+            self.files[slot.index()] = Some(file);
+        }
+
+        Ok(self.files[slot.index()].as_mut().unwrap())
+    }
+}
+
+#[cfg(feature = "std")]
+impl<E: Engine> ProverStore<E> for FileStore {
+    fn append(&mut self, slot: StoreSlot, values: &[E::Fr]) -> Result<(), SynthesisError> {
+        let limbs = <E::Fr as PrimeField>::Repr::default().as_ref().len();
+        let mut buf = Vec::with_capacity(values.len() * limbs * 8);
+        for value in values {
+            for limb in value.into_repr().as_ref() {
+                buf.write_u64::<LittleEndian>(*limb)?;
+            }
+        }
+
+        self.file(slot)?.write_all(&buf)?;
+
+        Ok(())
+    }
+
+    fn take(&mut self, slot: StoreSlot) -> Result<Vec<E::Fr>, SynthesisError> {
+        let limbs = <E::Fr as PrimeField>::Repr::default().as_ref().len();
+        let file = self.file(slot)?;
+        let len = file.seek(SeekFrom::End(0))? as usize / (limbs * 8);
+        file.seek(SeekFrom::Start(0))?;
+
+        let mut values = Vec::with_capacity(len);
+        {
+            let mut reader = BufReader::new(&mut *file);
+            for _ in 0..len {
+                let mut repr = <E::Fr as PrimeField>::Repr::default();
+                for limb in repr.as_mut() {
+                    *limb = reader.read_u64::<LittleEndian>()?;
+                }
+
+                values.push(E::Fr::from_repr(repr).map_err(|e| {
+                    io::Error::new(io::ErrorKind::InvalidData, e)
+                })?);
+            }
+        }
+
+        file.set_len(0)?;
+        file.seek(SeekFrom::Start(0))?;
+
+        Ok(values)
+    }
+
+    fn clear(&mut self, slot: StoreSlot) -> Result<(), SynthesisError> {
+        let file = self.file(slot)?;
+        file.set_len(0)?;
+        file.seek(SeekFrom::Start(0))?;
+
+        Ok(())
+    }
+}
+
+#[cfg(feature = "std")]
+impl Drop for FileStore {
+    fn drop(&mut self) {
+        for &slot in SLOTS.iter() {
+            if self.files[slot.index()].take().is_some() {
+                let _ = fs::remove_file(self.path(slot));
+            }
+        }
+    }
+}
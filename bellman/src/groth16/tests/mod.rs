@@ -22,6 +22,69 @@ use super::{
     verify_proof
 };
 
+/// Proves knowledge of `a` and `b` with `a * b = c` for a public `c`. Shared
+/// by the tests that only need some small circuit to produce parameters and
+/// proofs.
+pub(crate) struct MySillyCircuit<E: Engine> {
+    pub a: Option<E::Fr>,
+    pub b: Option<E::Fr>
+}
+
+impl<E: Engine> Circuit<E> for MySillyCircuit<E> {
+    fn synthesize<CS: ConstraintSystem<E>>(
+        self,
+        cs: &mut CS
+    ) -> Result<(), SynthesisError>
+    {
+        let a = cs.alloc(|| "a", || self.a.ok_or(SynthesisError::AssignmentMissing))?;
+        let b = cs.alloc(|| "b", || self.b.ok_or(SynthesisError::AssignmentMissing))?;
+        let c = cs.alloc_input(|| "c", || {
+            let mut a = self.a.ok_or(SynthesisError::AssignmentMissing)?;
+            let b = self.b.ok_or(SynthesisError::AssignmentMissing)?;
+
+            a.mul_assign(&b);
+            Ok(a)
+        })?;
+
+        cs.enforce(
+            || "a*b=c",
+            |lc| lc + a,
+            |lc| lc + b,
+            |lc| lc + c
+        );
+
+        Ok(())
+    }
+}
+
+/// Proves knowledge of `x` with `x^3 = y` for a public `y`.
+pub(crate) struct Cube<E: Engine> {
+    pub x: Option<E::Fr>
+}
+
+impl<E: Engine> Circuit<E> for Cube<E> {
+    fn synthesize<CS: ConstraintSystem<E>>(
+        self,
+        cs: &mut CS
+    ) -> Result<(), SynthesisError>
+    {
+        let x_val = self.x;
+        let x2_val = x_val.map(|mut x| { x.square(); x });
+        let x = cs.alloc(|| "x", || x_val.ok_or(SynthesisError::AssignmentMissing))?;
+        let x2 = cs.alloc(|| "x2", || x2_val.ok_or(SynthesisError::AssignmentMissing))?;
+        let y = cs.alloc_input(|| "y", || {
+            let mut y = x2_val.ok_or(SynthesisError::AssignmentMissing)?;
+            y.mul_assign(&x_val.unwrap());
+            Ok(y)
+        })?;
+
+        cs.enforce(|| "x*x=x2", |lc| lc + x, |lc| lc + x, |lc| lc + x2);
+        cs.enforce(|| "x2*x=y", |lc| lc + x2, |lc| lc + x, |lc| lc + y);
+
+        Ok(())
+    }
+}
+
 struct XORDemo<E: Engine> {
     a: Option<bool>,
     b: Option<bool>,
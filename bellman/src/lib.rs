@@ -3,7 +3,7 @@
 
 extern crate pairing;
 extern crate rand;
-#[cfg(feature = "multithread")]
+#[cfg(any(feature = "multithread", feature = "multithread-rayon"))]
 extern crate num_cpus;
 extern crate futures;
 #[cfg(feature = "multithread")]
@@ -11,7 +11,13 @@ extern crate futures_cpupool;
 extern crate bit_vec;
 #[cfg(feature = "multithread")]
 extern crate crossbeam;
+#[cfg(feature = "multithread-rayon")]
+extern crate rayon;
 extern crate byteorder;
+#[cfg(feature = "std")]
+extern crate memmap;
+#[cfg(feature = "std")]
+extern crate blake2_rfc;
 #[macro_use]
 extern crate parity_codec_derive;
 extern crate parity_codec as codec;
@@ -27,9 +33,14 @@ extern crate sr_io as runtime_io;
 extern crate alloc;
 
 pub mod multicore;
-mod multiexp;
+pub mod multiexp;
 pub mod domain;
+pub mod polynomial;
 pub mod groth16;
+#[cfg(feature = "std")]
+pub mod metric;
+#[cfg(feature = "std")]
+pub mod r1cs;
 
 use pairing::{Engine, Field};
 use rstd::prelude::*;
@@ -210,7 +221,9 @@ pub enum SynthesisError {
     /// During verification, our verifying key was malformed.
     MalformedVerifyingKey,
     /// During CRS generation, we observed an unconstrained auxillary variable
-    UnconstrainedVariable
+    UnconstrainedVariable,
+    /// During proof generation, the proof was cancelled
+    Cancelled
 }
 
 impl SynthesisError {
@@ -227,7 +240,8 @@ impl SynthesisError {
             #[cfg(not(feature = "std"))]
             SynthesisError::IoError => "encountered an I/O error",
             SynthesisError::MalformedVerifyingKey => "malformed verifying key",
-            SynthesisError::UnconstrainedVariable => "auxillary variable was unconstrained"
+            SynthesisError::UnconstrainedVariable => "auxillary variable was unconstrained",
+            SynthesisError::Cancelled => "proof generation was cancelled"
         }
     }
 }
@@ -0,0 +1,408 @@
+//! A constraint system that measures the cost of a circuit.
+//!
+//! `MetricConstraintSystem` keeps the namespaces the circuit pushes and
+//! records, for each of them, how many constraints, auxiliary variables,
+//! inputs and linear combination terms were created inside it. Assignments
+//! are never evaluated, so circuits can be measured without a witness.
+
+use pairing::Engine;
+
+use std::fmt::Write;
+use std::marker::PhantomData;
+
+use ::{
+    ConstraintSystem,
+    Index,
+    LinearCombination,
+    SynthesisError,
+    Variable
+};
+
+/// Costs recorded for a namespace.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Metrics {
+    /// Number of constraints enforced.
+    pub constraints: usize,
+    /// Number of auxiliary (private) variables allocated.
+    pub aux: usize,
+    /// Number of inputs allocated, not counting the "one" input.
+    pub inputs: usize,
+    /// Total number of terms over the `A`, `B` and `C` linear
+    /// combinations of every constraint.
+    pub lc_terms: usize
+}
+
+impl Metrics {
+    fn add_assign(&mut self, other: &Metrics) {
+        self.constraints += other.constraints;
+        self.aux += other.aux;
+        self.inputs += other.inputs;
+        self.lc_terms += other.lc_terms;
+    }
+}
+
+/// A namespace in the cost tree. Namespaces pushed more than once under
+/// the same parent with the same name are merged.
+#[derive(Clone, Debug, PartialEq)]
+pub struct MetricNode {
+    name: String,
+    own: Metrics,
+    children: Vec<MetricNode>
+}
+
+impl MetricNode {
+    fn new(name: String) -> Self {
+        MetricNode {
+            name: name,
+            own: Metrics::default(),
+            children: vec![]
+        }
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Costs recorded directly in this namespace, excluding children.
+    pub fn own(&self) -> Metrics {
+        self.own
+    }
+
+    /// Costs of this namespace including all of its children.
+    pub fn total(&self) -> Metrics {
+        let mut total = self.own;
+        for child in &self.children {
+            total.add_assign(&child.total());
+        }
+
+        total
+    }
+
+    pub fn children(&self) -> &[MetricNode] {
+        &self.children
+    }
+
+    /// Finds a child namespace by name.
+    pub fn child(&self, name: &str) -> Option<&MetricNode> {
+        self.children.iter().find(|c| c.name == name)
+    }
+
+    fn pretty_print(&self, s: &mut String, depth: usize) {
+        let total = self.total();
+
+        writeln!(
+            s,
+            "{:indent$}{}: constraints={} aux={} inputs={} lc_terms={}",
+            "",
+            if depth == 0 { "<root>" } else { &self.name },
+            total.constraints,
+            total.aux,
+            total.inputs,
+            total.lc_terms,
+            indent = depth * 2
+        ).unwrap();
+
+        for child in &self.children {
+            child.pretty_print(s, depth + 1);
+        }
+    }
+
+    fn to_json(&self, s: &mut String) {
+        let total = self.total();
+
+        s.push_str("{\"name\":\"");
+        for c in self.name.chars() {
+            match c {
+                '"' => s.push_str("\\\""),
+                '\\' => s.push_str("\\\\"),
+                c if (c as u32) < 0x20 => write!(s, "\\u{:04x}", c as u32).unwrap(),
+                c => s.push(c)
+            }
+        }
+        write!(
+            s,
+            "\",\"constraints\":{},\"aux\":{},\"inputs\":{},\"lc_terms\":{},\"children\":[",
+            total.constraints,
+            total.aux,
+            total.inputs,
+            total.lc_terms
+        ).unwrap();
+
+        for (i, child) in self.children.iter().enumerate() {
+            if i != 0 {
+                s.push(',');
+            }
+            child.to_json(s);
+        }
+
+        s.push_str("]}");
+    }
+}
+
+/// Constraint system that records the cost of a circuit per namespace.
+pub struct MetricConstraintSystem<E: Engine> {
+    root: MetricNode,
+    // Indices of the children leading to the current namespace.
+    current_namespace: Vec<usize>,
+    num_inputs: usize,
+    num_aux: usize,
+    _marker: PhantomData<E>
+}
+
+impl<E: Engine> Default for MetricConstraintSystem<E> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<E: Engine> MetricConstraintSystem<E> {
+    pub fn new() -> MetricConstraintSystem<E> {
+        MetricConstraintSystem {
+            root: MetricNode::new(String::new()),
+            current_namespace: vec![],
+            // The "one" input.
+            num_inputs: 1,
+            num_aux: 0,
+            _marker: PhantomData
+        }
+    }
+
+    /// The root of the cost tree. Its name is empty.
+    pub fn root(&self) -> &MetricNode {
+        &self.root
+    }
+
+    /// Costs of the whole circuit.
+    pub fn total(&self) -> Metrics {
+        self.root.total()
+    }
+
+    pub fn num_constraints(&self) -> usize {
+        self.total().constraints
+    }
+
+    /// Finds the namespace at `path`, with namespaces separated by `/`
+    /// as in `TestConstraintSystem`. The empty path is the root.
+    pub fn get(&self, path: &str) -> Option<&MetricNode> {
+        let mut node = &self.root;
+
+        if !path.is_empty() {
+            for name in path.split('/') {
+                node = node.child(name)?;
+            }
+        }
+
+        Some(node)
+    }
+
+    /// Renders the cost tree with one namespace per line, indented by
+    /// depth. Each line shows the totals of the namespace.
+    pub fn pretty_print(&self) -> String {
+        let mut s = String::new();
+        self.root.pretty_print(&mut s, 0);
+
+        s
+    }
+
+    /// Renders the cost tree as JSON. Each namespace is an object with
+    /// its `name`, its totals and its `children`.
+    pub fn to_json(&self) -> String {
+        let mut s = String::new();
+        self.root.to_json(&mut s);
+
+        s
+    }
+
+    fn current(&mut self) -> &mut MetricNode {
+        let mut node = &mut self.root;
+        for &i in &self.current_namespace {
+            node = &mut {node}.children[i];
+        }
+
+        node
+    }
+}
+
+impl<E: Engine> ConstraintSystem<E> for MetricConstraintSystem<E> {
+    type Root = Self;
+
+    fn alloc<F, A, AR>(
+        &mut self,
+        _: A,
+        _: F
+    ) -> Result<Variable, SynthesisError>
+        where F: FnOnce() -> Result<E::Fr, SynthesisError>, A: FnOnce() -> AR, AR: Into<String>
+    {
+        let index = self.num_aux;
+        self.num_aux += 1;
+        self.current().own.aux += 1;
+
+        Ok(Variable(Index::Aux(index)))
+    }
+
+    fn alloc_input<F, A, AR>(
+        &mut self,
+        _: A,
+        _: F
+    ) -> Result<Variable, SynthesisError>
+        where F: FnOnce() -> Result<E::Fr, SynthesisError>, A: FnOnce() -> AR, AR: Into<String>
+    {
+        let index = self.num_inputs;
+        self.num_inputs += 1;
+        self.current().own.inputs += 1;
+
+        Ok(Variable(Index::Input(index)))
+    }
+
+    fn enforce<A, AR, LA, LB, LC>(
+        &mut self,
+        _: A,
+        a: LA,
+        b: LB,
+        c: LC
+    )
+        where A: FnOnce() -> AR, AR: Into<String>,
+              LA: FnOnce(LinearCombination<E>) -> LinearCombination<E>,
+              LB: FnOnce(LinearCombination<E>) -> LinearCombination<E>,
+              LC: FnOnce(LinearCombination<E>) -> LinearCombination<E>
+    {
+        let terms = a(LinearCombination::zero()).as_ref().len() +
+                    b(LinearCombination::zero()).as_ref().len() +
+                    c(LinearCombination::zero()).as_ref().len();
+
+        let node = self.current();
+        node.own.constraints += 1;
+        node.own.lc_terms += terms;
+    }
+
+    fn push_namespace<NR, N>(&mut self, name_fn: N)
+        where NR: Into<String>, N: FnOnce() -> NR
+    {
+        let name = name_fn().into();
+
+        if name.chars().any(|a| a == '/') {
+            panic!("'/' is not allowed in names");
+        }
+
+        let node = self.current();
+        let index = match node.children.iter().position(|c| c.name == name) {
+            Some(index) => index,
+            None => {
+                node.children.push(MetricNode::new(name));
+                node.children.len() - 1
+            }
+        };
+
+        self.current_namespace.push(index);
+    }
+
+    fn pop_namespace(&mut self)
+    {
+        assert!(self.current_namespace.pop().is_some());
+    }
+
+    fn get_root(&mut self) -> &mut Self::Root
+    {
+        self
+    }
+}
+
+#[cfg(test)]
+mod test_with_bls12_381 {
+    use super::*;
+    use {Circuit};
+
+    use pairing::Field;
+    use pairing::bls12_381::Bls12;
+
+    // Computes x^(2^n) with one squaring per namespace, then exposes the
+    // result.
+    struct Squarings {
+        n: usize
+    }
+
+    impl<E: Engine> Circuit<E> for Squarings {
+        fn synthesize<CS: ConstraintSystem<E>>(
+            self,
+            cs: &mut CS
+        ) -> Result<(), SynthesisError>
+        {
+            let mut x = cs.alloc(|| "x", || Ok(E::Fr::one()))?;
+
+            {
+                let mut cs = cs.namespace(|| "squarings");
+
+                for i in 0..self.n {
+                    let mut cs = cs.namespace(|| format!("square {}", i));
+                    let y = cs.alloc(|| "y", || Ok(E::Fr::one()))?;
+
+                    cs.enforce(
+                        || "x*x=y",
+                        |lc| lc + x,
+                        |lc| lc + x,
+                        |lc| lc + y
+                    );
+
+                    x = y;
+                }
+            }
+
+            let mut cs = cs.namespace(|| "output");
+            let out = cs.alloc_input(|| "out", || Ok(E::Fr::one()))?;
+
+            cs.enforce(
+                || "x=out",
+                |lc| lc + x - out,
+                |lc| lc + CS::one(),
+                |lc| lc
+            );
+
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn metrics() {
+        let mut cs = MetricConstraintSystem::<Bls12>::new();
+        Circuit::<Bls12>::synthesize(Squarings { n: 3 }, &mut cs).unwrap();
+
+        assert_eq!(cs.num_constraints(), 4);
+        assert_eq!(cs.total(), Metrics { constraints: 4, aux: 4, inputs: 1, lc_terms: 3 * 3 + 3 });
+        assert_eq!(cs.root().own(), Metrics { constraints: 0, aux: 1, inputs: 0, lc_terms: 0 });
+
+        let squarings = cs.get("squarings").unwrap();
+        assert_eq!(squarings.children().len(), 3);
+        assert_eq!(squarings.own(), Metrics::default());
+        assert_eq!(squarings.total(), Metrics { constraints: 3, aux: 3, inputs: 0, lc_terms: 9 });
+        assert_eq!(
+            cs.get("squarings/square 1").unwrap().total(),
+            Metrics { constraints: 1, aux: 1, inputs: 0, lc_terms: 3 }
+        );
+        assert!(cs.get("squarings/square 3").is_none());
+        assert_eq!(cs.get("").unwrap().total(), cs.total());
+
+        // Namespaces entered again are merged.
+        Circuit::<Bls12>::synthesize(Squarings { n: 1 }, &mut cs.namespace(|| "output")).unwrap();
+        assert_eq!(cs.get("output").unwrap().children().len(), 2);
+        assert_eq!(cs.get("output").unwrap().total().constraints, 1 + 2);
+
+        let mut cs = MetricConstraintSystem::<Bls12>::new();
+        Circuit::<Bls12>::synthesize(Squarings { n: 1 }, &mut cs).unwrap();
+
+        assert_eq!(
+            cs.pretty_print(),
+            "<root>: constraints=2 aux=2 inputs=1 lc_terms=6\n\
+             \x20 squarings: constraints=1 aux=1 inputs=0 lc_terms=3\n\
+             \x20   square 0: constraints=1 aux=1 inputs=0 lc_terms=3\n\
+             \x20 output: constraints=1 aux=0 inputs=1 lc_terms=3\n"
+        );
+
+        assert_eq!(
+            cs.to_json(),
+            "{\"name\":\"\",\"constraints\":2,\"aux\":2,\"inputs\":1,\"lc_terms\":6,\"children\":[\
+             {\"name\":\"squarings\",\"constraints\":1,\"aux\":1,\"inputs\":0,\"lc_terms\":3,\"children\":[\
+             {\"name\":\"square 0\",\"constraints\":1,\"aux\":1,\"inputs\":0,\"lc_terms\":3,\"children\":[]}]},\
+             {\"name\":\"output\",\"constraints\":1,\"aux\":0,\"inputs\":1,\"lc_terms\":3,\"children\":[]}]}"
+        );
+    }
+}
@@ -1,40 +1,154 @@
 //! This is an interface for dealing with the kinds of
 //! parallel computations involved in bellman. It's
-//! currently just a thin wrapper around CpuPool and
-//! crossbeam but may be extended in the future to
-//! allow for various parallelism strategies.
+//! currently just a thin wrapper around a thread pool
+//! and scoped threads. The `multithread` feature uses
+//! CpuPool and crossbeam, and the `multithread-rayon`
+//! feature uses a rayon work-stealing pool for both
+//! (taking precedence if both are enabled). Without
+//! either, all work runs on the calling thread.
 
 use futures::{Future, IntoFuture, Poll};
-#[cfg(feature = "multithread")]
+#[cfg(any(feature = "multithread", feature = "multithread-rayon"))]
 use num_cpus;
-#[cfg(feature = "multithread")]
-use futures_cpupool::{CpuPool, CpuFuture};
-#[cfg(feature = "multithread")]
+#[cfg(all(feature = "multithread", not(feature = "multithread-rayon")))]
+use futures_cpupool::{Builder as CpuPoolBuilder, CpuPool, CpuFuture};
+#[cfg(all(feature = "multithread", not(feature = "multithread-rayon")))]
 use crossbeam::{self, Scope};
-#[cfg(not(feature = "multithread"))]
+#[cfg(feature = "multithread-rayon")]
+use futures::Async;
+#[cfg(feature = "multithread-rayon")]
+use futures::sync::oneshot;
+#[cfg(feature = "multithread-rayon")]
+use rayon;
+#[cfg(feature = "multithread-rayon")]
+use std::sync::Arc;
+#[cfg(not(any(feature = "multithread", feature = "multithread-rayon")))]
 use futures::future::{result, FutureResult};
+#[cfg(any(feature = "multithread", feature = "multithread-rayon"))]
+use std::env;
+#[cfg(not(feature = "std"))]
+use alloc::string::String;
 
-#[cfg(feature = "multithread")]
+/// Environment variable that overrides the number of threads of workers
+/// whose thread count is not set explicitly.
+pub const NUM_CPUS_ENV: &str = "BELLMAN_NUM_CPUS";
+
+/// Configures the thread pool of a `Worker`.
+///
+/// Without the `multithread` or `multithread-rayon` feature every
+/// `Worker` runs on the calling thread and these settings are ignored.
+#[derive(Clone, Debug, Default)]
+pub struct WorkerBuilder {
+    num_threads: Option<usize>,
+    stack_size: Option<usize>,
+    name_prefix: Option<String>
+}
+
+impl WorkerBuilder {
+    pub fn new() -> WorkerBuilder {
+        WorkerBuilder::default()
+    }
+
+    /// Sets the number of threads. Defaults to the value of
+    /// `BELLMAN_NUM_CPUS` if it is set, and to the number of CPUs
+    /// otherwise.
+    pub fn num_threads(mut self, num_threads: usize) -> WorkerBuilder {
+        assert!(num_threads > 0, "a worker needs at least one thread");
+        self.num_threads = Some(num_threads);
+        self
+    }
+
+    /// Sets the stack size of each thread, in bytes.
+    pub fn stack_size(mut self, stack_size: usize) -> WorkerBuilder {
+        self.stack_size = Some(stack_size);
+        self
+    }
+
+    /// Names the threads with this prefix followed by their index.
+    pub fn name_prefix<S: Into<String>>(mut self, name_prefix: S) -> WorkerBuilder {
+        self.name_prefix = Some(name_prefix.into());
+        self
+    }
+
+    #[cfg(all(feature = "multithread", not(feature = "multithread-rayon")))]
+    pub fn build(self) -> Worker {
+        let cpus = self.num_threads.unwrap_or_else(default_num_threads);
+
+        let mut pool = CpuPoolBuilder::new();
+        pool.pool_size(cpus);
+        if let Some(stack_size) = self.stack_size {
+            pool.stack_size(stack_size);
+        }
+        if let Some(name_prefix) = self.name_prefix {
+            pool.name_prefix(name_prefix);
+        }
+
+        Worker {
+            cpus: cpus,
+            pool: pool.create()
+        }
+    }
+
+    #[cfg(feature = "multithread-rayon")]
+    pub fn build(self) -> Worker {
+        let cpus = self.num_threads.unwrap_or_else(default_num_threads);
+
+        let mut pool = rayon::ThreadPoolBuilder::new().num_threads(cpus);
+        if let Some(stack_size) = self.stack_size {
+            pool = pool.stack_size(stack_size);
+        }
+        if let Some(name_prefix) = self.name_prefix {
+            pool = pool.thread_name(move |i| format!("{}{}", name_prefix, i));
+        }
+
+        Worker {
+            cpus: cpus,
+            pool: Arc::new(pool.build().expect("failed to create the thread pool"))
+        }
+    }
+
+    #[cfg(not(any(feature = "multithread", feature = "multithread-rayon")))]
+    pub fn build(self) -> Worker {
+        Worker {}
+    }
+}
+
+#[cfg(any(feature = "multithread", feature = "multithread-rayon"))]
+fn default_num_threads() -> usize {
+    env::var(NUM_CPUS_ENV).ok()
+        .and_then(|n| num_threads_override(&n))
+        .unwrap_or_else(num_cpus::get)
+}
+
+/// The thread count requested by a value of `BELLMAN_NUM_CPUS`, if it is
+/// a positive integer.
+#[cfg(any(test, feature = "multithread", feature = "multithread-rayon"))]
+fn num_threads_override(var: &str) -> Option<usize> {
+    var.parse().ok()
+        .filter(|n| *n > 0)
+}
+
+#[cfg(all(feature = "multithread", not(feature = "multithread-rayon")))]
 #[derive(Clone)]
 pub struct Worker {
     cpus: usize,
     pool: CpuPool
 }
 
-#[cfg(feature = "multithread")]
+#[cfg(all(feature = "multithread", not(feature = "multithread-rayon")))]
 impl Worker {
-    // We don't expose this outside the library so that
-    // all `Worker` instances have the same number of
-    // CPUs configured.
-    pub(crate) fn new_with_cpus(cpus: usize) -> Worker {
-        Worker {
-            cpus: cpus,
-            pool: CpuPool::new(cpus)
-        }
+    /// Creates a worker with the default configuration of
+    /// `WorkerBuilder`.
+    pub fn new() -> Worker {
+        Self::builder().build()
     }
 
-    pub fn new() -> Worker {
-        Self::new_with_cpus(num_cpus::get())
+    pub fn builder() -> WorkerBuilder {
+        WorkerBuilder::new()
+    }
+
+    pub fn num_threads(&self) -> usize {
+        self.cpus
     }
 
     pub fn log_num_cpus(&self) -> u32 {
@@ -74,20 +188,28 @@ impl Worker {
     }
 }
 
-#[cfg(feature = "multithread")]
+#[cfg(all(feature = "multithread", not(feature = "multithread-rayon")))]
 pub struct WorkerFuture<T, E> {
     future: CpuFuture<T, E>
 }
 
-#[cfg(not(feature = "multithread"))]
+#[cfg(not(any(feature = "multithread", feature = "multithread-rayon")))]
 #[derive(Clone)]
 pub struct Worker {}
 
- #[cfg(not(feature = "multithread"))]
+ #[cfg(not(any(feature = "multithread", feature = "multithread-rayon")))]
 impl Worker {
 
      pub fn new() -> Worker { Worker {} } 
 
+    pub fn builder() -> WorkerBuilder {
+        WorkerBuilder::new()
+    }
+
+    pub fn num_threads(&self) -> usize {
+        1
+    }
+
      pub fn log_num_cpus(&self) -> u32 {
         log2_floor(1)
     }
@@ -122,11 +244,11 @@ impl Worker {
      }
 }
 
- #[cfg(not(feature = "multithread"))]
+ #[cfg(not(any(feature = "multithread", feature = "multithread-rayon")))]
 pub struct Scope {
 }
 
- #[cfg(not(feature = "multithread"))]
+ #[cfg(not(any(feature = "multithread", feature = "multithread-rayon")))]
 impl Scope {
 pub fn spawn<F, T>(&self, f: F) -> T  where
         F: FnOnce() -> T + Send , T: Send 
@@ -136,12 +258,115 @@ pub fn spawn<F, T>(&self, f: F) -> T  where
 
  }
 
- #[cfg(not(feature = "multithread"))]
+ #[cfg(not(any(feature = "multithread", feature = "multithread-rayon")))]
 pub struct WorkerFuture<T, E> {
     future: FutureResult<T, E>
 }
 
 
+#[cfg(feature = "multithread-rayon")]
+#[derive(Clone)]
+pub struct Worker {
+    cpus: usize,
+    pool: Arc<rayon::ThreadPool>
+}
+
+#[cfg(feature = "multithread-rayon")]
+impl Worker {
+    /// Creates a worker with the default configuration of
+    /// `WorkerBuilder`.
+    pub fn new() -> Worker {
+        Self::builder().build()
+    }
+
+    pub fn builder() -> WorkerBuilder {
+        WorkerBuilder::new()
+    }
+
+    pub fn num_threads(&self) -> usize {
+        self.cpus
+    }
+
+    pub fn log_num_cpus(&self) -> u32 {
+        log2_floor(self.cpus)
+    }
+
+    pub fn compute<F, R>(
+        &self, f: F
+    ) -> WorkerFuture<R::Item, R::Error>
+        where F: FnOnce() -> R + Send + 'static,
+              R: IntoFuture + 'static,
+              R::Future: Send + 'static,
+              R::Item: Send + 'static,
+              R::Error: Send + 'static
+    {
+        let (sender, receiver) = oneshot::channel();
+
+        self.pool.spawn(move || {
+            // The receiver may have been dropped, in which case
+            // nobody is interested in the result.
+            let _ = sender.send(f().into_future().wait());
+        });
+
+        WorkerFuture {
+            future: receiver
+        }
+    }
+
+    pub fn scope<'a, F, R>(
+        &self,
+        elements: usize,
+        f: F
+    ) -> R
+        where F: for<'s> FnOnce(&Scope<'s, 'a>, usize) -> R + Send + 'a,
+              R: Send
+    {
+        let chunk_size = if elements < self.cpus {
+            1
+        } else {
+            elements / self.cpus
+        };
+
+        self.pool.scope(|scope| {
+            f(&Scope(scope), chunk_size)
+        })
+    }
+}
+
+/// Spawns tasks onto a worker's pool that may borrow from the
+/// enclosing stack frame. `Worker::scope` returns once they are done.
+#[cfg(feature = "multithread-rayon")]
+pub struct Scope<'s, 'a: 's>(&'s rayon::Scope<'a>);
+
+#[cfg(feature = "multithread-rayon")]
+impl<'s, 'a> Scope<'s, 'a> {
+    pub fn spawn<F>(&self, f: F)
+        where F: FnOnce() + Send + 'a
+    {
+        self.0.spawn(move |_| f())
+    }
+}
+
+#[cfg(feature = "multithread-rayon")]
+pub struct WorkerFuture<T, E> {
+    future: oneshot::Receiver<Result<T, E>>
+}
+
+#[cfg(feature = "multithread-rayon")]
+impl<T: Send + 'static, E: Send + 'static> Future for WorkerFuture<T, E> {
+    type Item = T;
+    type Error = E;
+
+    fn poll(&mut self) -> Poll<Self::Item, Self::Error>
+    {
+        match self.future.poll().expect("worker thread panicked") {
+            Async::Ready(result) => result.map(Async::Ready),
+            Async::NotReady => Ok(Async::NotReady)
+        }
+    }
+}
+
+#[cfg(not(feature = "multithread-rayon"))]
 impl<T: Send + 'static, E: Send + 'static> Future for WorkerFuture<T, E> {
     type Item = T;
     type Error = E;
@@ -175,3 +400,69 @@ fn test_log2_floor() {
     assert_eq!(log2_floor(7), 2);
     assert_eq!(log2_floor(8), 3);
 }
+
+#[test]
+fn test_num_threads_override() {
+    assert_eq!(num_threads_override("2"), Some(2));
+    assert_eq!(num_threads_override("16"), Some(16));
+    assert_eq!(num_threads_override("0"), None);
+    assert_eq!(num_threads_override("-1"), None);
+    assert_eq!(num_threads_override("two"), None);
+    assert_eq!(num_threads_override(""), None);
+}
+
+#[test]
+#[should_panic]
+fn test_worker_builder_no_threads() {
+    Worker::builder().num_threads(0);
+}
+
+#[cfg(any(feature = "multithread", feature = "multithread-rayon"))]
+#[test]
+fn test_worker_builder() {
+    use std::thread;
+
+    let worker = Worker::builder()
+        .num_threads(3)
+        .stack_size(1 << 20)
+        .name_prefix("bellman-test-")
+        .build();
+    assert_eq!(worker.num_threads(), 3);
+    assert_eq!(worker.log_num_cpus(), 1);
+
+    let name = worker.compute(|| Ok::<_, ()>(thread::current().name().map(String::from)))
+                     .wait()
+                     .unwrap()
+                     .unwrap();
+    assert!(name.starts_with("bellman-test-"));
+
+    // Clones share the same pool.
+    assert_eq!(worker.clone().num_threads(), 3);
+}
+
+#[cfg(not(any(feature = "multithread", feature = "multithread-rayon")))]
+#[test]
+fn test_worker_builder() {
+    use std::thread;
+
+    // The settings are accepted, but everything runs on the calling
+    // thread.
+    let worker = Worker::builder()
+        .num_threads(3)
+        .stack_size(1 << 20)
+        .name_prefix("bellman-test-")
+        .build();
+    assert_eq!(worker.num_threads(), 1);
+    assert_eq!(worker.log_num_cpus(), 0);
+
+    let id = worker.compute(|| Ok::<_, ()>(thread::current().id()))
+                   .wait()
+                   .unwrap();
+    assert_eq!(id, thread::current().id());
+
+    let sum = worker.scope(4, |scope, chunk_size| {
+        assert_eq!(chunk_size, 1);
+        scope.spawn(|| 1 + 2)
+    });
+    assert_eq!(sum, 3);
+}
@@ -14,8 +14,9 @@ use alloc::sync::Arc;
 #[cfg(feature = "std")]
 use std::io;
 use bit_vec::{self, BitVec};
+use rstd::cmp;
 use rstd::iter;
-use futures::{Future};
+use futures::{Future, future};
 use super::multicore::Worker;
 
 use super::SynthesisError;
@@ -25,6 +26,13 @@ pub trait SourceBuilder<G: CurveAffine>: Send + Sync + 'static + Clone {
     type Source: Source<G>;
 
     fn new(self) -> Self::Source;
+
+    /// Returns the fixed-base window table of the bases, along with the
+    /// position of the first base in it, if the builder has one. Such
+    /// bases are multiexp'd without reading them through `Source`.
+    fn precomputed(&self) -> Option<(Arc<PrecomputedBases<G>>, usize)> {
+        None
+    }
 }
 
 /// A source of bases, like an iterator.
@@ -32,6 +40,15 @@ pub trait Source<G: CurveAffine> {
     /// Parses the element from the source. Fails if the point is at infinity.
     fn add_assign_mixed(&mut self, to: &mut <G as CurveAffine>::Projective) -> Result<(), SynthesisError>;
 
+    /// Parses the element from the source and returns it. Fails if the
+    /// point is at infinity.
+    fn next_base(&mut self) -> Result<G, SynthesisError> {
+        let mut base = G::Projective::zero();
+        self.add_assign_mixed(&mut base)?;
+
+        Ok(base.into_affine())
+    }
+
     /// Skips `amt` elements from the source, avoiding deserialization.
     fn skip(&mut self, amt: usize) -> Result<(), SynthesisError>;
 }
@@ -46,6 +63,12 @@ impl<G: CurveAffine> SourceBuilder<G> for (Arc<Vec<G>>, usize) {
 
 impl<G: CurveAffine> Source<G> for (Arc<Vec<G>>, usize) {
     fn add_assign_mixed(&mut self, to: &mut <G as CurveAffine>::Projective) -> Result<(), SynthesisError> {
+        to.add_assign_mixed(&self.next_base()?);
+
+        Ok(())
+    }
+
+    fn next_base(&mut self) -> Result<G, SynthesisError> {
         if self.0.len() <= self.1 {
             #[cfg(feature = "std")]
             return Err(io::Error::new(io::ErrorKind::UnexpectedEof, "expected more bases from source").into());
@@ -53,17 +76,160 @@ impl<G: CurveAffine> Source<G> for (Arc<Vec<G>>, usize) {
             return Err(SynthesisError::IoError);
         }
 
-        if self.0[self.1].is_zero() {
+        let base = self.0[self.1];
+
+        if base.is_zero() {
             return Err(SynthesisError::UnexpectedIdentity)
         }
 
-        to.add_assign_mixed(&self.0[self.1]);
-
         self.1 += 1;
 
+        Ok(base)
+    }
+
+    fn skip(&mut self, amt: usize) -> Result<(), SynthesisError> {
+        if self.0.len() <= self.1 {
+            #[cfg(feature = "std")]
+            return Err(io::Error::new(io::ErrorKind::UnexpectedEof, "expected more bases from source").into());
+            #[cfg(not(feature = "std"))]
+            return Err(SynthesisError::IoError);
+        }
+
+        self.1 += amt;
+
+        Ok(())
+    }
+}
+
+/// A fixed-base window table: for every base `P` and window size `c`,
+/// the points `P, 2^c P, 2^(2c) P, ...`, one for each `c`-bit window of
+/// a scalar. A multiexp over these bases adds every window of every
+/// exponent into the same buckets, so the buckets are summed once instead
+/// of once per window and no doublings are needed.
+pub struct PrecomputedBases<G: CurveAffine> {
+    table: Vec<G>,
+    window: u32,
+    num_windows: usize
+}
+
+impl<G: CurveAffine> PrecomputedBases<G> {
+    /// Computes the table of `bases` for windows of `window` bits. It
+    /// takes `ceil(NUM_BITS / window)` times the memory of `bases`.
+    pub fn new(worker: &Worker, bases: &[G], window: u32) -> Self {
+        assert!(window > 0 && window <= 24, "unsupported window size");
+
+        let num_windows = num_windows::<G>(window);
+        let mut table = vec![G::zero(); bases.len() * num_windows];
+
+        worker.scope(bases.len(), |scope, chunk| {
+            for (bases, table) in bases.chunks(chunk).zip(table.chunks_mut(chunk * num_windows)) {
+                scope.spawn(move || {
+                    let mut shifted = Vec::with_capacity(table.len());
+
+                    for base in bases {
+                        let mut p = base.into_projective();
+
+                        for _ in 0..num_windows {
+                            shifted.push(p);
+
+                            for _ in 0..window {
+                                p.double();
+                            }
+                        }
+                    }
+
+                    G::Projective::batch_normalization(&mut shifted);
+
+                    for (t, p) in table.iter_mut().zip(shifted.iter()) {
+                        *t = p.into_affine();
+                    }
+                });
+            }
+        });
+
+        PrecomputedBases {
+            table: table,
+            window: window,
+            num_windows: num_windows
+        }
+    }
+
+    /// The window size of the table.
+    pub fn window(&self) -> u32 {
+        self.window
+    }
+
+    /// The number of bases in the table.
+    pub fn len(&self) -> usize {
+        self.table.len() / self.num_windows
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.table.is_empty()
+    }
+
+    // The shifted copies of the base at `index`.
+    fn shifted(&self, index: usize) -> Result<&[G], SynthesisError> {
+        if self.len() <= index {
+            #[cfg(feature = "std")]
+            return Err(io::Error::new(io::ErrorKind::UnexpectedEof, "expected more bases from source").into());
+            #[cfg(not(feature = "std"))]
+            return Err(SynthesisError::IoError);
+        }
+
+        let shifted = &self.table[index * self.num_windows..(index + 1) * self.num_windows];
+
+        if shifted[0].is_zero() {
+            return Err(SynthesisError::UnexpectedIdentity)
+        }
+
+        Ok(shifted)
+    }
+}
+
+/// The window size used for a table of `num_bases` precomputed bases.
+/// The buckets only have to be summed once, so it is larger than the
+/// window size of `multiexp`.
+pub fn precomputed_window_size(num_bases: usize) -> u32 {
+    let mut c = 3;
+    while c < 16 && (1usize << c) < num_bases {
+        c += 1;
+    }
+
+    c
+}
+
+fn num_windows<G: CurveAffine>(window: u32) -> usize {
+    ((G::Scalar::NUM_BITS + window - 1) / window) as usize
+}
+
+impl<G: CurveAffine> SourceBuilder<G> for (Arc<PrecomputedBases<G>>, usize) {
+    type Source = (Arc<PrecomputedBases<G>>, usize);
+
+    fn new(self) -> (Arc<PrecomputedBases<G>>, usize) {
+        (self.0.clone(), self.1)
+    }
+
+    fn precomputed(&self) -> Option<(Arc<PrecomputedBases<G>>, usize)> {
+        Some((self.0.clone(), self.1))
+    }
+}
+
+impl<G: CurveAffine> Source<G> for (Arc<PrecomputedBases<G>>, usize) {
+    fn add_assign_mixed(&mut self, to: &mut <G as CurveAffine>::Projective) -> Result<(), SynthesisError> {
+        to.add_assign_mixed(&self.next_base()?);
+
         Ok(())
     }
 
+    fn next_base(&mut self) -> Result<G, SynthesisError> {
+        let base = self.0.shifted(self.1)?[0];
+
+        self.1 += 1;
+
+        Ok(base)
+    }
+
     fn skip(&mut self, amt: usize) -> Result<(), SynthesisError> {
         if self.0.len() <= self.1 {
             #[cfg(feature = "std")]
@@ -148,15 +314,98 @@ impl DensityTracker {
     }
 }
 
+/// How a multiexp accumulates the bases of each bucket.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum BucketAccumulation {
+    /// Adds every base into a projective bucket with a mixed addition.
+    Projective,
+    /// Collects the bases of every bucket and adds them up pairwise in
+    /// affine coordinates, sharing a single inversion between all of the
+    /// additions of a round (see `CurveAffine::batch_add_assign`). This
+    /// is cheaper per base but keeps the bases of a window in memory.
+    BatchAffine
+}
+
+impl Default for BucketAccumulation {
+    fn default() -> Self {
+        BucketAccumulation::Projective
+    }
+}
+
+// The bases of every bucket of a window, for `BucketAccumulation::BatchAffine`.
+struct AffineBuckets<G: CurveAffine> {
+    buckets: Vec<Vec<G>>
+}
+
+impl<G: CurveAffine> AffineBuckets<G> {
+    fn new(num_buckets: usize) -> Self {
+        AffineBuckets {
+            buckets: vec![vec![]; num_buckets]
+        }
+    }
+
+    fn push(&mut self, bucket: usize, base: G) {
+        self.buckets[bucket].push(base);
+    }
+
+    // Sums every bucket, halving the number of bases in all of them in
+    // each round.
+    fn sum(mut self) -> Vec<G> {
+        let mut acc = vec![];
+        let mut other = vec![];
+
+        while self.buckets.iter().any(|b| b.len() > 1) {
+            for bucket in &self.buckets {
+                for pair in bucket.chunks(2).filter(|pair| pair.len() == 2) {
+                    acc.push(pair[0]);
+                    other.push(pair[1]);
+                }
+            }
+
+            G::batch_add_assign(&mut acc, &other);
+
+            let mut sums = acc.drain(..);
+            for bucket in &mut self.buckets {
+                let odd = if bucket.len() % 2 == 1 { bucket.last().cloned() } else { None };
+                let pairs = bucket.len() / 2;
+
+                bucket.clear();
+                bucket.extend(sums.by_ref().take(pairs));
+                bucket.extend(odd);
+            }
+
+            other.clear();
+        }
+
+        self.buckets.into_iter()
+            .map(|b| b.into_iter().next().unwrap_or_else(G::zero))
+            .collect()
+    }
+}
+
+/// Observes the windows ("chunks") of a multiexp as they are computed on
+/// the worker.
+pub(crate) trait ChunkObserver: Send + Sync {
+    /// Called before a chunk is computed. Returning an error aborts the
+    /// multiexp with that error.
+    fn before_chunk(&self) -> Result<(), SynthesisError>;
+
+    /// Called after a chunk has been computed.
+    fn chunk_completed(&self);
+}
+
 fn multiexp_inner<Q, D, G, S>(
     pool: &Worker,
     bases: S,
     density_map: D,
     exponents: Arc<Vec<<<G::Engine as Engine>::Fr as PrimeField>::Repr>>,
+    carries: Arc<Vec<u128>>,
     mut skip: u32,
     c: u32,
-    handle_trivial: bool
-) -> Box<Future<Item=<G as CurveAffine>::Projective, Error=SynthesisError>>
+    handle_trivial: bool,
+    accumulation: BucketAccumulation,
+    observer: Option<Arc<dyn ChunkObserver>>
+) -> Box<dyn Future<Item=<G as CurveAffine>::Projective, Error=SynthesisError>>
     where for<'a> &'a Q: QueryDensity,
           D: Send + Sync + 'static + Clone + AsRef<Q>,
           G: CurveAffine,
@@ -166,23 +415,34 @@ fn multiexp_inner<Q, D, G, S>(
     let this = {
         let bases = bases.clone();
         let exponents = exponents.clone();
+        let carries = carries.clone();
         let density_map = density_map.clone();
+        let observer = observer.clone();
 
         pool.compute(move || {
+            if let Some(ref observer) = observer {
+                observer.before_chunk()?;
+            }
+
             // Accumulate the result
             let mut acc = G::Projective::zero();
 
             // Build a source for the bases
             let mut bases = bases.new();
 
-            // Create space for the buckets
-            let mut buckets = vec![<G as CurveAffine>::Projective::zero(); (1 << c) - 1];
+            // Create space for the buckets, one for each digit magnitude
+            let mut buckets = vec![<G as CurveAffine>::Projective::zero(); 1 << (c - 1)];
+            let mut affine_buckets = match accumulation {
+                BucketAccumulation::Projective => None,
+                BucketAccumulation::BatchAffine => Some(AffineBuckets::new(buckets.len()))
+            };
 
             let zero = <G::Engine as Engine>::Fr::zero().into_repr();
             let one = <G::Engine as Engine>::Fr::one().into_repr();
 
             // Sort the bases into buckets
-            for (&exp, density) in exponents.iter().zip(density_map.as_ref().iter()) {
+            let exponents = exponents.iter().zip(carries.iter());
+            for ((&exp, &carries), density) in exponents.zip(density_map.as_ref().iter()) {
                 if density {
                     if exp == zero {
                         bases.skip(1)?;
@@ -193,12 +453,20 @@ fn multiexp_inner<Q, D, G, S>(
                             bases.skip(1)?;
                         }
                     } else {
-                        let mut exp = exp;
-                        exp.shr(skip);
-                        let exp = exp.as_ref()[0] % (1 << c);
-
-                        if exp != 0 {
-                            bases.add_assign_mixed(&mut buckets[(exp - 1) as usize])?;
+                        let digit = signed_digit(exp.as_ref(), carries, skip / c, c);
+
+                        if digit != 0 {
+                            let mut base = bases.next_base()?;
+                            if digit < 0 {
+                                base.negate();
+                            }
+
+                            let bucket = (digit.abs() - 1) as usize;
+                            if let Some(ref mut affine_buckets) = affine_buckets {
+                                affine_buckets.push(bucket, base);
+                            } else {
+                                buckets[bucket].add_assign_mixed(&base);
+                            }
                         } else {
                             bases.skip(1)?;
                         }
@@ -206,6 +474,12 @@ fn multiexp_inner<Q, D, G, S>(
                 }
             }
 
+            if let Some(affine_buckets) = affine_buckets {
+                for (bucket, sum) in buckets.iter_mut().zip(affine_buckets.sum()) {
+                    bucket.add_assign_mixed(&sum);
+                }
+            }
+
             // Summation by parts
             // e.g. 3a + 2b + 1c = a +
             //                    (a) + b +
@@ -216,20 +490,25 @@ fn multiexp_inner<Q, D, G, S>(
                 acc.add_assign(&running_sum);
             }
 
+            if let Some(ref observer) = observer {
+                observer.chunk_completed();
+            }
+
             Ok(acc)
         })
     };
 
     skip += c;
 
-    if skip >= <G::Engine as Engine>::Fr::NUM_BITS {
+    // The signed digits of an exponent can carry into one more window.
+    if skip > <G::Engine as Engine>::Fr::NUM_BITS {
         // There isn't another region.
         Box::new(this)
     } else {
         // There's another region more significant. Calculate and join it with
         // this region recursively.
         Box::new(
-            this.join(multiexp_inner(pool, bases, density_map, exponents, skip, c, false))
+            this.join(multiexp_inner(pool, bases, density_map, exponents, carries, skip, c, false, accumulation, observer))
                 .map(move |(this, mut higher)| {
                     for _ in 0..c {
                         higher.double();
@@ -243,6 +522,184 @@ fn multiexp_inner<Q, D, G, S>(
     }
 }
 
+// The `c` bits of `exp` starting at bit `offset`.
+fn window_bits(exp: &[u64], offset: u32, c: u32) -> u64 {
+    let limb = (offset / 64) as usize;
+    let shift = offset % 64;
+
+    let mut bits = exp.get(limb).map_or(0, |l| l >> shift);
+    if shift + c > 64 && shift != 0 {
+        bits |= exp.get(limb + 1).map_or(0, |l| l << (64 - shift));
+    }
+
+    bits & ((1 << c) - 1)
+}
+
+// The carries of the signed-digit recoding of `exp` with `c`-bit windows
+// (see `signed_digit`), bit `i` being the carry into window `i`. They only
+// depend on the lower windows, so they are computed once per exponent
+// rather than once per window.
+fn signed_digit_carries(exp: &[u64], num_windows: u32, c: u32) -> u128 {
+    assert!(num_windows <= 128, "too many windows");
+
+    let half = 1 << (c - 1);
+
+    let mut carries = 0;
+    let mut carry = 0;
+    for i in 1..num_windows {
+        carry = if window_bits(exp, (i - 1) * c, c) + carry > half { 1 } else { 0 };
+        carries |= (carry as u128) << i;
+    }
+
+    carries
+}
+
+// The digit of `exp` in window `window` of its signed-digit recoding with
+// `c`-bit windows, given its `carries`. Every digit is in
+// `(-2^(c-1), 2^(c-1)]`, so a window needs half as many buckets as with
+// unsigned digits; a digit above `2^(c-1)` borrows `2^c` from the next
+// window instead.
+fn signed_digit(exp: &[u64], carries: u128, window: u32, c: u32) -> i64 {
+    let half = 1 << (c - 1);
+
+    let digit = window_bits(exp, window * c, c) + ((carries >> window) & 1) as u64;
+    if digit > half {
+        digit as i64 - (1 << c)
+    } else {
+        digit as i64
+    }
+}
+
+/// The window size used for a multiexp of `num_exponents` exponents.
+fn window_size(num_exponents: usize) -> u32 {
+    if num_exponents < 32 {
+        3u32
+    } else {
+        (f64::from(num_exponents as u32)).ln().ceil() as u32
+    }
+}
+
+/// The number of chunks a multiexp of `num_exponents` exponents over
+/// `bases` is split into.
+pub(crate) fn multiexp_chunks<G, S>(pool: &Worker, bases: &S, num_exponents: usize) -> usize
+    where G: CurveAffine, S: SourceBuilder<G>
+{
+    if bases.precomputed().is_some() {
+        precomputed_chunks(pool, num_exponents)
+    } else {
+        // Including the window the signed digits can carry into.
+        (G::Scalar::NUM_BITS / window_size(num_exponents) + 1) as usize
+    }
+}
+
+fn precomputed_chunks(pool: &Worker, num_exponents: usize) -> usize {
+    cmp::max(1, cmp::min(pool.num_threads(), num_exponents))
+}
+
+// Splits the exponents into one range per chunk and adds the shifted
+// bases of every window of each exponent into a single set of buckets.
+fn multiexp_precomputed<Q, D, G>(
+    pool: &Worker,
+    table: Arc<PrecomputedBases<G>>,
+    offset: usize,
+    density_map: D,
+    exponents: Arc<Vec<<<G::Engine as Engine>::Fr as PrimeField>::Repr>>,
+    observer: Option<Arc<dyn ChunkObserver>>
+) -> Box<dyn Future<Item=<G as CurveAffine>::Projective, Error=SynthesisError>>
+    where for<'a> &'a Q: QueryDensity,
+          D: Send + Sync + 'static + Clone + AsRef<Q>,
+          G: CurveAffine
+{
+    let num_chunks = precomputed_chunks(pool, exponents.len());
+    let chunk = (exponents.len() + num_chunks - 1) / num_chunks;
+
+    // The position of the first base of each chunk in the table.
+    let mut starts = Vec::with_capacity(num_chunks);
+    let mut index = offset;
+    for (i, density) in density_map.as_ref().iter().take(exponents.len()).enumerate() {
+        if i % chunk == 0 {
+            starts.push(index);
+        }
+        if density {
+            index += 1;
+        }
+    }
+    starts.resize(num_chunks, index);
+
+    let mut result: Box<dyn Future<Item=G::Projective, Error=SynthesisError>> =
+        Box::new(future::ok(G::Projective::zero()));
+
+    for (i, start) in starts.into_iter().enumerate() {
+        let table = table.clone();
+        let exponents = exponents.clone();
+        let density_map = density_map.clone();
+        let observer = observer.clone();
+
+        let this = pool.compute(move || {
+            if let Some(ref observer) = observer {
+                observer.before_chunk()?;
+            }
+
+            let c = table.window;
+            let mask = (1u64 << c) - 1;
+            let zero = <G::Engine as Engine>::Fr::zero().into_repr();
+
+            let mut buckets = vec![G::Projective::zero(); (1 << c) - 1];
+            let mut index = start;
+
+            let range = cmp::min(i * chunk, exponents.len())..cmp::min((i + 1) * chunk, exponents.len());
+            let density = density_map.as_ref().iter().skip(range.start);
+
+            for (&exp, density) in exponents[range].iter().zip(density) {
+                if !density {
+                    continue;
+                }
+
+                let shifted = table.shifted(index)?;
+                index += 1;
+
+                if exp == zero {
+                    continue;
+                }
+
+                let mut exp = exp;
+                for base in shifted {
+                    let digit = exp.as_ref()[0] & mask;
+                    if digit != 0 {
+                        buckets[(digit - 1) as usize].add_assign_mixed(base);
+                    }
+
+                    exp.shr(c);
+                }
+            }
+
+            // Summation by parts
+            // e.g. 3a + 2b + 1c = a +
+            //                    (a) + b +
+            //                    ((a) + b) + c
+            let mut acc = G::Projective::zero();
+            let mut running_sum = G::Projective::zero();
+            for exp in buckets.into_iter().rev() {
+                running_sum.add_assign(&exp);
+                acc.add_assign(&running_sum);
+            }
+
+            if let Some(ref observer) = observer {
+                observer.chunk_completed();
+            }
+
+            Ok(acc)
+        });
+
+        result = Box::new(result.join(this).map(|(mut acc, this)| {
+            acc.add_assign(&this);
+            acc
+        }));
+    }
+
+    result
+}
+
 /// Perform multi-exponentiation. The caller is responsible for ensuring the
 /// query size is the same as the number of exponents.
 pub fn multiexp<Q, D, G, S>(
@@ -250,17 +707,49 @@ pub fn multiexp<Q, D, G, S>(
     bases: S,
     density_map: D,
     exponents: Arc<Vec<<<G::Engine as Engine>::Fr as PrimeField>::Repr>>
-) -> Box<Future<Item=<G as CurveAffine>::Projective, Error=SynthesisError>>
+) -> Box<dyn Future<Item=<G as CurveAffine>::Projective, Error=SynthesisError>>
     where for<'a> &'a Q: QueryDensity,
           D: Send + Sync + 'static + Clone + AsRef<Q>,
           G: CurveAffine,
           S: SourceBuilder<G>
 {
-    let c = if exponents.len() < 32 {
-        3u32
-    } else {
-        (f64::from(exponents.len() as u32)).ln().ceil() as u32
-    };
+    multiexp_observed(pool, bases, density_map, exponents, BucketAccumulation::default(), None)
+}
+
+/// Like `multiexp`, but accumulates the buckets as selected by
+/// `accumulation`. Precomputed bases always use projective buckets. The
+/// Groth16 prover selects it with `ParameterSource::get_accumulation`.
+pub fn multiexp_with_accumulation<Q, D, G, S>(
+    pool: &Worker,
+    bases: S,
+    density_map: D,
+    exponents: Arc<Vec<<<G::Engine as Engine>::Fr as PrimeField>::Repr>>,
+    accumulation: BucketAccumulation
+) -> Box<dyn Future<Item=<G as CurveAffine>::Projective, Error=SynthesisError>>
+    where for<'a> &'a Q: QueryDensity,
+          D: Send + Sync + 'static + Clone + AsRef<Q>,
+          G: CurveAffine,
+          S: SourceBuilder<G>
+{
+    multiexp_observed(pool, bases, density_map, exponents, accumulation, None)
+}
+
+/// Like `multiexp_with_accumulation`, but reports each chunk to
+/// `observer`, which can also abort the multiexp between chunks.
+pub(crate) fn multiexp_observed<Q, D, G, S>(
+    pool: &Worker,
+    bases: S,
+    density_map: D,
+    exponents: Arc<Vec<<<G::Engine as Engine>::Fr as PrimeField>::Repr>>,
+    accumulation: BucketAccumulation,
+    observer: Option<Arc<dyn ChunkObserver>>
+) -> Box<dyn Future<Item=<G as CurveAffine>::Projective, Error=SynthesisError>>
+    where for<'a> &'a Q: QueryDensity,
+          D: Send + Sync + 'static + Clone + AsRef<Q>,
+          G: CurveAffine,
+          S: SourceBuilder<G>
+{
+    let c = window_size(exponents.len());
 
     if let Some(query_size) = density_map.as_ref().get_query_size() {
         // If the density map has a known query size, it should not be
@@ -269,7 +758,65 @@ pub fn multiexp<Q, D, G, S>(
         assert!(query_size == exponents.len());
     }
 
-    multiexp_inner(pool, bases, density_map, exponents, 0, c, true)
+    if let Some((table, offset)) = bases.precomputed() {
+        return multiexp_precomputed(pool, table, offset, density_map, exponents, observer);
+    }
+
+    // Including the window the signed digits can carry into.
+    let num_windows = <G::Engine as Engine>::Fr::NUM_BITS / c + 1;
+    let mut carries = vec![0; exponents.len()];
+    pool.scope(exponents.len(), |scope, chunk| {
+        for (exponents, carries) in exponents.chunks(chunk).zip(carries.chunks_mut(chunk)) {
+            scope.spawn(move || {
+                for (exp, carries) in exponents.iter().zip(carries.iter_mut()) {
+                    *carries = signed_digit_carries(exp.as_ref(), num_windows, c);
+                }
+            });
+        }
+    });
+
+    multiexp_inner(pool, bases, density_map, exponents, Arc::new(carries), 0, c, true, accumulation, observer)
+}
+
+#[test]
+fn test_signed_digits() {
+    use rand::{self, Rand};
+    use pairing::bls12_381::Fr;
+
+    let rng = &mut rand::thread_rng();
+
+    let mut minus_one = Fr::zero();
+    minus_one.sub_assign(&Fr::one());
+
+    for c in 3..17 {
+        let num_windows = Fr::NUM_BITS / c + 1;
+        let mut radix = Fr::one();
+        for _ in 0..c {
+            radix.double();
+        }
+
+        for exp in (0..20).map(|_| Fr::rand(rng)).chain(Some(minus_one)) {
+            let repr = exp.into_repr();
+            let carries = signed_digit_carries(repr.as_ref(), num_windows, c);
+
+            // The digits are in range and add up to the exponent.
+            let mut acc = Fr::zero();
+            for window in (0..num_windows).rev() {
+                let digit = signed_digit(repr.as_ref(), carries, window, c);
+                assert!(-(1 << (c - 1)) < digit && digit <= 1 << (c - 1));
+
+                let mut d = Fr::from_repr(digit.unsigned_abs().into()).unwrap();
+                if digit < 0 {
+                    d.negate();
+                }
+
+                acc.mul_assign(&radix);
+                acc.add_assign(&d);
+            }
+
+            assert_eq!(acc, exp);
+        }
+    }
 }
 
 #[test]
@@ -305,10 +852,78 @@ fn test_with_bls12() {
 
     let fast = multiexp(
         &pool,
-        (g, 0),
+        (g.clone(), 0),
         FullDensity,
-        v
+        v.clone()
     ).wait().unwrap();
 
     assert_eq!(naive, fast);
+
+    // Exponents whose signed digits carry into the window above the top
+    // bit: with 100 exponents, 5-bit windows divide the 255-bit scalars.
+    let mut minus_one = <Bls12 as Engine>::Fr::zero();
+    minus_one.sub_assign(&<Bls12 as Engine>::Fr::one());
+    let edge = Arc::new((0..100).map(|i| match i % 4 {
+        0 => minus_one.into_repr(),
+        1 => <<Bls12 as Engine>::Fr as PrimeField>::Repr::from(0b10000 << (i % 60)),
+        2 => <<Bls12 as Engine>::Fr as PrimeField>::Repr::from(0b10001),
+        _ => v[i]
+    }).collect::<Vec<_>>());
+    let edge_bases = Arc::new(g[..100].to_vec());
+    assert_eq!(window_size(edge.len()), 5);
+
+    let fast = multiexp(&pool, (edge_bases.clone(), 0), FullDensity, edge.clone()).wait().unwrap();
+    assert_eq!(naive_multiexp(edge_bases, edge), fast);
+
+    let batch_affine = multiexp_with_accumulation(
+        &pool,
+        (g.clone(), 0),
+        FullDensity,
+        v.clone(),
+        BucketAccumulation::BatchAffine
+    ).wait().unwrap();
+
+    assert_eq!(naive, batch_affine);
+
+    // The same base in a bucket more than once, and G2.
+    let g2 = Arc::new(vec![<Bls12 as Engine>::G2::rand(rng).into_affine(); 64]);
+    let v2 = Arc::new(vec![<Bls12 as Engine>::Fr::rand(rng).into_repr(); 64]);
+    let batch_affine = multiexp_with_accumulation(
+        &pool,
+        (g2.clone(), 0),
+        FullDensity,
+        v2.clone(),
+        BucketAccumulation::BatchAffine
+    ).wait().unwrap();
+
+    assert_eq!(naive_multiexp(g2, v2), batch_affine);
+
+    let table = Arc::new(PrecomputedBases::new(&pool, &g, precomputed_window_size(SAMPLES)));
+    assert_eq!(table.len(), SAMPLES);
+
+    let precomputed = multiexp(
+        &pool,
+        (table.clone(), 0),
+        FullDensity,
+        v.clone()
+    ).wait().unwrap();
+
+    assert_eq!(naive, precomputed);
+
+    // Sparse exponents starting in the middle of the table.
+    let mut density = DensityTracker::new();
+    for i in 0..SAMPLES / 2 {
+        density.add_element();
+        if i % 3 != 0 {
+            density.inc(i);
+        }
+    }
+    let density = Arc::new(density);
+    let sparse = Arc::new(v[..SAMPLES / 2].to_vec());
+    let dense_bases = Arc::new(g[100..].to_vec());
+
+    let expected = multiexp(&pool, (dense_bases, 0), density.clone(), sparse.clone()).wait().unwrap();
+    let precomputed = multiexp(&pool, (table, 100), density, sparse).wait().unwrap();
+
+    assert_eq!(expected, precomputed);
 }
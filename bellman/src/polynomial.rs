@@ -0,0 +1,363 @@
+//! Dense univariate polynomials over the scalar field of an engine.
+//!
+//! `Polynomial` keeps its coefficients in order of increasing degree and
+//! multiplies and interpolates them with the FFTs of `EvaluationDomain`,
+//! so that commitment schemes and other proof systems can be built on the
+//! same code as the Groth16 prover.
+
+use pairing::{
+    Engine,
+    Field
+};
+
+use rstd::prelude::*;
+
+use ::{
+    SynthesisError
+};
+
+use domain::{
+    DomainKind,
+    EvaluationDomain,
+    Scalar
+};
+
+use multicore::Worker;
+
+/// A polynomial in coefficient form, without trailing zero coefficients.
+pub struct Polynomial<E: Engine> {
+    coeffs: Vec<E::Fr>
+}
+
+impl<E: Engine> PartialEq for Polynomial<E> {
+    fn eq(&self, other: &Polynomial<E>) -> bool {
+        self.coeffs == other.coeffs
+    }
+}
+
+impl<E: Engine> Eq for Polynomial<E> { }
+
+impl<E: Engine> Clone for Polynomial<E> {
+    fn clone(&self) -> Polynomial<E> {
+        Polynomial {
+            coeffs: self.coeffs.clone()
+        }
+    }
+}
+
+impl<E: Engine> Polynomial<E> {
+    pub fn zero() -> Self {
+        Polynomial {
+            coeffs: vec![]
+        }
+    }
+
+    /// The polynomial `sum_i coeffs[i] X^i`.
+    pub fn from_coeffs(coeffs: Vec<E::Fr>) -> Self {
+        let mut poly = Polynomial {
+            coeffs: coeffs
+        };
+        poly.truncate();
+
+        poly
+    }
+
+    /// Interpolates the polynomial from its evaluations over `domain`.
+    pub fn from_evaluations(
+        mut domain: EvaluationDomain<E, Scalar<E>>,
+        worker: &Worker
+    ) -> Self
+    {
+        domain.ifft(worker);
+
+        Self::from_coeffs(domain.into_coeffs().into_iter().map(|s| s.0).collect())
+    }
+
+    /// Lagrange interpolation of the polynomial of degree less than
+    /// `points.len()` through the given `(x, y)` points, in quadratic time.
+    /// Fails with `DivisionByZero` if two points share an `x`.
+    pub fn interpolate(points: &[(E::Fr, E::Fr)]) -> Result<Self, SynthesisError> {
+        // The product of every X - x_i
+        let mut vanishing = vec![E::Fr::one()];
+        for &(x, _) in points {
+            let mut next = vec![E::Fr::zero(); vanishing.len() + 1];
+            for (j, v) in vanishing.iter().enumerate() {
+                next[j + 1].add_assign(v);
+
+                let mut tmp = *v;
+                tmp.mul_assign(&x);
+                next[j].sub_assign(&tmp);
+            }
+            vanishing = next;
+        }
+
+        let mut coeffs = vec![E::Fr::zero(); points.len()];
+        for (i, &(x_i, y_i)) in points.iter().enumerate() {
+            // The vanishing polynomial divided by X - x_i
+            let mut basis = vec![E::Fr::zero(); points.len()];
+            let mut carry = E::Fr::zero();
+            for j in (0..points.len()).rev() {
+                carry.mul_assign(&x_i);
+                carry.add_assign(&vanishing[j + 1]);
+                basis[j] = carry;
+            }
+
+            // scaled to be y_i at x_i
+            let mut denom = E::Fr::one();
+            for (j, &(x_j, _)) in points.iter().enumerate() {
+                if i != j {
+                    let mut tmp = x_i;
+                    tmp.sub_assign(&x_j);
+                    denom.mul_assign(&tmp);
+                }
+            }
+            let mut scale = denom.inverse().ok_or(SynthesisError::DivisionByZero)?;
+            scale.mul_assign(&y_i);
+
+            for (c, b) in coeffs.iter_mut().zip(basis.iter()) {
+                let mut tmp = *b;
+                tmp.mul_assign(&scale);
+                c.add_assign(&tmp);
+            }
+        }
+
+        Ok(Self::from_coeffs(coeffs))
+    }
+
+    pub fn coeffs(&self) -> &[E::Fr] {
+        &self.coeffs
+    }
+
+    pub fn into_coeffs(self) -> Vec<E::Fr> {
+        self.coeffs
+    }
+
+    pub fn is_zero(&self) -> bool {
+        self.coeffs.is_empty()
+    }
+
+    /// The degree of the polynomial, which is 0 for the zero polynomial.
+    pub fn degree(&self) -> usize {
+        if self.coeffs.is_empty() {
+            0
+        } else {
+            self.coeffs.len() - 1
+        }
+    }
+
+    /// Evaluates the polynomial at `x` with Horner's rule.
+    pub fn evaluate(&self, x: &E::Fr) -> E::Fr {
+        let mut acc = E::Fr::zero();
+        for c in self.coeffs.iter().rev() {
+            acc.mul_assign(x);
+            acc.add_assign(c);
+        }
+
+        acc
+    }
+
+    /// Evaluates the polynomial over the domain of size `m`, which must
+    /// be larger than its degree.
+    pub fn evaluate_over_domain(
+        &self,
+        m: usize,
+        worker: &Worker
+    ) -> Result<EvaluationDomain<E, Scalar<E>>, SynthesisError>
+    {
+        let coeffs = self.coeffs.iter().map(|&c| Scalar(c)).collect();
+        let mut domain = EvaluationDomain::from_coeffs_with_size(coeffs, m)?;
+        domain.fft(worker);
+
+        Ok(domain)
+    }
+
+    pub fn add_assign(&mut self, other: &Polynomial<E>) {
+        if self.coeffs.len() < other.coeffs.len() {
+            self.coeffs.resize(other.coeffs.len(), E::Fr::zero());
+        }
+
+        for (a, b) in self.coeffs.iter_mut().zip(other.coeffs.iter()) {
+            a.add_assign(b);
+        }
+        self.truncate();
+    }
+
+    pub fn sub_assign(&mut self, other: &Polynomial<E>) {
+        if self.coeffs.len() < other.coeffs.len() {
+            self.coeffs.resize(other.coeffs.len(), E::Fr::zero());
+        }
+
+        for (a, b) in self.coeffs.iter_mut().zip(other.coeffs.iter()) {
+            a.sub_assign(b);
+        }
+        self.truncate();
+    }
+
+    /// Multiplies every coefficient by `by`.
+    pub fn scale(&mut self, by: &E::Fr) {
+        for c in self.coeffs.iter_mut() {
+            c.mul_assign(by);
+        }
+        self.truncate();
+    }
+
+    /// Multiplies two polynomials of any degrees by evaluating them over
+    /// the smallest domain that holds their product.
+    pub fn mul(
+        &self,
+        other: &Polynomial<E>,
+        worker: &Worker
+    ) -> Result<Polynomial<E>, SynthesisError>
+    {
+        if self.is_zero() || other.is_zero() {
+            return Ok(Self::zero());
+        }
+
+        let n = self.coeffs.len() + other.coeffs.len() - 1;
+        let m = DomainKind::MixedRadix.size::<E>(n)?;
+
+        let mut a = self.evaluate_over_domain(m, worker)?;
+        let b = other.evaluate_over_domain(m, worker)?;
+        a.mul_assign(worker, &b);
+
+        let mut product = Self::from_evaluations(a, worker);
+        debug_assert!(product.coeffs.len() <= n);
+        product.truncate();
+
+        Ok(product)
+    }
+
+    /// Long division by `divisor`, returning the quotient and the
+    /// remainder. Fails with `DivisionByZero` if `divisor` is zero.
+    pub fn divide(
+        &self,
+        divisor: &Polynomial<E>
+    ) -> Result<(Polynomial<E>, Polynomial<E>), SynthesisError>
+    {
+        let lead_inv = match divisor.coeffs.last() {
+            Some(lead) => lead.inverse().ok_or(SynthesisError::DivisionByZero)?,
+            None => return Err(SynthesisError::DivisionByZero)
+        };
+
+        if self.coeffs.len() < divisor.coeffs.len() {
+            return Ok((Self::zero(), self.clone()));
+        }
+
+        let shift = self.coeffs.len() - divisor.coeffs.len();
+        let mut remainder = self.coeffs.clone();
+        let mut quotient = vec![E::Fr::zero(); shift + 1];
+
+        for i in (0..shift + 1).rev() {
+            let mut q = remainder[i + divisor.coeffs.len() - 1];
+            q.mul_assign(&lead_inv);
+            quotient[i] = q;
+
+            for (r, d) in remainder[i..].iter_mut().zip(divisor.coeffs.iter()) {
+                let mut tmp = *d;
+                tmp.mul_assign(&q);
+                r.sub_assign(&tmp);
+            }
+        }
+
+        remainder.truncate(divisor.coeffs.len() - 1);
+
+        Ok((Self::from_coeffs(quotient), Self::from_coeffs(remainder)))
+    }
+
+    // Drops the trailing zero coefficients.
+    fn truncate(&mut self) {
+        while self.coeffs.last().map_or(false, |c| c.is_zero()) {
+            self.coeffs.pop();
+        }
+    }
+}
+
+#[cfg(test)]
+mod test_with_bls12_381 {
+    use super::*;
+
+    use rand::{Rand, thread_rng};
+    use pairing::bls12_381::{Bls12, Fr};
+
+    fn random(rng: &mut ::rand::ThreadRng, len: usize) -> Polynomial<Bls12> {
+        Polynomial::from_coeffs((0..len).map(|_| Fr::rand(rng)).collect())
+    }
+
+    #[test]
+    fn arithmetic() {
+        let rng = &mut thread_rng();
+        let worker = Worker::new();
+
+        for &(len_a, len_b) in &[(0, 5), (1, 1), (3, 17), (64, 65), (100, 7)] {
+            let a = random(rng, len_a);
+            let b = random(rng, len_b);
+            let x = Fr::rand(rng);
+
+            let product = a.mul(&b, &worker).unwrap();
+            let mut expected = a.evaluate(&x);
+            expected.mul_assign(&b.evaluate(&x));
+            assert!(product.evaluate(&x) == expected);
+            if len_a > 0 && len_b > 0 {
+                assert_eq!(product.degree(), a.degree() + b.degree());
+            }
+
+            let mut sum = a.clone();
+            sum.add_assign(&b);
+            sum.sub_assign(&b);
+            assert!(sum == a);
+            sum.sub_assign(&a);
+            assert!(sum.is_zero());
+
+            // a = q * b + r with deg r < deg b
+            if !b.is_zero() {
+                let (q, r) = a.divide(&b).unwrap();
+                assert!(r.is_zero() || r.degree() < b.degree());
+
+                let mut expected = q.mul(&b, &worker).unwrap();
+                expected.add_assign(&r);
+                assert!(expected == a);
+
+                let (q, r) = product.divide(&b).unwrap();
+                assert!(q == a);
+                assert!(r.is_zero());
+            }
+        }
+
+        assert!(random(rng, 3).divide(&Polynomial::zero()).is_err());
+    }
+
+    #[test]
+    fn interpolation() {
+        let rng = &mut thread_rng();
+        let worker = Worker::new();
+
+        for &len in &[0, 1, 2, 9] {
+            let poly = random(rng, len);
+            let points = (0..len)
+                .map(|_| { let x = Fr::rand(rng); (x, poly.evaluate(&x)) })
+                .collect::<Vec<_>>();
+
+            assert!(Polynomial::interpolate(&points).unwrap() == poly);
+        }
+
+        let x = Fr::rand(rng);
+        assert!(Polynomial::<Bls12>::interpolate(&[(x, Fr::one()), (x, Fr::zero())]).is_err());
+
+        for &m in &[8, 12] {
+            let poly = random(rng, m - 1);
+            let evals = poly.evaluate_over_domain(m, &worker).unwrap();
+
+            let x = Fr::rand(rng);
+            assert!(evals.evaluate_barycentric(&x) == poly.evaluate(&x));
+
+            // At a point of the domain
+            let omega = Polynomial::<Bls12>::from_coeffs(vec![Fr::zero(), Fr::one()])
+                .evaluate_over_domain(m, &worker).unwrap()
+                .as_ref()[1].0;
+            let omega_3 = omega.pow(&[3]);
+            assert!(evals.evaluate_barycentric(&omega_3) == evals.as_ref()[3].0);
+
+            assert!(Polynomial::from_evaluations(evals, &worker) == poly);
+        }
+    }
+}
@@ -0,0 +1,859 @@
+//! Export and import of constraint systems in the binary `.r1cs` and
+//! `.wtns` formats used by circom and snarkjs.
+//!
+//! Both formats start with a four byte magic, a `u32` version and a `u32`
+//! number of sections. Each section is a `u32` type and a `u64` byte length
+//! followed by its contents. All integers are little endian, and field
+//! elements are written in canonical (non-Montgomery) form as little endian
+//! integers of `n8` bytes.
+//!
+//! `.r1cs` (version 1) has three sections:
+//!
+//! 1. header: `n8: u32`, the field modulus, `n_wires: u32`,
+//!    `n_pub_out: u32`, `n_pub_in: u32`, `n_prv_in: u32`, `n_labels: u64`
+//!    and `n_constraints: u32`;
+//! 2. constraints: for each constraint the `A`, `B` and `C` linear
+//!    combinations, each a `u32` number of terms followed by
+//!    `(wire: u32, coeff)` pairs sorted by wire;
+//! 3. wire to label map: a `u64` label per wire.
+//!
+//! `.wtns` (version 2) has a header section with `n8: u32`, the field
+//! modulus and `n_witness: u32`, and a section with the value of every wire.
+//!
+//! Wire 0 is the constant one. Bellman's inputs follow as public inputs, in
+//! allocation order, and then its auxiliary variables. There are no public
+//! outputs or private inputs in the bellman model, so those counts are zero.
+//! When importing, public outputs and public inputs both become bellman
+//! inputs, in wire order.
+
+use pairing::{
+    Engine,
+    Field,
+    PrimeField,
+    PrimeFieldRepr
+};
+
+use byteorder::{ByteOrder, LittleEndian, ReadBytesExt, WriteBytesExt};
+
+use std::collections::BTreeMap;
+use std::io::{self, Read, Write};
+
+use ::{
+    Circuit,
+    ConstraintSystem,
+    Index,
+    LinearCombination,
+    SynthesisError,
+    Variable
+};
+
+const R1CS_MAGIC: &[u8; 4] = b"r1cs";
+const R1CS_VERSION: u32 = 1;
+const WTNS_MAGIC: &[u8; 4] = b"wtns";
+const WTNS_VERSION: u32 = 2;
+
+/// Constraint system that records the constraints of a circuit, and
+/// optionally its witness, so they can be written as `.r1cs` and `.wtns`
+/// files.
+pub struct R1csConstraintSystem<E: Engine> {
+    num_inputs: usize,
+    num_aux: usize,
+    constraints: Vec<[LinearCombination<E>; 3]>,
+    // Assignments of inputs (starting with one) and aux variables, if
+    // `record_witness` is set.
+    record_witness: bool,
+    input_assignment: Vec<E::Fr>,
+    aux_assignment: Vec<E::Fr>
+}
+
+impl<E: Engine> Default for R1csConstraintSystem<E> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<E: Engine> R1csConstraintSystem<E> {
+    /// Records the constraints only. Assignments are not evaluated, so
+    /// circuits can be exported without a witness.
+    pub fn new() -> Self {
+        R1csConstraintSystem {
+            num_inputs: 1,
+            num_aux: 0,
+            constraints: vec![],
+            record_witness: false,
+            input_assignment: vec![],
+            aux_assignment: vec![]
+        }
+    }
+
+    /// Records the constraints and the assignments of every variable.
+    pub fn with_witness() -> Self {
+        R1csConstraintSystem {
+            record_witness: true,
+            input_assignment: vec![E::Fr::one()],
+            ..Self::new()
+        }
+    }
+
+    /// Number of inputs, including the constant one.
+    pub fn num_inputs(&self) -> usize {
+        self.num_inputs
+    }
+
+    pub fn num_aux(&self) -> usize {
+        self.num_aux
+    }
+
+    pub fn num_constraints(&self) -> usize {
+        self.constraints.len()
+    }
+
+    /// The number of wires, which is the number of inputs (including the
+    /// constant one) and aux variables.
+    pub fn num_wires(&self) -> usize {
+        self.num_inputs + self.num_aux
+    }
+
+    fn wire(&self, index: Index) -> u32 {
+        match index {
+            Index::Input(i) => i as u32,
+            Index::Aux(i) => (self.num_inputs + i) as u32
+        }
+    }
+
+    /// Writes the constraint system in the `.r1cs` format.
+    pub fn write_r1cs<W: Write>(
+        &self,
+        mut writer: W
+    ) -> io::Result<()>
+    {
+        let mut header = vec![];
+        write_field_header::<E, _>(&mut header)?;
+        header.write_u32::<LittleEndian>(self.num_wires() as u32)?;
+        // Public outputs
+        header.write_u32::<LittleEndian>(0)?;
+        header.write_u32::<LittleEndian>((self.num_inputs - 1) as u32)?;
+        // Private inputs
+        header.write_u32::<LittleEndian>(0)?;
+        header.write_u64::<LittleEndian>(self.num_wires() as u64)?;
+        header.write_u32::<LittleEndian>(self.constraints.len() as u32)?;
+
+        let mut constraints = vec![];
+        for constraint in &self.constraints {
+            for lc in constraint {
+                // Merge repeated variables and drop zero coefficients so
+                // that equal constraint systems are written identically.
+                let mut terms = BTreeMap::new();
+                for &(var, coeff) in lc.as_ref() {
+                    terms.entry(self.wire(var.0))
+                         .or_insert(E::Fr::zero())
+                         .add_assign(&coeff);
+                }
+                let terms = terms.into_iter()
+                                 .filter(|&(_, coeff)| !coeff.is_zero())
+                                 .collect::<Vec<_>>();
+
+                constraints.write_u32::<LittleEndian>(terms.len() as u32)?;
+                for (wire, coeff) in terms {
+                    constraints.write_u32::<LittleEndian>(wire)?;
+                    write_fr::<E, _>(&mut constraints, &coeff)?;
+                }
+            }
+        }
+
+        let mut labels = vec![];
+        for wire in 0..self.num_wires() {
+            labels.write_u64::<LittleEndian>(wire as u64)?;
+        }
+
+        writer.write_all(R1CS_MAGIC)?;
+        writer.write_u32::<LittleEndian>(R1CS_VERSION)?;
+        writer.write_u32::<LittleEndian>(3)?;
+        write_section(&mut writer, 1, &header)?;
+        write_section(&mut writer, 2, &constraints)?;
+        write_section(&mut writer, 3, &labels)?;
+
+        Ok(())
+    }
+
+    /// Writes the recorded witness in the `.wtns` format. Fails if the
+    /// constraint system was not created with `with_witness`.
+    pub fn write_witness<W: Write>(
+        &self,
+        mut writer: W
+    ) -> io::Result<()>
+    {
+        if !self.record_witness {
+            return Err(io::Error::new(io::ErrorKind::InvalidInput, "the witness was not recorded"));
+        }
+
+        let mut header = vec![];
+        write_field_header::<E, _>(&mut header)?;
+        header.write_u32::<LittleEndian>(self.num_wires() as u32)?;
+
+        let mut values = vec![];
+        for value in self.input_assignment.iter().chain(self.aux_assignment.iter()) {
+            write_fr::<E, _>(&mut values, value)?;
+        }
+
+        writer.write_all(WTNS_MAGIC)?;
+        writer.write_u32::<LittleEndian>(WTNS_VERSION)?;
+        writer.write_u32::<LittleEndian>(2)?;
+        write_section(&mut writer, 1, &header)?;
+        write_section(&mut writer, 2, &values)?;
+
+        Ok(())
+    }
+}
+
+/// Size in bytes of an encoded field element.
+fn field_size<E: Engine>() -> usize {
+    E::Fr::char().as_ref().len() * 8
+}
+
+fn write_section<W: Write>(
+    writer: &mut W,
+    ty: u32,
+    contents: &[u8]
+) -> io::Result<()>
+{
+    writer.write_u32::<LittleEndian>(ty)?;
+    writer.write_u64::<LittleEndian>(contents.len() as u64)?;
+    writer.write_all(contents)
+}
+
+fn write_field_header<E: Engine, W: Write>(
+    writer: &mut W
+) -> io::Result<()>
+{
+    let mut modulus = vec![0u8; field_size::<E>()];
+    E::Fr::char().write_le(&mut modulus[..]).expect("buffer has the size of the representation");
+
+    writer.write_u32::<LittleEndian>(modulus.len() as u32)?;
+    writer.write_all(&modulus)
+}
+
+fn write_fr<E: Engine, W: Write>(
+    writer: &mut W,
+    value: &E::Fr
+) -> io::Result<()>
+{
+    let mut buf = vec![0u8; field_size::<E>()];
+    value.into_repr().write_le(&mut buf[..]).expect("buffer has the size of the representation");
+
+    writer.write_all(&buf)
+}
+
+impl<E: Engine> ConstraintSystem<E> for R1csConstraintSystem<E> {
+    type Root = Self;
+
+    fn alloc<F, A, AR>(
+        &mut self,
+        _: A,
+        f: F
+    ) -> Result<Variable, SynthesisError>
+        where F: FnOnce() -> Result<E::Fr, SynthesisError>, A: FnOnce() -> AR, AR: Into<String>
+    {
+        if self.record_witness {
+            self.aux_assignment.push(f()?);
+        }
+
+        let index = self.num_aux;
+        self.num_aux += 1;
+
+        Ok(Variable(Index::Aux(index)))
+    }
+
+    fn alloc_input<F, A, AR>(
+        &mut self,
+        _: A,
+        f: F
+    ) -> Result<Variable, SynthesisError>
+        where F: FnOnce() -> Result<E::Fr, SynthesisError>, A: FnOnce() -> AR, AR: Into<String>
+    {
+        if self.record_witness {
+            self.input_assignment.push(f()?);
+        }
+
+        let index = self.num_inputs;
+        self.num_inputs += 1;
+
+        Ok(Variable(Index::Input(index)))
+    }
+
+    fn enforce<A, AR, LA, LB, LC>(
+        &mut self,
+        _: A,
+        a: LA,
+        b: LB,
+        c: LC
+    )
+        where A: FnOnce() -> AR, AR: Into<String>,
+              LA: FnOnce(LinearCombination<E>) -> LinearCombination<E>,
+              LB: FnOnce(LinearCombination<E>) -> LinearCombination<E>,
+              LC: FnOnce(LinearCombination<E>) -> LinearCombination<E>
+    {
+        self.constraints.push([
+            a(LinearCombination::zero()),
+            b(LinearCombination::zero()),
+            c(LinearCombination::zero())
+        ]);
+    }
+
+    fn push_namespace<NR, N>(&mut self, _: N)
+        where NR: Into<String>, N: FnOnce() -> NR
+    {
+        // Do nothing; we don't care about namespaces in this context.
+    }
+
+    fn pop_namespace(&mut self)
+    {
+        // Do nothing; we don't care about namespaces in this context.
+    }
+
+    fn get_root(&mut self) -> &mut Self::Root {
+        self
+    }
+}
+
+fn invalid_data(msg: &str) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, msg)
+}
+
+/// Reads the magic, version and sections of a file, returning the
+/// contents of each section with its type.
+fn read_sections<R: Read>(
+    mut reader: R,
+    magic: &[u8; 4],
+    version: u32
+) -> io::Result<Vec<(u32, Vec<u8>)>>
+{
+    let mut buf = [0u8; 4];
+    reader.read_exact(&mut buf)?;
+    if &buf != magic {
+        return Err(invalid_data("invalid magic"));
+    }
+
+    if reader.read_u32::<LittleEndian>()? != version {
+        return Err(invalid_data("unsupported version"));
+    }
+
+    let num_sections = reader.read_u32::<LittleEndian>()?;
+    let mut sections = vec![];
+    for _ in 0..num_sections {
+        let ty = reader.read_u32::<LittleEndian>()?;
+        let len = reader.read_u64::<LittleEndian>()?;
+
+        // Read through `take` so that a bogus length cannot make us
+        // allocate more than the file contains.
+        let mut contents = vec![];
+        (&mut reader).take(len).read_to_end(&mut contents)?;
+        if contents.len() as u64 != len {
+            return Err(io::Error::new(io::ErrorKind::UnexpectedEof, "section is truncated"));
+        }
+
+        sections.push((ty, contents));
+    }
+
+    Ok(sections)
+}
+
+/// Finds the only section of type `ty`.
+fn section(
+    sections: &[(u32, Vec<u8>)],
+    ty: u32
+) -> io::Result<&[u8]>
+{
+    let mut found = sections.iter().filter(|s| s.0 == ty);
+
+    match (found.next(), found.next()) {
+        (Some(s), None) => Ok(&s.1),
+        (None, _) => Err(invalid_data("missing section")),
+        _ => Err(invalid_data("duplicate section"))
+    }
+}
+
+/// Reads `n8` and the modulus, checking that they describe `E::Fr`.
+fn read_field_header<E: Engine>(
+    reader: &mut &[u8]
+) -> io::Result<()>
+{
+    let mut modulus = vec![0u8; field_size::<E>()];
+    E::Fr::char().write_le(&mut modulus[..]).expect("buffer has the size of the representation");
+
+    if reader.read_u32::<LittleEndian>()? as usize != modulus.len() {
+        return Err(invalid_data("field element size does not match the scalar field"));
+    }
+
+    let mut buf = vec![0u8; modulus.len()];
+    reader.read_exact(&mut buf)?;
+    if buf != modulus {
+        return Err(invalid_data("modulus does not match the scalar field"));
+    }
+
+    Ok(())
+}
+
+fn read_fr<E: Engine>(
+    reader: &mut &[u8]
+) -> io::Result<E::Fr>
+{
+    let mut buf = vec![0u8; field_size::<E>()];
+    reader.read_exact(&mut buf)?;
+
+    let mut repr = <E::Fr as PrimeField>::Repr::default();
+    for (digit, bytes) in repr.as_mut().iter_mut().zip(buf.chunks(8)) {
+        *digit = LittleEndian::read_u64(bytes);
+    }
+
+    E::Fr::from_repr(repr).map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))
+}
+
+/// A constraint system read from a `.r1cs` file.
+pub struct R1cs<E: Engine> {
+    num_wires: usize,
+    // Public outputs and public inputs, not counting the constant one.
+    num_public: usize,
+    constraints: Vec<[WireTerms<E>; 3]>
+}
+
+// The terms of a linear combination as (wire, coefficient) pairs.
+type WireTerms<E> = Vec<(usize, <E as Engine>::Fr)>;
+
+impl<E: Engine> R1cs<E> {
+    /// Reads a constraint system over `E::Fr` in the `.r1cs` format.
+    /// Sections other than the header, the constraints and the wire to
+    /// label map (such as custom gates) are rejected.
+    pub fn read<R: Read>(
+        reader: R
+    ) -> io::Result<Self>
+    {
+        let sections = read_sections(reader, R1CS_MAGIC, R1CS_VERSION)?;
+        if sections.iter().any(|s| s.0 < 1 || s.0 > 3) {
+            return Err(invalid_data("unsupported section"));
+        }
+
+        let mut header = section(&sections, 1)?;
+        read_field_header::<E>(&mut header)?;
+        let num_wires = header.read_u32::<LittleEndian>()? as usize;
+        let num_pub_out = header.read_u32::<LittleEndian>()? as usize;
+        let num_pub_in = header.read_u32::<LittleEndian>()? as usize;
+        // Private inputs are ordinary aux variables to bellman, but they
+        // must still fit in the wires after the public ones.
+        let num_prv_in = header.read_u32::<LittleEndian>()? as usize;
+        let _num_labels = header.read_u64::<LittleEndian>()?;
+        let num_constraints = header.read_u32::<LittleEndian>()? as usize;
+
+        let num_public = num_pub_out.checked_add(num_pub_in)
+            .filter(|n| *n < num_wires)
+            .ok_or_else(|| invalid_data("more public wires than wires"))?;
+        num_public.checked_add(num_prv_in)
+            .filter(|n| *n < num_wires)
+            .ok_or_else(|| invalid_data("more input wires than wires"))?;
+
+        let mut contents = section(&sections, 2)?;
+        let mut constraints = vec![];
+        for _ in 0..num_constraints {
+            let mut constraint = [vec![], vec![], vec![]];
+
+            for lc in constraint.iter_mut() {
+                let num_terms = contents.read_u32::<LittleEndian>()?;
+                for _ in 0..num_terms {
+                    let wire = contents.read_u32::<LittleEndian>()? as usize;
+                    if wire >= num_wires {
+                        return Err(invalid_data("wire out of range"));
+                    }
+
+                    lc.push((wire, read_fr::<E>(&mut contents)?));
+                }
+            }
+
+            constraints.push(constraint);
+        }
+
+        if !contents.is_empty() {
+            return Err(invalid_data("trailing data in constraints section"));
+        }
+
+        Ok(R1cs {
+            num_wires: num_wires,
+            num_public: num_public,
+            constraints: constraints
+        })
+    }
+
+    /// Number of wires, including the constant one.
+    pub fn num_wires(&self) -> usize {
+        self.num_wires
+    }
+
+    /// Number of public inputs of the circuit, not counting the constant
+    /// one.
+    pub fn num_public(&self) -> usize {
+        self.num_public
+    }
+
+    pub fn num_constraints(&self) -> usize {
+        self.constraints.len()
+    }
+
+    /// The public inputs to pass to `verify_proof` for a witness of this
+    /// constraint system. The witness is checked as in `circuit`.
+    pub fn public_inputs<'a>(
+        &self,
+        witness: &'a [E::Fr]
+    ) -> io::Result<&'a [E::Fr]>
+    {
+        self.check_witness(witness)?;
+
+        Ok(&witness[1..self.num_public + 1])
+    }
+
+    // The witness must assign every wire, starting with the constant one.
+    fn check_witness(
+        &self,
+        witness: &[E::Fr]
+    ) -> io::Result<()>
+    {
+        if witness.len() != self.num_wires {
+            return Err(invalid_data("witness does not match the number of wires"));
+        }
+
+        if witness[0] != E::Fr::one() {
+            return Err(invalid_data("the first wire of the witness must be one"));
+        }
+
+        Ok(())
+    }
+
+    /// A circuit replaying this constraint system, for parameter
+    /// generation when `witness` is `None` and for proving otherwise.
+    /// The witness must assign every wire, starting with the constant one.
+    pub fn circuit<'a>(
+        &'a self,
+        witness: Option<&'a [E::Fr]>
+    ) -> io::Result<R1csCircuit<'a, E>>
+    {
+        if let Some(witness) = witness {
+            self.check_witness(witness)?;
+        }
+
+        Ok(R1csCircuit {
+            r1cs: self,
+            witness: witness
+        })
+    }
+}
+
+/// Reads a witness over `E::Fr` in the `.wtns` format, returning the
+/// assignment of every wire.
+pub fn read_witness<E: Engine, R: Read>(
+    reader: R
+) -> io::Result<Vec<E::Fr>>
+{
+    let sections = read_sections(reader, WTNS_MAGIC, WTNS_VERSION)?;
+
+    let mut header = section(&sections, 1)?;
+    read_field_header::<E>(&mut header)?;
+    let num_witness = header.read_u32::<LittleEndian>()? as usize;
+
+    let mut contents = section(&sections, 2)?;
+    if contents.len() != num_witness * field_size::<E>() {
+        return Err(invalid_data("witness section does not match its header"));
+    }
+
+    let mut witness = Vec::with_capacity(num_witness);
+    for _ in 0..num_witness {
+        witness.push(read_fr::<E>(&mut contents)?);
+    }
+
+    Ok(witness)
+}
+
+/// A circuit that allocates the wires of an `R1cs` in order, public wires
+/// with `alloc_input` and the others with `alloc`, and then enforces its
+/// constraints.
+pub struct R1csCircuit<'a, E: Engine> {
+    r1cs: &'a R1cs<E>,
+    witness: Option<&'a [E::Fr]>
+}
+
+impl<'a, E: Engine> Circuit<E> for R1csCircuit<'a, E> {
+    fn synthesize<CS: ConstraintSystem<E>>(
+        self,
+        cs: &mut CS
+    ) -> Result<(), SynthesisError>
+    {
+        let witness = self.witness;
+        let value = |wire: usize| {
+            witness.map(|w| w[wire]).ok_or(SynthesisError::AssignmentMissing)
+        };
+
+        let mut vars = Vec::with_capacity(self.r1cs.num_wires);
+        vars.push(CS::one());
+
+        for wire in 1..self.r1cs.num_wires {
+            let var = if wire <= self.r1cs.num_public {
+                cs.alloc_input(|| format!("wire {}", wire), || value(wire))?
+            } else {
+                cs.alloc(|| format!("wire {}", wire), || value(wire))?
+            };
+
+            vars.push(var);
+        }
+
+        for (i, constraint) in self.r1cs.constraints.iter().enumerate() {
+            let lc = |lc: LinearCombination<E>, terms: &[(usize, E::Fr)]| {
+                terms.iter().fold(lc, |lc, &(wire, coeff)| lc + (coeff, vars[wire]))
+            };
+
+            cs.enforce(
+                || format!("constraint {}", i),
+                |zero| lc(zero, &constraint[0]),
+                |zero| lc(zero, &constraint[1]),
+                |zero| lc(zero, &constraint[2])
+            );
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test_with_bls12_381 {
+    use super::*;
+    use {Circuit};
+
+    use pairing::bls12_381::{Bls12, Fr, FrRepr};
+    use groth16::tests::MySillyCircuit;
+    use rand::Rand;
+
+    fn fr(n: u64) -> Vec<u8> {
+        let mut buf = vec![0u8; 32];
+        FrRepr::from(n).write_le(&mut buf[..]).unwrap();
+        buf
+    }
+
+    #[test]
+    fn export() {
+        let mut cs = R1csConstraintSystem::<Bls12>::new();
+        MySillyCircuit::<Bls12> { a: None, b: None }.synthesize(&mut cs).unwrap();
+
+        assert_eq!(cs.num_inputs(), 2);
+        assert_eq!(cs.num_aux(), 2);
+        assert_eq!(cs.num_constraints(), 1);
+        assert!(cs.write_witness(vec![]).is_err());
+
+        let mut v = vec![];
+        cs.write_r1cs(&mut v).unwrap();
+
+        let mut modulus = vec![0u8; 32];
+        Fr::char().write_le(&mut modulus[..]).unwrap();
+
+        let mut expected = vec![];
+        expected.extend_from_slice(b"r1cs");
+        expected.extend_from_slice(&[1, 0, 0, 0, 3, 0, 0, 0]);
+
+        // Header: n8, modulus, 4 wires, 0 outputs, 1 input, 0 private
+        // inputs, 4 labels, 1 constraint.
+        expected.extend_from_slice(&[1, 0, 0, 0, 64, 0, 0, 0, 0, 0, 0, 0]);
+        expected.extend_from_slice(&[32, 0, 0, 0]);
+        expected.extend_from_slice(&modulus);
+        expected.extend_from_slice(&[4, 0, 0, 0, 0, 0, 0, 0, 1, 0, 0, 0, 0, 0, 0, 0]);
+        expected.extend_from_slice(&[4, 0, 0, 0, 0, 0, 0, 0, 1, 0, 0, 0]);
+
+        // Constraints: a is wire 2, b is wire 3 and c is wire 1.
+        expected.extend_from_slice(&[2, 0, 0, 0, 3 * (4 + 36), 0, 0, 0, 0, 0, 0, 0]);
+        for &wire in &[2u8, 3, 1] {
+            expected.extend_from_slice(&[1, 0, 0, 0, wire, 0, 0, 0]);
+            expected.extend_from_slice(&fr(1));
+        }
+
+        // Labels
+        expected.extend_from_slice(&[3, 0, 0, 0, 32, 0, 0, 0, 0, 0, 0, 0]);
+        for wire in 0..4 {
+            expected.extend_from_slice(&[wire, 0, 0, 0, 0, 0, 0, 0]);
+        }
+
+        assert_eq!(v, expected);
+
+        let mut cs = R1csConstraintSystem::<Bls12>::with_witness();
+        MySillyCircuit::<Bls12> {
+            a: Some(Fr::from_repr(FrRepr::from(3)).unwrap()),
+            b: Some(Fr::from_repr(FrRepr::from(5)).unwrap())
+        }.synthesize(&mut cs).unwrap();
+
+        let mut r1cs = vec![];
+        cs.write_r1cs(&mut r1cs).unwrap();
+        assert_eq!(r1cs, v);
+
+        let mut v = vec![];
+        cs.write_witness(&mut v).unwrap();
+
+        assert_eq!(&v[..12], b"wtns\x02\x00\x00\x00\x02\x00\x00\x00");
+        assert_eq!(LittleEndian::read_u32(&v[12..]), 1);
+        assert_eq!(LittleEndian::read_u64(&v[16..]), 4 + 32 + 4);
+        assert_eq!(LittleEndian::read_u32(&v[24..]), 32);
+        assert_eq!(&v[28..60], &modulus[..]);
+        assert_eq!(LittleEndian::read_u32(&v[60..]), 4);
+        assert_eq!(LittleEndian::read_u32(&v[64..]), 2);
+        assert_eq!(LittleEndian::read_u64(&v[68..]), 4 * 32);
+
+        let mut values = vec![];
+        for n in &[1, 15, 3, 5] {
+            values.extend_from_slice(&fr(*n));
+        }
+        assert_eq!(&v[76..], &values[..]);
+
+        // Missing assignments are reported when recording the witness.
+        let mut cs = R1csConstraintSystem::<Bls12>::with_witness();
+        assert!(MySillyCircuit::<Bls12> { a: None, b: None }.synthesize(&mut cs).is_err());
+    }
+
+    #[test]
+    fn import() {
+        use groth16::{
+            generate_random_parameters,
+            create_random_proof,
+            prepare_verifying_key,
+            verify_proof
+        };
+        use rand::thread_rng;
+
+        let rng = &mut thread_rng();
+
+        let a = Fr::rand(rng);
+        let b = Fr::rand(rng);
+        let mut c = a;
+        c.mul_assign(&b);
+
+        let mut cs = R1csConstraintSystem::<Bls12>::with_witness();
+        MySillyCircuit::<Bls12> { a: Some(a), b: Some(b) }.synthesize(&mut cs).unwrap();
+
+        let mut r1cs_file = vec![];
+        cs.write_r1cs(&mut r1cs_file).unwrap();
+        let mut wtns_file = vec![];
+        cs.write_witness(&mut wtns_file).unwrap();
+
+        let r1cs = R1cs::<Bls12>::read(&r1cs_file[..]).unwrap();
+        let witness = read_witness::<Bls12, _>(&wtns_file[..]).unwrap();
+
+        assert_eq!(r1cs.num_wires(), 4);
+        assert_eq!(r1cs.num_public(), 1);
+        assert_eq!(r1cs.num_constraints(), 1);
+        assert_eq!(witness, vec![Fr::one(), c, a, b]);
+        assert_eq!(r1cs.public_inputs(&witness).unwrap(), &[c]);
+
+        // Replaying the imported circuit exports the same files.
+        let mut cs = R1csConstraintSystem::<Bls12>::with_witness();
+        r1cs.circuit(Some(&witness)).unwrap().synthesize(&mut cs).unwrap();
+
+        let mut v = vec![];
+        cs.write_r1cs(&mut v).unwrap();
+        assert_eq!(v, r1cs_file);
+        let mut v = vec![];
+        cs.write_witness(&mut v).unwrap();
+        assert_eq!(v, wtns_file);
+
+        let params = generate_random_parameters::<Bls12, _, _>(
+            r1cs.circuit(None).unwrap(),
+            rng
+        ).unwrap();
+        let pvk = prepare_verifying_key(&params.vk);
+
+        let proof = create_random_proof(
+            r1cs.circuit(Some(&witness)).unwrap(),
+            &params,
+            rng
+        ).unwrap();
+
+        assert!(verify_proof(&pvk, &proof, r1cs.public_inputs(&witness).unwrap()).unwrap());
+
+        // A proof can't be created without a witness, or from a witness of
+        // the wrong shape.
+        assert!(create_random_proof(r1cs.circuit(None).unwrap(), &params, rng).is_err());
+        assert!(r1cs.circuit(Some(&witness[1..])).is_err());
+        assert!(r1cs.circuit(Some(&[c, c, a, b])).is_err());
+        assert!(r1cs.public_inputs(&witness[..1]).is_err());
+        assert!(r1cs.public_inputs(&[c, c, a, b]).is_err());
+
+        // Malformed files are rejected.
+        assert!(R1cs::<Bls12>::read(&r1cs_file[..r1cs_file.len() - 1]).is_err());
+        assert!(R1cs::<Bls12>::read(&wtns_file[..]).is_err());
+        assert!(read_witness::<Bls12, _>(&r1cs_file[..]).is_err());
+
+        // The modulus in the header
+        let mut bad = r1cs_file.clone();
+        bad[28] ^= 1;
+        assert!(R1cs::<Bls12>::read(&bad[..]).is_err());
+
+        // More private inputs than there are wires left
+        let mut bad = r1cs_file.clone();
+        assert_eq!(bad[72], 0);
+        bad[72] = 3;
+        assert!(R1cs::<Bls12>::read(&bad[..]).is_err());
+        bad[72] = 2;
+        assert!(R1cs::<Bls12>::read(&bad[..]).is_ok());
+
+        // The wire of the first term of A
+        let mut bad = r1cs_file.clone();
+        let constraints = 12 + 12 + 64 + 12 + 4;
+        assert_eq!(bad[constraints], 2);
+        bad[constraints] = 4;
+        assert!(R1cs::<Bls12>::read(&bad[..]).is_err());
+
+        // A coefficient that is not in the field
+        let mut bad = r1cs_file.clone();
+        for byte in &mut bad[constraints + 4..constraints + 36] {
+            *byte = 0xff;
+        }
+        assert!(R1cs::<Bls12>::read(&bad[..]).is_err());
+    }
+
+    #[test]
+    fn circom() {
+        use groth16::{
+            generate_random_parameters,
+            create_random_proof,
+            prepare_verifying_key,
+            verify_proof
+        };
+        use rand::thread_rng;
+
+        // Hand-written files for tests/circom/multiplier.circom and the
+        // witness for tests/circom/input.json, laid out after the format
+        // description rather than generated by circom. The output c = a * b
+        // and the public input a are public, b is private. Unlike the files
+        // bellman writes, they have public outputs, private inputs and
+        // negated coefficients.
+        let r1cs_file = include_bytes!("../tests/circom/multiplier.r1cs");
+        let wtns_file = include_bytes!("../tests/circom/multiplier.wtns");
+
+        let rng = &mut thread_rng();
+
+        let r1cs = R1cs::<Bls12>::read(&r1cs_file[..]).unwrap();
+        let witness = read_witness::<Bls12, _>(&wtns_file[..]).unwrap();
+
+        let n = |n| Fr::from_repr(FrRepr::from(n)).unwrap();
+        assert_eq!(r1cs.num_wires(), 4);
+        assert_eq!(r1cs.num_public(), 2);
+        assert_eq!(r1cs.num_constraints(), 1);
+        assert_eq!(witness, vec![Fr::one(), n(33), n(3), n(11)]);
+        assert_eq!(r1cs.public_inputs(&witness).unwrap(), &[n(33), n(3)]);
+
+        let params = generate_random_parameters::<Bls12, _, _>(
+            r1cs.circuit(None).unwrap(),
+            rng
+        ).unwrap();
+        let pvk = prepare_verifying_key(&params.vk);
+
+        let proof = create_random_proof(
+            r1cs.circuit(Some(&witness)).unwrap(),
+            &params,
+            rng
+        ).unwrap();
+
+        assert!(verify_proof(&pvk, &proof, &[n(33), n(3)]).unwrap());
+        assert!(!verify_proof(&pvk, &proof, &[n(33), n(11)]).unwrap());
+    }
+}
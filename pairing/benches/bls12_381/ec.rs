@@ -2,7 +2,7 @@ mod g1 {
     use rand::{Rand, SeedableRng, XorShiftRng};
 
     use pairing::bls12_381::*;
-    use pairing::CurveProjective;
+    use pairing::{BitIterator, CurveAffine, CurveProjective, PrimeField, Wnaf};
 
     #[bench]
     fn bench_g1_mul_assign(b: &mut ::test::Bencher) {
@@ -23,6 +23,65 @@ mod g1 {
         });
     }
 
+    #[bench]
+    fn bench_g1_affine_mul(b: &mut ::test::Bencher) {
+        const SAMPLES: usize = 1000;
+
+        let mut rng = XorShiftRng::from_seed([0x5dbe6259, 0x8d313d76, 0x3237db17, 0xe5bc0654]);
+
+        let v: Vec<(G1Affine, Fr)> = (0..SAMPLES)
+            .map(|_| (G1::rand(&mut rng).into_affine(), Fr::rand(&mut rng)))
+            .collect();
+
+        let mut count = 0;
+        b.iter(|| {
+            let tmp = v[count].0.mul(v[count].1);
+            count = (count + 1) % SAMPLES;
+            tmp
+        });
+    }
+
+    #[bench]
+    fn bench_g1_wnaf(b: &mut ::test::Bencher) {
+        // Single-scalar w-NAF without the endomorphism, for comparison
+        // with `mul_assign`.
+        const SAMPLES: usize = 1000;
+
+        let mut rng = XorShiftRng::from_seed([0x5dbe6259, 0x8d313d76, 0x3237db17, 0xe5bc0654]);
+
+        let v: Vec<(G1, Fr)> = (0..SAMPLES)
+            .map(|_| (G1::rand(&mut rng), Fr::rand(&mut rng)))
+            .collect();
+
+        let mut wnaf = Wnaf::new();
+        let mut count = 0;
+        b.iter(|| {
+            let tmp = wnaf.scalar(v[count].1.into_repr()).base(v[count].0);
+            count = (count + 1) % SAMPLES;
+            tmp
+        });
+    }
+
+    #[bench]
+    fn bench_g1_mul_bits(b: &mut ::test::Bencher) {
+        // Double-and-add without the endomorphism, the way points outside
+        // of the prime order subgroup are multiplied.
+        const SAMPLES: usize = 1000;
+
+        let mut rng = XorShiftRng::from_seed([0x5dbe6259, 0x8d313d76, 0x3237db17, 0xe5bc0654]);
+
+        let v: Vec<(G1Affine, Fr)> = (0..SAMPLES)
+            .map(|_| (G1::rand(&mut rng).into_affine(), Fr::rand(&mut rng)))
+            .collect();
+
+        let mut count = 0;
+        b.iter(|| {
+            let tmp = v[count].0.mul_bits(BitIterator::new(v[count].1.into_repr()));
+            count = (count + 1) % SAMPLES;
+            tmp
+        });
+    }
+
     #[bench]
     fn bench_g1_add_assign(b: &mut ::test::Bencher) {
         const SAMPLES: usize = 1000;
@@ -66,7 +125,7 @@ mod g2 {
     use rand::{Rand, SeedableRng, XorShiftRng};
 
     use pairing::bls12_381::*;
-    use pairing::CurveProjective;
+    use pairing::{BitIterator, CurveAffine, CurveProjective, PrimeField, Wnaf};
 
     #[bench]
     fn bench_g2_mul_assign(b: &mut ::test::Bencher) {
@@ -87,6 +146,65 @@ mod g2 {
         });
     }
 
+    #[bench]
+    fn bench_g2_affine_mul(b: &mut ::test::Bencher) {
+        const SAMPLES: usize = 1000;
+
+        let mut rng = XorShiftRng::from_seed([0x5dbe6259, 0x8d313d76, 0x3237db17, 0xe5bc0654]);
+
+        let v: Vec<(G2Affine, Fr)> = (0..SAMPLES)
+            .map(|_| (G2::rand(&mut rng).into_affine(), Fr::rand(&mut rng)))
+            .collect();
+
+        let mut count = 0;
+        b.iter(|| {
+            let tmp = v[count].0.mul(v[count].1);
+            count = (count + 1) % SAMPLES;
+            tmp
+        });
+    }
+
+    #[bench]
+    fn bench_g2_wnaf(b: &mut ::test::Bencher) {
+        // Single-scalar w-NAF without the endomorphism, for comparison
+        // with `mul_assign`.
+        const SAMPLES: usize = 1000;
+
+        let mut rng = XorShiftRng::from_seed([0x5dbe6259, 0x8d313d76, 0x3237db17, 0xe5bc0654]);
+
+        let v: Vec<(G2, Fr)> = (0..SAMPLES)
+            .map(|_| (G2::rand(&mut rng), Fr::rand(&mut rng)))
+            .collect();
+
+        let mut wnaf = Wnaf::new();
+        let mut count = 0;
+        b.iter(|| {
+            let tmp = wnaf.scalar(v[count].1.into_repr()).base(v[count].0);
+            count = (count + 1) % SAMPLES;
+            tmp
+        });
+    }
+
+    #[bench]
+    fn bench_g2_mul_bits(b: &mut ::test::Bencher) {
+        // Double-and-add without the endomorphism, the way points outside
+        // of the prime order subgroup are multiplied.
+        const SAMPLES: usize = 1000;
+
+        let mut rng = XorShiftRng::from_seed([0x5dbe6259, 0x8d313d76, 0x3237db17, 0xe5bc0654]);
+
+        let v: Vec<(G2Affine, Fr)> = (0..SAMPLES)
+            .map(|_| (G2::rand(&mut rng).into_affine(), Fr::rand(&mut rng)))
+            .collect();
+
+        let mut count = 0;
+        b.iter(|| {
+            let tmp = v[count].0.mul_bits(BitIterator::new(v[count].1.into_repr()));
+            count = (count + 1) % SAMPLES;
+            tmp
+        });
+    }
+
     #[bench]
     fn bench_g2_add_assign(b: &mut ::test::Bencher) {
         const SAMPLES: usize = 1000;
@@ -0,0 +1,607 @@
+//! BLS signatures over BLS12-381, following the proof-of-possession scheme
+//! of [draft-irtf-cfrg-bls-signature-05](https://datatracker.ietf.org/doc/html/draft-irtf-cfrg-bls-signature-05)
+//! with public keys in G1 and signatures in G2 (the
+//! `BLS_SIG_BLS12381G2_XMD:SHA-256_SSWU_RO_POP_` ciphersuite).
+//!
+//! Aggregating public keys is only safe for keys whose owners have proven
+//! possession of the secret key; otherwise an attacker can choose a "rogue"
+//! key that cancels out the honest ones. `PublicKey::verify_possession`
+//! must succeed for every key passed to `fast_aggregate_verify`.
+
+use bls12_381::{Bls12, Fr, FrRepr, G1, G1Affine, G1Compressed, G2, G2Affine};
+use core::fmt;
+use rand::{Rand, Rng};
+use rstd::prelude::*;
+use sha256::{sha256, Sha256, BLOCK_SIZE, OUTPUT_SIZE};
+use {CurveAffine, CurveProjective, EncodedPoint, Engine, Field, PrimeField};
+
+/// The domain separation tag used to hash messages.
+pub const SIGNATURE_DST: &[u8] = b"BLS_SIG_BLS12381G2_XMD:SHA-256_SSWU_RO_POP_";
+
+/// The domain separation tag used to hash public keys into proofs of
+/// possession.
+pub const POP_DST: &[u8] = b"BLS_POP_BLS12381G2_XMD:SHA-256_SSWU_RO_POP_";
+
+/// A secret key, a nonzero scalar. Its `Debug` output leaves out the
+/// scalar.
+#[derive(Clone, PartialEq, Eq)]
+pub struct SecretKey(Fr);
+
+/// A public key, a point of G1 other than the identity.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct PublicKey(G1Affine);
+
+/// A signature, or the aggregate of several signatures, in G2.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct Signature(G2Affine);
+
+/// A proof that the owner of a public key knows its secret key.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct ProofOfPossession(G2Affine);
+
+impl SecretKey {
+    /// Derives a secret key from at least 32 bytes of keying material
+    /// `ikm`, which must be kept secret, and optional `key_info`, with the
+    /// `KeyGen` procedure of the draft. Returns `None` if `ikm` is too short.
+    pub fn key_gen(ikm: &[u8], key_info: &[u8]) -> Option<SecretKey> {
+        // ceil((3 * ceil(log2(r))) / 16)
+        const L: usize = 48;
+
+        if ikm.len() < 32 {
+            return None;
+        }
+
+        let mut salt = b"BLS-SIG-KEYGEN-SALT-".to_vec();
+        loop {
+            salt = sha256(&[&salt]).to_vec();
+
+            let prk = hmac_sha256(&salt, &[ikm, &[0]]);
+            let mut okm = [0u8; L];
+            hkdf_expand(&prk, &[key_info, &[0, L as u8]], &mut okm);
+
+            let sk = fr_from_okm(&okm);
+            if !sk.is_zero() {
+                return Some(SecretKey(sk));
+            }
+        }
+    }
+
+    /// The public key matching this secret key.
+    pub fn public_key(&self) -> PublicKey {
+        PublicKey(G1Affine::one().mul(self.0).into_affine())
+    }
+
+    /// Signs `msg`.
+    pub fn sign(&self, msg: &[u8]) -> Signature {
+        Signature(self.hash_and_sign(msg, SIGNATURE_DST))
+    }
+
+    /// Proves possession of this secret key, by signing the compressed
+    /// encoding of the public key under a separate tag.
+    pub fn prove_possession(&self) -> ProofOfPossession {
+        let pk = G1Compressed::from_affine(self.public_key().0);
+        ProofOfPossession(self.hash_and_sign(pk.as_ref(), POP_DST))
+    }
+
+    fn hash_and_sign(&self, msg: &[u8], dst: &[u8]) -> G2Affine {
+        let mut h = G2::hash_to_curve(msg, dst);
+        h.mul_assign(self.0);
+        h.into_affine()
+    }
+}
+
+impl fmt::Debug for SecretKey {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "SecretKey(<redacted>)")
+    }
+}
+
+impl Rand for SecretKey {
+    fn rand<R: Rng>(rng: &mut R) -> Self {
+        loop {
+            let sk = Fr::rand(rng);
+            if !sk.is_zero() {
+                return SecretKey(sk);
+            }
+        }
+    }
+}
+
+impl PublicKey {
+    /// Wraps a point of G1 as a public key. Returns `None` for the identity,
+    /// which is never a valid key.
+    ///
+    /// The point must be in the prime-order subgroup, which is the case for
+    /// every point decoded with `EncodedPoint::into_affine`.
+    pub fn from_affine(p: G1Affine) -> Option<PublicKey> {
+        if p.is_zero() {
+            None
+        } else {
+            Some(PublicKey(p))
+        }
+    }
+
+    pub fn into_affine(self) -> G1Affine {
+        self.0
+    }
+
+    /// Checks that `sig` is a signature of `msg` under this key.
+    pub fn verify(&self, msg: &[u8], sig: &Signature) -> bool {
+        core_verify(&[(self.0, msg)], SIGNATURE_DST, &sig.0)
+    }
+
+    /// Checks a proof that the owner of this key knows its secret key.
+    pub fn verify_possession(&self, pop: &ProofOfPossession) -> bool {
+        let pk = G1Compressed::from_affine(self.0);
+        core_verify(&[(self.0, pk.as_ref())], POP_DST, &pop.0)
+    }
+}
+
+impl Signature {
+    pub fn from_affine(p: G2Affine) -> Signature {
+        Signature(p)
+    }
+
+    pub fn into_affine(self) -> G2Affine {
+        self.0
+    }
+
+    /// Aggregates signatures into a single signature, or returns `None` if
+    /// there are none.
+    pub fn aggregate(sigs: &[Signature]) -> Option<Signature> {
+        if sigs.is_empty() {
+            return None;
+        }
+
+        let mut acc = G2::zero();
+        for sig in sigs {
+            acc.add_assign_mixed(&sig.0);
+        }
+
+        Some(Signature(acc.into_affine()))
+    }
+}
+
+impl ProofOfPossession {
+    pub fn from_affine(p: G2Affine) -> ProofOfPossession {
+        ProofOfPossession(p)
+    }
+
+    pub fn into_affine(self) -> G2Affine {
+        self.0
+    }
+}
+
+/// Checks an aggregate signature of each `msgs[i]` under `pks[i]`. The
+/// messages must be distinct, which rules out rogue key attacks without
+/// proofs of possession.
+pub fn aggregate_verify(pks: &[PublicKey], msgs: &[&[u8]], sig: &Signature) -> bool {
+    if pks.is_empty() || pks.len() != msgs.len() {
+        return false;
+    }
+
+    for (i, msg) in msgs.iter().enumerate() {
+        if msgs[..i].contains(msg) {
+            return false;
+        }
+    }
+
+    let pairs = pks
+        .iter()
+        .zip(msgs.iter())
+        .map(|(pk, msg)| (pk.0, *msg))
+        .collect::<Vec<_>>();
+
+    core_verify(&pairs, SIGNATURE_DST, &sig.0)
+}
+
+/// Checks an aggregate signature of the same `msg` under every key in
+/// `pks`. Possession of each key must have been verified beforehand.
+pub fn fast_aggregate_verify(pks: &[PublicKey], msg: &[u8], sig: &Signature) -> bool {
+    if pks.is_empty() {
+        return false;
+    }
+
+    let mut acc = G1::zero();
+    for pk in pks {
+        acc.add_assign_mixed(&pk.0);
+    }
+    if acc.is_zero() {
+        return false;
+    }
+
+    core_verify(&[(acc.into_affine(), msg)], SIGNATURE_DST, &sig.0)
+}
+
+/// Checks that e(pk_1, H(msg_1)) * ... * e(pk_n, H(msg_n)) = e(g1, sig) with
+/// a single multi-Miller loop and final exponentiation.
+fn core_verify(pairs: &[(G1Affine, &[u8])], dst: &[u8], sig: &G2Affine) -> bool {
+    if sig.is_zero() || pairs.iter().any(|&(ref pk, _)| pk.is_zero()) {
+        return false;
+    }
+
+    let mut neg_g1 = G1Affine::one();
+    neg_g1.negate();
+
+    let mut prepared = pairs
+        .iter()
+        .map(|&(ref pk, msg)| {
+            (
+                pk.prepare(),
+                G2::hash_to_curve(msg, dst).into_affine().prepare(),
+            )
+        })
+        .collect::<Vec<_>>();
+    prepared.push((neg_g1.prepare(), sig.prepare()));
+
+    let terms = prepared.iter().map(|&(ref p, ref q)| (p, q)).collect::<Vec<_>>();
+
+    Bls12::final_exponentiation(&Bls12::miller_loop(&terms))
+        .map(|r| r == <Bls12 as Engine>::Fqk::one())
+        .unwrap_or(false)
+}
+
+/// Reduces 48 bytes of key derivation output, read as a big-endian integer,
+/// modulo r.
+fn fr_from_okm(okm: &[u8]) -> Fr {
+    // Split the integer into 128-bit chunks, each of which is below r.
+    let shift = Fr::from_repr(FrRepr([0, 0, 1, 0])).unwrap();
+    let mut res = Fr::zero();
+    for chunk in okm.chunks(16) {
+        let mut limbs = [0u64; 2];
+        for (i, b) in chunk.iter().enumerate() {
+            limbs[1 - i / 8] |= u64::from(*b) << (8 * (7 - i % 8));
+        }
+
+        res.mul_assign(&shift);
+        res.add_assign(&Fr::from_repr(FrRepr([limbs[0], limbs[1], 0, 0])).unwrap());
+    }
+
+    res
+}
+
+/// HMAC-SHA256 (RFC 2104) of the concatenation of `chunks`.
+fn hmac_sha256(key: &[u8], chunks: &[&[u8]]) -> [u8; OUTPUT_SIZE] {
+    let mut block = [0u8; BLOCK_SIZE];
+    if key.len() > BLOCK_SIZE {
+        block[..OUTPUT_SIZE].copy_from_slice(&sha256(&[key]));
+    } else {
+        block[..key.len()].copy_from_slice(key);
+    }
+
+    let mut ipad = block;
+    let mut opad = block;
+    for (i, o) in ipad.iter_mut().zip(opad.iter_mut()) {
+        *i ^= 0x36;
+        *o ^= 0x5c;
+    }
+
+    let mut inner = Sha256::new();
+    inner.update(&ipad);
+    for chunk in chunks {
+        inner.update(chunk);
+    }
+
+    sha256(&[&opad, &inner.finalize()])
+}
+
+/// HKDF-Expand (RFC 5869) of the pseudorandom key `prk` with the
+/// concatenation of `info` as context, filling `okm`.
+fn hkdf_expand(prk: &[u8], info: &[&[u8]], okm: &mut [u8]) {
+    let mut t = [0u8; OUTPUT_SIZE];
+    for (i, out) in okm.chunks_mut(OUTPUT_SIZE).enumerate() {
+        let counter = [i as u8 + 1];
+        let next = {
+            let mut chunks: Vec<&[u8]> = vec![if i == 0 { &[] } else { &t }];
+            chunks.extend_from_slice(info);
+            chunks.push(&counter);
+            hmac_sha256(prk, &chunks)
+        };
+        t = next;
+
+        out.copy_from_slice(&t[..out.len()]);
+    }
+}
+
+#[cfg(test)]
+use rand::{SeedableRng, XorShiftRng};
+
+#[test]
+fn test_hkdf() {
+    // Test case 1 of RFC 5869.
+    let ikm = [0x0b; 22];
+    let salt = [0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12];
+    let info = [0xf0, 0xf1, 0xf2, 0xf3, 0xf4, 0xf5, 0xf6, 0xf7, 0xf8, 0xf9];
+
+    let prk = hmac_sha256(&salt, &[&ikm]);
+    assert_eq!(
+        prk,
+        [
+            0x07, 0x77, 0x09, 0x36, 0x2c, 0x2e, 0x32, 0xdf, 0x0d, 0xdc, 0x3f, 0x0d, 0xc4, 0x7b,
+            0xba, 0x63, 0x90, 0xb6, 0xc7, 0x3b, 0xb5, 0x0f, 0x9c, 0x31, 0x22, 0xec, 0x84, 0x4a,
+            0xd7, 0xc2, 0xb3, 0xe5
+        ]
+    );
+
+    let mut okm = [0u8; 42];
+    hkdf_expand(&prk, &[&info[..5], &info[5..]], &mut okm);
+    assert_eq!(
+        &okm[..],
+        &[
+            0x3c, 0xb2, 0x5f, 0x25, 0xfa, 0xac, 0xd5, 0x7a, 0x90, 0x43, 0x4f, 0x64, 0xd0, 0x36,
+            0x2f, 0x2a, 0x2d, 0x2d, 0x0a, 0x90, 0xcf, 0x1a, 0x5a, 0x4c, 0x5d, 0xb0, 0x2d, 0x56,
+            0xec, 0xc4, 0xc5, 0xbf, 0x34, 0x00, 0x72, 0x08, 0xd5, 0xb8, 0x87, 0x18, 0x58, 0x65
+        ][..]
+    );
+}
+
+#[test]
+fn test_fr_from_okm() {
+    use PrimeFieldRepr;
+
+    // 2^256 + 1 reduces to 2^256 - 2r + 1.
+    let mut okm = [0u8; 48];
+    okm[15] = 1;
+    okm[47] = 1;
+
+    let mut expected = FrRepr([0, 0, 0, 0]);
+    expected.sub_noborrow(&Fr::char());
+    expected.sub_noborrow(&Fr::char());
+    expected.add_nocarry(&FrRepr::from(1));
+    assert_eq!(fr_from_okm(&okm), Fr::from_repr(expected).unwrap());
+}
+
+#[test]
+fn test_key_gen() {
+    let ikm = [0x42; 32];
+
+    let sk = SecretKey::key_gen(&ikm, b"").unwrap();
+    assert_eq!(sk, SecretKey::key_gen(&ikm, b"").unwrap());
+    assert!(sk != SecretKey::key_gen(&ikm, b"info").unwrap());
+    assert!(sk != SecretKey::key_gen(&[0x43; 32], b"").unwrap());
+
+    assert!(SecretKey::key_gen(&ikm[..31], b"").is_none());
+
+    // The scalar does not show up in logs.
+    assert_eq!(format!("{:?}", sk), "SecretKey(<redacted>)");
+}
+
+#[test]
+fn test_key_gen_vectors() {
+    // The master keys of the test cases of EIP-2333, which derives them
+    // with the KeyGen of the draft and an empty key_info.
+    let vectors: [(&[u8], &str); 4] = [
+        (
+            &[
+                0xc5, 0x52, 0x57, 0xc3, 0x60, 0xc0, 0x7c, 0x72, 0x02, 0x9a, 0xeb, 0xc1, 0xb5, 0x3c,
+                0x05, 0xed, 0x03, 0x62, 0xad, 0xa3, 0x8e, 0xad, 0x3e, 0x3e, 0x9e, 0xfa, 0x37, 0x08,
+                0xe5, 0x34, 0x95, 0x53, 0x1f, 0x09, 0xa6, 0x98, 0x75, 0x99, 0xd1, 0x82, 0x64, 0xc1,
+                0xe1, 0xc9, 0x2f, 0x2c, 0xf1, 0x41, 0x63, 0x0c, 0x7a, 0x3c, 0x4a, 0xb7, 0xc8, 0x1b,
+                0x2f, 0x00, 0x16, 0x98, 0xe7, 0x46, 0x3b, 0x04
+            ],
+            "6083874454709270928345386274498605044986640685124978867557563392430687146096",
+        ),
+        (
+            &[
+                0x31, 0x41, 0x59, 0x26, 0x53, 0x58, 0x97, 0x93, 0x23, 0x84, 0x62, 0x64, 0x33, 0x83,
+                0x27, 0x95, 0x02, 0x88, 0x41, 0x97, 0x16, 0x93, 0x99, 0x37, 0x51, 0x05, 0x82, 0x09,
+                0x74, 0x94, 0x45, 0x92
+            ],
+            "29757020647961307431480504535336562678282505419141012933316116377660817309383",
+        ),
+        (
+            &[
+                0x00, 0x99, 0xff, 0x99, 0x11, 0x11, 0x00, 0x22, 0x99, 0xdd, 0x77, 0x44, 0xee, 0x33,
+                0x55, 0xbb, 0xdd, 0x88, 0x44, 0x11, 0x55, 0x66, 0xcc, 0x55, 0x66, 0x33, 0x55, 0x66,
+                0x88, 0x88, 0xcc, 0x00
+            ],
+            "27580842291869792442942448775674722299803720648445448686099262467207037398656",
+        ),
+        (
+            &[
+                0xd4, 0xe5, 0x67, 0x40, 0xf8, 0x76, 0xae, 0xf8, 0xc0, 0x10, 0xb8, 0x6a, 0x40, 0xd5,
+                0xf5, 0x67, 0x45, 0xa1, 0x18, 0xd0, 0x90, 0x6a, 0x34, 0xe6, 0x9a, 0xec, 0x8c, 0x0d,
+                0xb1, 0xcb, 0x8f, 0xa3
+            ],
+            "19022158461524446591288038168518313374041767046816487870552872741050760015818",
+        )
+    ];
+
+    for &(ikm, sk) in &vectors {
+        assert_eq!(
+            SecretKey::key_gen(ikm, b"").unwrap(),
+            SecretKey(Fr::from_str(sk).unwrap())
+        );
+    }
+}
+
+#[test]
+fn test_sign_vectors() {
+    use bls12_381::G2Compressed;
+    use PrimeFieldRepr;
+
+    // Secret key, public key and signature of the zero 32-byte message from
+    // the `sign` tests of the Ethereum 2.0 BLS test vectors, which use this
+    // ciphersuite.
+    let vectors: [([u8; 32], [u8; 48], [u8; 96]); 3] = [
+        (
+            [
+                0x26, 0x3d, 0xbd, 0x79, 0x2f, 0x5b, 0x1b, 0xe4, 0x7e, 0xd8, 0x5f, 0x89, 0x38, 0xc0,
+                0xf2, 0x95, 0x86, 0xaf, 0x0d, 0x3a, 0xc7, 0xb9, 0x77, 0xf2, 0x1c, 0x27, 0x8f, 0xe1,
+                0x46, 0x20, 0x40, 0xe3
+            ],
+            [
+                0xa4, 0x91, 0xd1, 0xb0, 0xec, 0xd9, 0xbb, 0x91, 0x79, 0x89, 0xf0, 0xe7, 0x4f, 0x0d,
+                0xea, 0x04, 0x22, 0xea, 0xc4, 0xa8, 0x73, 0xe5, 0xe2, 0x64, 0x4f, 0x36, 0x8d, 0xff,
+                0xb9, 0xa6, 0xe2, 0x0f, 0xd6, 0xe1, 0x0c, 0x1b, 0x77, 0x65, 0x4d, 0x06, 0x7c, 0x06,
+                0x18, 0xf6, 0xe5, 0xa7, 0xf7, 0x9a
+            ],
+            [
+                0xb6, 0xed, 0x93, 0x67, 0x46, 0xe0, 0x1f, 0x8e, 0xcf, 0x28, 0x1f, 0x02, 0x09, 0x53,
+                0xfb, 0xf1, 0xf0, 0x1d, 0xeb, 0xd5, 0x65, 0x7c, 0x4a, 0x38, 0x39, 0x40, 0xb0, 0x20,
+                0xb2, 0x65, 0x07, 0xf6, 0x07, 0x63, 0x34, 0xf9, 0x1e, 0x23, 0x66, 0xc9, 0x6e, 0x9a,
+                0xb2, 0x79, 0xfb, 0x51, 0x58, 0x09, 0x03, 0x52, 0xea, 0x1c, 0x5b, 0x0c, 0x92, 0x74,
+                0x50, 0x4f, 0x4f, 0x0e, 0x70, 0x53, 0xaf, 0x24, 0x80, 0x2e, 0x51, 0xe4, 0x56, 0x8d,
+                0x16, 0x4f, 0xe9, 0x86, 0x83, 0x4f, 0x41, 0xe5, 0x5c, 0x8e, 0x85, 0x0c, 0xe1, 0xf9,
+                0x84, 0x58, 0xc0, 0xcf, 0xc9, 0xab, 0x38, 0x0b, 0x55, 0x28, 0x5a, 0x55
+            ],
+        ),
+        (
+            [
+                0x47, 0xb8, 0x19, 0x2d, 0x77, 0xbf, 0x87, 0x1b, 0x62, 0xe8, 0x78, 0x59, 0xd6, 0x53,
+                0x92, 0x27, 0x25, 0x72, 0x4a, 0x5c, 0x03, 0x1a, 0xfe, 0xab, 0xc6, 0x0b, 0xce, 0xf5,
+                0xff, 0x66, 0x51, 0x38
+            ],
+            [
+                0xb3, 0x01, 0x80, 0x3f, 0x8b, 0x5a, 0xc4, 0xa1, 0x13, 0x35, 0x81, 0xfc, 0x67, 0x6d,
+                0xfe, 0xdc, 0x60, 0xd8, 0x91, 0xdd, 0x5f, 0xa9, 0x90, 0x28, 0x80, 0x5e, 0x5e, 0xa5,
+                0xb0, 0x8d, 0x34, 0x91, 0xaf, 0x75, 0xd0, 0x70, 0x7a, 0xda, 0xb3, 0xb7, 0x0c, 0x6a,
+                0x6a, 0x58, 0x02, 0x17, 0xbf, 0x81
+            ],
+            [
+                0xb2, 0x3c, 0x46, 0xbe, 0x3a, 0x00, 0x1c, 0x63, 0xca, 0x71, 0x1f, 0x87, 0xa0, 0x05,
+                0xc2, 0x00, 0xcc, 0x55, 0x0b, 0x94, 0x29, 0xd5, 0xf4, 0xeb, 0x38, 0xd7, 0x43, 0x22,
+                0x14, 0x4f, 0x1b, 0x63, 0x92, 0x6d, 0xa3, 0x38, 0x89, 0x79, 0xe5, 0x32, 0x10, 0x12,
+                0xfb, 0x1a, 0x05, 0x26, 0xbc, 0xd1, 0x00, 0xb5, 0xef, 0x5f, 0xe7, 0x26, 0x28, 0xce,
+                0x4c, 0xd5, 0xe9, 0x04, 0xae, 0xaa, 0x32, 0x79, 0x52, 0x78, 0x43, 0xfa, 0xe5, 0xca,
+                0x9c, 0xa6, 0x75, 0xf4, 0xf5, 0x1e, 0xd8, 0xf8, 0x3b, 0xbf, 0x71, 0x55, 0xda, 0x9e,
+                0xcc, 0x96, 0x63, 0x10, 0x0a, 0x88, 0x5d, 0x5d, 0xc6, 0xdf, 0x96, 0xd9
+            ],
+        ),
+        (
+            [
+                0x32, 0x83, 0x88, 0xaf, 0xf0, 0xd4, 0xa5, 0xb7, 0xdc, 0x92, 0x05, 0xab, 0xd3, 0x74,
+                0xe7, 0xe9, 0x8f, 0x3c, 0xd9, 0xf3, 0x41, 0x8e, 0xdb, 0x4e, 0xaf, 0xda, 0x5f, 0xb1,
+                0x64, 0x73, 0xd2, 0x16
+            ],
+            [
+                0xb5, 0x3d, 0x21, 0xa4, 0xcf, 0xd5, 0x62, 0xc4, 0x69, 0xcc, 0x81, 0x51, 0x4d, 0x4c,
+                0xe5, 0xa6, 0xb5, 0x77, 0xd8, 0x40, 0x3d, 0x32, 0xa3, 0x94, 0xdc, 0x26, 0x5d, 0xd1,
+                0x90, 0xb4, 0x7f, 0xa9, 0xf8, 0x29, 0xfd, 0xd7, 0x96, 0x3a, 0xfd, 0xf9, 0x72, 0xe5,
+                0xe7, 0x78, 0x54, 0x05, 0x1f, 0x6f
+            ],
+            [
+                0x94, 0x8a, 0x7c, 0xb9, 0x9f, 0x76, 0xd6, 0x16, 0xc2, 0xc5, 0x64, 0xce, 0x9b, 0xf4,
+                0xa5, 0x19, 0xf1, 0xbe, 0xa6, 0xb0, 0xa6, 0x24, 0xa0, 0x22, 0x76, 0x44, 0x3c, 0x24,
+                0x58, 0x54, 0x21, 0x9f, 0xab, 0xb8, 0xd4, 0xce, 0x06, 0x1d, 0x25, 0x5a, 0xf5, 0x33,
+                0x0b, 0x07, 0x8d, 0x53, 0x80, 0x68, 0x17, 0x51, 0xaa, 0x70, 0x53, 0xda, 0x2c, 0x98,
+                0xba, 0xe8, 0x98, 0xed, 0xc2, 0x18, 0xc7, 0x5f, 0x07, 0xe2, 0x4d, 0x88, 0x02, 0xa1,
+                0x7c, 0xd1, 0xf6, 0x83, 0x3b, 0x71, 0xe5, 0x8f, 0x5e, 0xb5, 0xb9, 0x42, 0x08, 0xb4,
+                0xd0, 0xbb, 0x38, 0x48, 0xce, 0xcb, 0x07, 0x5e, 0xa2, 0x1b, 0xe1, 0x15
+            ],
+        )
+    ];
+
+    let msg = [0u8; 32];
+    for &(ref sk, ref pk, ref sig) in &vectors {
+        let mut repr = FrRepr::default();
+        repr.read_be(&sk[..]).unwrap();
+        let sk = SecretKey(Fr::from_repr(repr).unwrap());
+
+        let public_key = G1Compressed::from_affine(sk.public_key().into_affine());
+        assert_eq!(public_key.as_ref(), &pk[..]);
+
+        let signature = G2Compressed::from_affine(sk.sign(&msg).into_affine());
+        assert_eq!(signature.as_ref(), &sig[..]);
+        assert!(sk.public_key().verify(&msg, &sk.sign(&msg)));
+    }
+}
+
+#[test]
+fn test_sign_and_verify() {
+    let mut rng = XorShiftRng::from_seed([0x5dbe6259, 0x8d313d76, 0x3237db17, 0xe5bc0654]);
+
+    let sk = SecretKey::rand(&mut rng);
+    let pk = sk.public_key();
+    let sig = sk.sign(b"message");
+
+    assert!(pk.verify(b"message", &sig));
+    assert!(!pk.verify(b"other message", &sig));
+    assert!(!SecretKey::rand(&mut rng).public_key().verify(b"message", &sig));
+    assert!(!pk.verify(b"message", &Signature::from_affine(G2Affine::zero())));
+
+    // A proof of possession is not a signature of the encoded key, and vice
+    // versa, since the two use different tags.
+    let pop = sk.prove_possession();
+    assert!(pk.verify_possession(&pop));
+    let encoded = G1Compressed::from_affine(pk.into_affine());
+    assert!(!pk.verify(encoded.as_ref(), &Signature::from_affine(pop.into_affine())));
+    assert!(!pk.verify_possession(&ProofOfPossession::from_affine(
+        sk.sign(encoded.as_ref()).into_affine()
+    )));
+
+    assert!(PublicKey::from_affine(G1Affine::zero()).is_none());
+}
+
+#[test]
+fn test_aggregate_verify() {
+    let mut rng = XorShiftRng::from_seed([0x5dbe6259, 0x8d313d76, 0x3237db17, 0xe5bc0654]);
+
+    let sks = (0..4).map(|_| SecretKey::rand(&mut rng)).collect::<Vec<_>>();
+    let pks = sks.iter().map(|sk| sk.public_key()).collect::<Vec<_>>();
+    let msgs: Vec<&[u8]> = vec![b"zero", b"one", b"two", b"three"];
+
+    let sigs = sks
+        .iter()
+        .zip(msgs.iter())
+        .map(|(sk, msg)| sk.sign(msg))
+        .collect::<Vec<_>>();
+    let sig = Signature::aggregate(&sigs).unwrap();
+
+    assert!(aggregate_verify(&pks, &msgs, &sig));
+    assert!(!aggregate_verify(&pks[1..], &msgs[1..], &sig));
+    assert!(!aggregate_verify(&pks, &[b"zero", b"one", b"two", b"four"], &sig));
+    assert!(!aggregate_verify(&[pks[1], pks[0], pks[2], pks[3]], &msgs, &sig));
+    assert!(!aggregate_verify(&[], &[], &sig));
+
+    // Messages must be distinct.
+    let sigs = [sks[0].sign(b"same"), sks[1].sign(b"same")];
+    let sig = Signature::aggregate(&sigs).unwrap();
+    assert!(!aggregate_verify(&pks[..2], &[b"same", b"same"], &sig));
+
+    assert!(Signature::aggregate(&[]).is_none());
+}
+
+#[test]
+fn test_fast_aggregate_verify() {
+    let mut rng = XorShiftRng::from_seed([0x5dbe6259, 0x8d313d76, 0x3237db17, 0xe5bc0654]);
+
+    let sks = (0..4).map(|_| SecretKey::rand(&mut rng)).collect::<Vec<_>>();
+    let pks = sks.iter().map(|sk| sk.public_key()).collect::<Vec<_>>();
+
+    let sigs = sks.iter().map(|sk| sk.sign(b"block")).collect::<Vec<_>>();
+    let sig = Signature::aggregate(&sigs).unwrap();
+
+    assert!(fast_aggregate_verify(&pks, b"block", &sig));
+    assert!(!fast_aggregate_verify(&pks, b"other block", &sig));
+    assert!(!fast_aggregate_verify(&pks[1..], b"block", &sig));
+    assert!(!fast_aggregate_verify(&[], b"block", &sig));
+}
+
+#[test]
+fn test_rogue_key() {
+    let mut rng = XorShiftRng::from_seed([0x5dbe6259, 0x8d313d76, 0x3237db17, 0xe5bc0654]);
+
+    let honest = SecretKey::rand(&mut rng);
+    let attacker = SecretKey::rand(&mut rng);
+
+    // The rogue key is the attacker's key minus the honest one, so the two
+    // aggregate to a key the attacker alone can sign for.
+    let mut rogue = attacker.public_key().into_affine().into_projective();
+    let mut honest_pk = honest.public_key().into_affine();
+    honest_pk.negate();
+    rogue.add_assign_mixed(&honest_pk);
+    let rogue = PublicKey::from_affine(rogue.into_affine()).unwrap();
+
+    let forged = attacker.sign(b"message");
+    assert!(fast_aggregate_verify(
+        &[honest.public_key(), rogue],
+        b"message",
+        &forged
+    ));
+
+    // Without the secret key behind the rogue key, the attacker cannot
+    // produce a proof of possession for it.
+    assert!(!rogue.verify_possession(&attacker.prove_possession()));
+    assert!(!rogue.verify_possession(&honest.prove_possession()));
+}
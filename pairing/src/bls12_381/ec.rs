@@ -85,7 +85,11 @@ macro_rules! curve_impl {
         }
 
         impl $affine {
-            fn mul_bits<S: AsRef<[u64]>>(&self, bits: BitIterator<S>) -> $projective {
+            /// Multiplies this point by the integer whose bits are `bits`,
+            /// most significant first, with double-and-add. Unlike `mul`,
+            /// this is correct for every point of the curve, including
+            /// points outside of the prime order subgroup.
+            pub fn mul_bits<S: AsRef<[u64]>>(&self, bits: BitIterator<S>) -> $projective {
                 let mut res = $projective::zero();
                 for i in bits {
                     res.double();
@@ -122,6 +126,71 @@ macro_rules! curve_impl {
                 })
             }
 
+            /// The denominator of the slope of the line through `self`
+            /// and `other`, or `None` if their sum is trivial.
+            fn slope_denominator(&self, other: &$affine) -> Option<$basefield> {
+                if self.is_zero() || other.is_zero() {
+                    None
+                } else if self.x != other.x {
+                    let mut d = other.x;
+                    d.sub_assign(&self.x);
+                    Some(d)
+                } else if self.y == other.y {
+                    // Doubling; y is never zero (see `double`).
+                    let mut d = self.y;
+                    d.double();
+                    Some(d)
+                } else {
+                    None
+                }
+            }
+
+            /// Adds `other`, given the inverse of `slope_denominator`.
+            fn add_assign_with_inverse(&mut self, other: &$affine, inverse: Option<$basefield>) {
+                let inverse = match inverse {
+                    Some(inverse) => inverse,
+                    None => {
+                        if self.is_zero() {
+                            *self = *other;
+                        } else if !other.is_zero() {
+                            // other = -self
+                            *self = $affine::zero();
+                        }
+
+                        return;
+                    }
+                };
+
+                // lambda = (y2 - y1) / (x2 - x1), or 3x^2 / 2y when doubling
+                let mut lambda;
+                if self.x == other.x {
+                    lambda = self.x;
+                    lambda.square();
+                    let tmp = lambda;
+                    lambda.double();
+                    lambda.add_assign(&tmp);
+                } else {
+                    lambda = other.y;
+                    lambda.sub_assign(&self.y);
+                }
+                lambda.mul_assign(&inverse);
+
+                // x3 = lambda^2 - x1 - x2
+                let mut x3 = lambda;
+                x3.square();
+                x3.sub_assign(&self.x);
+                x3.sub_assign(&other.x);
+
+                // y3 = lambda * (x1 - x3) - y1
+                let mut y3 = self.x;
+                y3.sub_assign(&x3);
+                y3.mul_assign(&lambda);
+                y3.sub_assign(&self.y);
+
+                self.x = x3;
+                self.y = y3;
+            }
+
             fn is_on_curve(&self) -> bool {
                 if self.is_zero() {                                   
                     true
@@ -140,7 +209,14 @@ macro_rules! curve_impl {
             }
 
             fn is_in_correct_subgroup_assuming_on_curve(&self) -> bool {
-                self.mul($scalarfield::char()).is_zero()                
+                self.is_torsion_free()
+            }
+
+            /// The textbook subgroup check, multiplying by the group order.
+            /// Kept to test the endomorphism-based check against.
+            #[cfg(test)]
+            fn is_in_correct_subgroup_by_order(&self) -> bool {
+                self.mul_bits(BitIterator::new($scalarfield::char())).is_zero()
             }
         }
 
@@ -172,8 +248,9 @@ macro_rules! curve_impl {
             }
 
             fn mul<S: Into<<Self::Scalar as PrimeField>::Repr>>(&self, by: S) -> $projective {
-                let bits = BitIterator::new(by.into());
-                self.mul_bits(bits)
+                let mut res = self.into_projective();
+                res.mul_assign(by);
+                res
             }
 
             fn negate(&mut self) {
@@ -194,6 +271,38 @@ macro_rules! curve_impl {
                 (*self).into()
             }
 
+            fn batch_add_assign(acc: &mut [Self], other: &[Self]) {
+                assert_eq!(acc.len(), other.len());
+
+                // Montgomery's trick, as in `batch_normalization`.
+
+                // First pass: compute the products of the denominators
+                // preceding each sum.
+                let mut prod = Vec::with_capacity(acc.len());
+                let mut tmp = $basefield::one();
+                for (a, b) in acc.iter().zip(other.iter()) {
+                    prod.push(tmp);
+                    if let Some(d) = a.slope_denominator(b) {
+                        tmp.mul_assign(&d);
+                    }
+                }
+
+                // Invert `tmp`.
+                tmp = tmp.inverse().unwrap(); // Guaranteed to be nonzero.
+
+                // Second pass: iterate backwards to compute inverses
+                for ((a, b), s) in acc.iter_mut().zip(other.iter()).zip(prod.into_iter()).rev() {
+                    let inverse = a.slope_denominator(b).map(|d| {
+                        // 1/d = tmp * s; tmp := tmp * d
+                        let mut inverse = tmp;
+                        inverse.mul_assign(&s);
+                        tmp.mul_assign(&d);
+                        inverse
+                    });
+
+                    a.add_assign_with_inverse(b, inverse);
+                }
+            }
         }
 
         impl Rand for $projective {
@@ -531,25 +640,12 @@ macro_rules! curve_impl {
                 }
             }
 
+            // The scalar is split into short scalars with an endomorphism, which only
+            // acts as a scalar on the prime-order subgroup; this is the precondition of
+            // `CurveProjective::mul_assign`. Points outside of it, for instance before
+            // clearing the cofactor, must be multiplied with `mul_bits`.
             fn mul_assign<S: Into<<Self::Scalar as PrimeField>::Repr>>(&mut self, other: S) {
-                let mut res = Self::zero();
-
-                let mut found_one = false;
-
-                for i in BitIterator::new(other.into())
-                {
-                    if found_one {
-                        res.double();
-                    } else {
-                        found_one = i;
-                    }
-
-                    if i {
-                        res.add_assign(self);
-                    }
-                }
-
-                *self = res;
+                *self = self.mul_by_endomorphism(reduce_scalar(other.into()));
             }
 
             fn into_affine(&self) -> $affine {
@@ -620,6 +716,41 @@ macro_rules! curve_impl {
     }
 }
 
+use super::{Fr, FrRepr};
+use {BitIterator, PrimeField, PrimeFieldRepr};
+
+/// Reduces `k` modulo the group order, which bounds the scalars of its
+/// endomorphism decompositions.
+fn reduce_scalar(mut k: FrRepr) -> FrRepr {
+    let r = Fr::char();
+    while k >= r {
+        k.sub_noborrow(&r);
+    }
+
+    k
+}
+
+/// Returns the quotient and the remainder of `n` divided by `d`.
+fn div_rem(n: FrRepr, d: FrRepr) -> (FrRepr, FrRepr) {
+    let mut q = FrRepr::default();
+    let mut r = FrRepr::default();
+
+    for i in BitIterator::new(n) {
+        q.mul2();
+        r.mul2();
+        if i {
+            r.as_mut()[0] |= 1;
+        }
+
+        if r >= d {
+            r.sub_noborrow(&d);
+            q.as_mut()[0] |= 1;
+        }
+    }
+
+    (q, r)
+}
+
 pub mod g1 {
     use super::super::{Bls12, Fq, Fq12, FqRepr, Fr, FrRepr};
     use super::g2::G2Affine;
@@ -629,9 +760,13 @@ pub mod g1 {
     #[cfg(not(feature = "std"))] 
     use core::fmt;    
     use {
-        BitIterator, CurveAffine, CurveProjective, EncodedPoint, Engine, Field, GroupDecodingError,
-        PrimeField, PrimeFieldRepr, SqrtField,
+        BitIterator, CanonicalEncoding, CurveAffine, CurveProjective, EncodedPoint, Engine, Field,
+        GroupDecodingError, PrimeField, PrimeFieldRepr, SqrtField,
     };
+    use IoError;
+    use utils::{Read, Write};
+    use wnaf::wnaf_exp_simultaneous;
+    use super::{div_rem, reduce_scalar};
     #[cfg(not(feature = "std"))]
     use rstd::result::Result;
     use rstd::prelude::*;
@@ -894,6 +1029,19 @@ pub mod g1 {
     }
 
     impl G1Affine {
+        /// Checks that `self` lies in G1 using the endomorphism
+        /// (x, y) -> (BETA * x, y), which acts as multiplication by -x^2
+        /// on G1 and on no other point of the curve (Scott,
+        /// <https://eprint.iacr.org/2021/1130>).
+        fn is_torsion_free(&self) -> bool {
+            // x^2 = 0xac45a4010001a4020000000100000000
+            let mut res = self.mul_bits(BitIterator::new([0x100000000, 0xac45a4010001a402]));
+
+            res.add_assign(&self.into_projective().endomorphism());
+
+            res.is_zero()
+        }
+
         fn scale_by_cofactor(&self) -> G1 {
             // G1 cofactor = (x - 1)^2 / 3  = 76329603384216526031706109802092473003
             let cofactor = BitIterator::new([0x8c00aaab0000aaab, 0x396c8c005555e156]);
@@ -918,6 +1066,26 @@ pub mod g1 {
     }
 
     impl G1 {
+        /// Applies the endomorphism (x, y) -> (BETA * x, y), which acts on G1
+        /// as multiplication by -x^2.
+        fn endomorphism(&self) -> Self {
+            let mut res = *self;
+            res.x.mul_assign(&super::super::fq::BETA);
+            res
+        }
+
+        /// Multiplies by `k < r` with the GLV method. Writing k = a x^2 + b
+        /// with a, b < 2^128 gives k P = b P - a endomorphism(P).
+        fn mul_by_endomorphism(&self, k: FrRepr) -> Self {
+            // x^2 = 0xac45a4010001a4020000000100000000
+            let (a, b) = div_rem(k, FrRepr([0x100000000, 0xac45a4010001a402, 0, 0]));
+
+            let mut endo = self.endomorphism();
+            endo.negate();
+
+            wnaf_exp_simultaneous(&[*self, endo], &[b, a])
+        }
+
         fn empirical_recommended_wnaf_for_scalar(scalar: FrRepr) -> usize {
             let num_bits = scalar.num_bits() as usize;
 
@@ -960,6 +1128,25 @@ pub mod g1 {
         }
     }
 
+    /// Encoded as the uncompressed encoding of the point.
+    impl CanonicalEncoding for G1Prepared {
+        fn write_canonical<W: Write>(&self, mut writer: W) -> Result<(), IoError> {
+            writer.write_all(self.0.into_uncompressed().as_ref())
+        }
+
+        fn read_canonical<R: Read>(mut reader: R) -> Result<Self, IoError> {
+            let mut repr = G1Uncompressed::empty();
+            reader.read_exact(repr.as_mut())?;
+
+            Ok(G1Prepared(repr.into_affine()?))
+        }
+
+        fn is_well_formed(&self) -> bool {
+            self.0.is_zero() ||
+            (self.0.is_on_curve() && self.0.is_in_correct_subgroup_assuming_on_curve())
+        }
+    }
+
     #[test]
     fn g1_generator() {
         use SqrtField;
@@ -1287,6 +1474,88 @@ pub mod g1 {
         assert_eq!(tmp2, c.into_projective());
     }
 
+    #[test]
+    fn g1_subgroup_check() {
+        use rand::{SeedableRng, XorShiftRng};
+
+        let mut rng = XorShiftRng::from_seed([0x5dbe6259, 0x8d313d76, 0x3237db17, 0xe5bc0654]);
+
+        for _ in 0..50 {
+            // A random point on the curve, almost surely outside G1.
+            let p = loop {
+                if let Some(p) = G1Affine::get_point_from_x(Fq::rand(&mut rng), rng.gen()) {
+                    break p;
+                }
+            };
+            // Its component of cofactor order, outside G1 unless zero.
+            let torsion = p.mul_bits(BitIterator::new(Fr::char()));
+            // Its projection into G1.
+            let cleared = p.scale_by_cofactor();
+            let mut mixed = cleared;
+            mixed.add_assign(&torsion);
+
+            assert!(!p.is_in_correct_subgroup_by_order());
+            assert!(!p.is_in_correct_subgroup_assuming_on_curve());
+
+            for q in &[torsion, cleared, mixed] {
+                let q = q.into_affine();
+                assert_eq!(
+                    q.is_in_correct_subgroup_assuming_on_curve(),
+                    q.is_in_correct_subgroup_by_order()
+                );
+            }
+            assert!(cleared.into_affine().is_in_correct_subgroup_assuming_on_curve());
+            assert!(!torsion.into_affine().is_in_correct_subgroup_assuming_on_curve());
+            assert!(!mixed.into_affine().is_in_correct_subgroup_assuming_on_curve());
+
+            let q = G1::rand(&mut rng).into_affine();
+            assert!(q.is_in_correct_subgroup_assuming_on_curve());
+            assert!(q.is_in_correct_subgroup_by_order());
+        }
+
+        assert!(G1Affine::zero().is_in_correct_subgroup_assuming_on_curve());
+    }
+
+    #[test]
+    fn g1_mul_by_endomorphism() {
+        use rand::{SeedableRng, XorShiftRng};
+
+        let mut rng = XorShiftRng::from_seed([0x5dbe6259, 0x8d313d76, 0x3237db17, 0xe5bc0654]);
+
+        let mut r_minus_one = Fr::char();
+        r_minus_one.sub_noborrow(&FrRepr::from(1));
+        let mut r_plus_one = Fr::char();
+        r_plus_one.add_nocarry(&FrRepr::from(1));
+
+        let mut scalars = vec![
+            FrRepr::from(0),
+            FrRepr::from(1),
+            FrRepr::from(super::super::BLS_X),
+            r_minus_one,
+            Fr::char(),
+            r_plus_one,
+            FrRepr([!0; 4]),
+        ];
+        for _ in 0..20 {
+            scalars.push(Fr::rand(&mut rng).into_repr());
+            scalars.push(FrRepr(rng.gen()));
+        }
+
+        for k in scalars {
+            let p = G1::rand(&mut rng);
+
+            let mut res = p;
+            res.mul_assign(k);
+
+            assert_eq!(res, p.into_affine().mul_bits(BitIterator::new(k)));
+            assert_eq!(res, p.into_affine().mul(k));
+        }
+
+        let mut res = G1::zero();
+        res.mul_assign(Fr::rand(&mut rng));
+        assert!(res.is_zero());
+    }
+
     #[test]
     fn g1_curve_tests() {
         ::tests::curve::curve_tests::<G1>();
@@ -1305,6 +1574,8 @@ pub mod g2 {
         BitIterator, CurveAffine, CurveProjective, EncodedPoint, Engine, Field, GroupDecodingError,
         PrimeField, PrimeFieldRepr, SqrtField,
     };
+    use wnaf::wnaf_exp_simultaneous;
+    use super::{div_rem, reduce_scalar};
     #[cfg(not(feature = "std"))]
     use rstd::result::Result;
     use rstd::prelude::*;
@@ -1642,6 +1913,17 @@ pub mod g2 {
             }
         }
 
+        /// Checks that `self` lies in G2 using the endomorphism psi, which
+        /// acts on G2 as multiplication by x and on no other point of the
+        /// curve (Scott, <https://eprint.iacr.org/2021/1130>).
+        fn is_torsion_free(&self) -> bool {
+            // x is negative, so check that psi(P) + |x| P is zero.
+            let mut res = self.mul_bits(BitIterator::new([super::super::BLS_X]));
+            res.add_assign(&self.into_projective().psi());
+
+            res.is_zero()
+        }
+
         fn scale_by_cofactor(&self) -> G2 {
             // G2 cofactor = (x^8 - 4 x^7 + 5 x^6) - (4 x^4 + 6 x^3 - 4 x^2 - 4 x + 13) // 9
             // 0x5d543a95414e7f1091d50792876a202cd91de4547085abaa68a205b2e5a7ddfa628f1cb4d9e82ef21537e293a6691ae1616ec6e786f0c70cf1c38e31c7238e5
@@ -1664,6 +1946,48 @@ pub mod g2 {
     }
 
     impl G2 {
+        /// The untwist-Frobenius-twist endomorphism
+        /// (x, y) -> (c_x * conj(x), c_y * conj(y)), which acts on G2 as
+        /// multiplication by x.
+        fn psi(&self) -> Self {
+            let mut res = *self;
+
+            res.x.frobenius_map(1);
+            res.x.mul_assign(&Fq2 {
+                c0: Fq::zero(),
+                c1: super::super::fq::PSI_COEFF_X_C1,
+            });
+
+            res.y.frobenius_map(1);
+            res.y.mul_assign(&Fq2 {
+                c0: super::super::fq::PSI_COEFF_Y_C0,
+                c1: super::super::fq::PSI_COEFF_Y_C1,
+            });
+
+            res.z.frobenius_map(1);
+
+            res
+        }
+
+        /// Multiplies by `k < r` with the GLS method. Writing k in base |x| as
+        /// k_0 + k_1 |x| + k_2 |x|^2 + k_3 |x|^3, with psi acting as -|x|, gives
+        /// k Q = k_0 Q - k_1 psi(Q) + k_2 psi^2(Q) - k_3 psi^3(Q).
+        fn mul_by_endomorphism(&self, k: FrRepr) -> Self {
+            let x = FrRepr::from(super::super::BLS_X);
+            let (k, k0) = div_rem(k, x);
+            let (k, k1) = div_rem(k, x);
+            let (k3, k2) = div_rem(k, x);
+
+            let mut q1 = self.psi();
+            q1.negate();
+            let mut q2 = q1.psi();
+            q2.negate();
+            let mut q3 = q2.psi();
+            q3.negate();
+
+            wnaf_exp_simultaneous(&[*self, q1, q2, q3], &[k0, k1, k2, k3])
+        }
+
         fn empirical_recommended_wnaf_for_scalar(scalar: FrRepr) -> usize {
             let num_bits = scalar.num_bits() as usize;
 
@@ -1784,7 +2108,9 @@ pub mod g2 {
                 infinity: false,
             };
             assert!(!p.is_on_curve());
-            assert!(p.is_in_correct_subgroup_assuming_on_curve());
+            // psi is only an endomorphism of the correct twist, so only the
+            // order-based check sees that this point has order r.
+            assert!(p.is_in_correct_subgroup_by_order());
         }
 
         // Reject point on a twist (b = 2 * (u + 1))
@@ -2097,6 +2423,88 @@ pub mod g2 {
         );
     }
 
+    #[test]
+    fn g2_subgroup_check() {
+        use rand::{SeedableRng, XorShiftRng};
+
+        let mut rng = XorShiftRng::from_seed([0x5dbe6259, 0x8d313d76, 0x3237db17, 0xe5bc0654]);
+
+        for _ in 0..50 {
+            // A random point on the curve, almost surely outside G2.
+            let p = loop {
+                if let Some(p) = G2Affine::get_point_from_x(Fq2::rand(&mut rng), rng.gen()) {
+                    break p;
+                }
+            };
+            // Its component of cofactor order, outside G2 unless zero.
+            let torsion = p.mul_bits(BitIterator::new(Fr::char()));
+            // Its projection into G2.
+            let cleared = p.scale_by_cofactor();
+            let mut mixed = cleared;
+            mixed.add_assign(&torsion);
+
+            assert!(!p.is_in_correct_subgroup_by_order());
+            assert!(!p.is_in_correct_subgroup_assuming_on_curve());
+
+            for q in &[torsion, cleared, mixed] {
+                let q = q.into_affine();
+                assert_eq!(
+                    q.is_in_correct_subgroup_assuming_on_curve(),
+                    q.is_in_correct_subgroup_by_order()
+                );
+            }
+            assert!(cleared.into_affine().is_in_correct_subgroup_assuming_on_curve());
+            assert!(!torsion.into_affine().is_in_correct_subgroup_assuming_on_curve());
+            assert!(!mixed.into_affine().is_in_correct_subgroup_assuming_on_curve());
+
+            let q = G2::rand(&mut rng).into_affine();
+            assert!(q.is_in_correct_subgroup_assuming_on_curve());
+            assert!(q.is_in_correct_subgroup_by_order());
+        }
+
+        assert!(G2Affine::zero().is_in_correct_subgroup_assuming_on_curve());
+    }
+
+    #[test]
+    fn g2_mul_by_endomorphism() {
+        use rand::{SeedableRng, XorShiftRng};
+
+        let mut rng = XorShiftRng::from_seed([0x5dbe6259, 0x8d313d76, 0x3237db17, 0xe5bc0654]);
+
+        let mut r_minus_one = Fr::char();
+        r_minus_one.sub_noborrow(&FrRepr::from(1));
+        let mut r_plus_one = Fr::char();
+        r_plus_one.add_nocarry(&FrRepr::from(1));
+
+        let mut scalars = vec![
+            FrRepr::from(0),
+            FrRepr::from(1),
+            FrRepr::from(super::super::BLS_X),
+            r_minus_one,
+            Fr::char(),
+            r_plus_one,
+            FrRepr([!0; 4]),
+        ];
+        for _ in 0..20 {
+            scalars.push(Fr::rand(&mut rng).into_repr());
+            scalars.push(FrRepr(rng.gen()));
+        }
+
+        for k in scalars {
+            let p = G2::rand(&mut rng);
+
+            let mut res = p;
+            res.mul_assign(k);
+
+            assert_eq!(res, p.into_affine().mul_bits(BitIterator::new(k)));
+            assert_eq!(res, p.into_affine().mul(k));
+        }
+
+        let mut res = G2::zero();
+        res.mul_assign(Fr::rand(&mut rng));
+        assert!(res.is_zero());
+    }
+
     #[test]
     fn g2_curve_tests() {
         ::tests::curve::curve_tests::<G2>();
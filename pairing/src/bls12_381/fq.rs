@@ -1,7 +1,9 @@
 use super::fq2::Fq2;
 use rstd::cmp::Ordering;
 use rstd::mem;
-use {Field, PrimeField, PrimeFieldDecodingError, PrimeFieldRepr, SqrtField};
+use {CanonicalEncoding, Field, PrimeField, PrimeFieldDecodingError, PrimeFieldRepr, SqrtField};
+use IoError;
+use utils::{Read, Write};
 use core::fmt;
 
 // q = 4002409555221667393417789825735904156556882819939007885332058136124031650490837864442687629129015664037894272559787
@@ -499,6 +501,48 @@ pub const FROBENIUS_COEFF_FQ12_C1: [Fq2; 12] = [
     },
 ];
 
+// Primitive cube root of unity, such that (x, y) -> (BETA * x, y) acts on G1
+// as multiplication by -x^2.
+// BETA = 793479390729215512621379701633421447060886740281060493010456487427281649075476305620758731620350
+pub const BETA: Fq = Fq(FqRepr([
+    0x30f1361b798a64e8,
+    0xf3b8ddab7ece5a2a,
+    0x16a8ca3ac61577f7,
+    0xc26a2ff874fd029b,
+    0x3636b76660701c6e,
+    0x51ba4ab241b6160,
+]));
+
+// Coefficients of the untwist-Frobenius-twist endomorphism psi on G2,
+// 1 / (u + 1)^((q - 1) / 3) and 1 / (u + 1)^((q - 1) / 2).
+// PSI_COEFF_X = 4002409555221667392624310435006688643935503118305586438271171395842971157480381377015405980053539358417135540939437*u
+pub const PSI_COEFF_X_C1: Fq = Fq(FqRepr([
+    0x890dc9e4867545c3,
+    0x2af322533285a5d5,
+    0x50880866309b7e2c,
+    0xa20d1b8c7e881024,
+    0x14e4f04fe2db9068,
+    0x14e56d3f1564853a,
+]));
+
+// PSI_COEFF_Y = 1028732146235106349975324479215795277384839936929757896155643118032610843298655225875571310552543014690878354869257*u + 2973677408986561043442465346520108879172042883009249989176415018091420807192182638567116318576472649347015917690530
+pub const PSI_COEFF_Y_C0: Fq = Fq(FqRepr([
+    0x3e2f585da55c9ad1,
+    0x4294213d86c18183,
+    0x382844c88b623732,
+    0x92ad2afd19103e18,
+    0x1d794e4fac7cf0b9,
+    0xbd592fc7d825ec8,
+]));
+pub const PSI_COEFF_Y_C1: Fq = Fq(FqRepr([
+    0x7bcfa7a25aa30fda,
+    0xdc17dec12a927e7c,
+    0x2f088dd86b4ebef1,
+    0xd1ca2087da74d4a7,
+    0x2da2596696cebc1d,
+    0xe2b7eedbbfd87d2,
+]));
+
 // -((2**384) mod q) mod q
 pub const NEGATIVE_ONE: Fq = Fq(FqRepr([
     0x43f5fffffffcaaae,
@@ -1126,6 +1170,29 @@ impl Fq {
     }
 }
 
+impl CanonicalEncoding for Fq {
+    fn write_canonical<W: Write>(&self, mut writer: W) -> Result<(), IoError> {
+        let mut buf = [0u8; 48];
+        self.into_repr().write_be(&mut buf[..])?;
+
+        writer.write_all(&buf)
+    }
+
+    fn read_canonical<R: Read>(mut reader: R) -> Result<Self, IoError> {
+        let mut buf = [0u8; 48];
+        reader.read_exact(&mut buf)?;
+
+        let mut repr = FqRepr::default();
+        repr.read_be(&buf[..])?;
+
+        Fq::from_repr(repr).map_err(|_| IoError::NotInField)
+    }
+
+    fn is_well_formed(&self) -> bool {
+        self.is_valid()
+    }
+}
+
 impl SqrtField for Fq {
     fn legendre(&self) -> ::LegendreSymbol {
         use LegendreSymbol::*;
@@ -1179,6 +1246,48 @@ fn test_b_coeff() {
     assert_eq!(Fq::from_repr(FqRepr::from(4)).unwrap(), B_COEFF);
 }
 
+#[test]
+fn test_endomorphism_coeffs() {
+    let mut beta = BETA;
+    beta.square();
+    beta.mul_assign(&BETA);
+    assert!(BETA != Fq::one());
+    assert_eq!(beta, Fq::one());
+
+    let nqr = Fq2 {
+        c0: Fq::one(),
+        c1: Fq::one(),
+    };
+
+    let mut psi_coeff_x = Fq2 {
+        c0: Fq::zero(),
+        c1: PSI_COEFF_X_C1,
+    };
+    psi_coeff_x.mul_assign(&nqr.pow([
+        0x9354ffffffffe38e,
+        0xa395554e5c6aaaa,
+        0xcd104635a790520c,
+        0xcc27c3d6fbd7063f,
+        0x190937e76bc3e447,
+        0x8ab05f8bdd54cde,
+    ]));
+    assert_eq!(psi_coeff_x, Fq2::one());
+
+    let mut psi_coeff_y = Fq2 {
+        c0: PSI_COEFF_Y_C0,
+        c1: PSI_COEFF_Y_C1,
+    };
+    psi_coeff_y.mul_assign(&nqr.pow([
+        0xdcff7fffffffd555,
+        0xf55ffff58a9ffff,
+        0xb39869507b587b12,
+        0xb23ba5c279c2895f,
+        0x258dd3db21a5d66b,
+        0xd0088f51cbff34d,
+    ]));
+    assert_eq!(psi_coeff_y, Fq2::one());
+}
+
 #[test]
 fn test_frob_coeffs() {
     let mut nqr = Fq::one();
@@ -2,7 +2,9 @@ use super::fq::FROBENIUS_COEFF_FQ12_C1;
 use super::fq2::Fq2;
 use super::fq6::Fq6;
 use rand::{Rand, Rng};
-use Field;
+use {CanonicalEncoding, Field};
+use IoError;
+use utils::{Read, Write};
 use core::fmt;
 
 /// An element of Fq12, represented by c0 + c1 * w.
@@ -149,6 +151,26 @@ impl Field for Fq12 {
     }
 }
 
+/// Encoded as `c0.c0.c0`, `c0.c0.c1`, `c0.c1.c0`, ..., `c1.c2.c1`: twelve
+/// elements of Fq, each a 48-byte big-endian integer.
+impl CanonicalEncoding for Fq12 {
+    fn write_canonical<W: Write>(&self, mut writer: W) -> Result<(), IoError> {
+        self.c0.write_canonical(&mut writer)?;
+        self.c1.write_canonical(&mut writer)
+    }
+
+    fn read_canonical<R: Read>(mut reader: R) -> Result<Self, IoError> {
+        Ok(Fq12 {
+            c0: Fq6::read_canonical(&mut reader)?,
+            c1: Fq6::read_canonical(&mut reader)?
+        })
+    }
+
+    fn is_well_formed(&self) -> bool {
+        self.c0.is_well_formed() && self.c1.is_well_formed()
+    }
+}
+
 #[cfg(test)]
 use rand::{SeedableRng, XorShiftRng};
 
@@ -1,6 +1,8 @@
 use super::fq::{FROBENIUS_COEFF_FQ2_C1, Fq, NEGATIVE_ONE};
 use rand::{Rand, Rng};
-use {Field, SqrtField};
+use {CanonicalEncoding, Field, SqrtField};
+use IoError;
+use utils::{Read, Write};
 use core::fmt;
 use rstd::cmp::Ordering;
 
@@ -160,6 +162,24 @@ impl Field for Fq2 {
     }
 }
 
+impl CanonicalEncoding for Fq2 {
+    fn write_canonical<W: Write>(&self, mut writer: W) -> Result<(), IoError> {
+        self.c0.write_canonical(&mut writer)?;
+        self.c1.write_canonical(&mut writer)
+    }
+
+    fn read_canonical<R: Read>(mut reader: R) -> Result<Self, IoError> {
+        Ok(Fq2 {
+            c0: Fq::read_canonical(&mut reader)?,
+            c1: Fq::read_canonical(&mut reader)?
+        })
+    }
+
+    fn is_well_formed(&self) -> bool {
+        self.c0.is_well_formed() && self.c1.is_well_formed()
+    }
+}
+
 impl SqrtField for Fq2 {
     fn legendre(&self) -> ::LegendreSymbol {
         self.norm().legendre()
@@ -1,7 +1,9 @@
 use super::fq::{FROBENIUS_COEFF_FQ6_C1, FROBENIUS_COEFF_FQ6_C2};
 use super::fq2::Fq2;
 use rand::{Rand, Rng};
-use Field;
+use {CanonicalEncoding, Field};
+use IoError;
+use utils::{Read, Write};
 use core::fmt;
 
 /// An element of Fq6, represented by c0 + c1 * v + c2 * v^(2).
@@ -302,6 +304,26 @@ impl Field for Fq6 {
     }
 }
 
+impl CanonicalEncoding for Fq6 {
+    fn write_canonical<W: Write>(&self, mut writer: W) -> Result<(), IoError> {
+        self.c0.write_canonical(&mut writer)?;
+        self.c1.write_canonical(&mut writer)?;
+        self.c2.write_canonical(&mut writer)
+    }
+
+    fn read_canonical<R: Read>(mut reader: R) -> Result<Self, IoError> {
+        Ok(Fq6 {
+            c0: Fq2::read_canonical(&mut reader)?,
+            c1: Fq2::read_canonical(&mut reader)?,
+            c2: Fq2::read_canonical(&mut reader)?
+        })
+    }
+
+    fn is_well_formed(&self) -> bool {
+        self.c0.is_well_formed() && self.c1.is_well_formed() && self.c2.is_well_formed()
+    }
+}
+
 #[cfg(test)]
 use rand::{SeedableRng, XorShiftRng};
 
@@ -0,0 +1,1087 @@
+//! Hashing to G1 and G2 with the `BLS12381G1_XMD:SHA-256_SSWU_RO_` and
+//! `BLS12381G2_XMD:SHA-256_SSWU_RO_` suites of RFC 9380, section 8.8.
+//!
+//! Field elements are mapped with the simplified SWU map to curves E'
+//! isogenous to E (section 6.6.3), and then moved to E through the
+//! isogeny maps of appendix E.
+
+use super::{Fq, Fq2, FqRepr, G1, G1Affine, G2, G2Affine};
+use hash_to_curve::expand_message_xmd;
+use {BitIterator, CurveAffine, CurveProjective, Field, PrimeField, PrimeFieldRepr, SqrtField};
+
+/// The number of bytes of `expand_message_xmd` output reduced into each
+/// element of Fq, `ceil((ceil(log2(q)) + 128) / 8)`.
+const L: usize = 64;
+
+impl G1 {
+    /// Hashes `msg` to a uniformly distributed element of G1 under the
+    /// domain separation tag `dst`, following the
+    /// `BLS12381G1_XMD:SHA-256_SSWU_RO_` suite.
+    pub fn hash_to_curve(msg: &[u8], dst: &[u8]) -> G1 {
+        let uniform_bytes = expand_message_xmd(msg, dst, 2 * L);
+
+        let mut p = map_to_curve_g1(&fq_from_okm(&uniform_bytes[..L])).into_projective();
+        p.add_assign_mixed(&map_to_curve_g1(&fq_from_okm(&uniform_bytes[L..])));
+
+        // Clearing the cofactor with h_eff = 1 - x.
+        p.into_affine().mul_bits(BitIterator::new([G1_H_EFF]))
+    }
+}
+
+impl G2 {
+    /// Hashes `msg` to a uniformly distributed element of G2 under the
+    /// domain separation tag `dst`, following the
+    /// `BLS12381G2_XMD:SHA-256_SSWU_RO_` suite.
+    pub fn hash_to_curve(msg: &[u8], dst: &[u8]) -> G2 {
+        let uniform_bytes = expand_message_xmd(msg, dst, 4 * L);
+
+        let u0 = Fq2 {
+            c0: fq_from_okm(&uniform_bytes[..L]),
+            c1: fq_from_okm(&uniform_bytes[L..2 * L]),
+        };
+        let u1 = Fq2 {
+            c0: fq_from_okm(&uniform_bytes[2 * L..3 * L]),
+            c1: fq_from_okm(&uniform_bytes[3 * L..]),
+        };
+
+        let mut p = map_to_curve_g2(&u0).into_projective();
+        p.add_assign_mixed(&map_to_curve_g2(&u1));
+
+        p.into_affine().mul_bits(BitIterator::new(G2_H_EFF))
+    }
+}
+
+/// Reduces `L` bytes of `expand_message_xmd` output, read as a big-endian
+/// integer, modulo q.
+fn fq_from_okm(okm: &[u8]) -> Fq {
+    // Split the integer as hi * 2^256 + lo; both halves are below q.
+    let mut buf = [0u8; 48];
+    let mut hi = FqRepr::default();
+    buf[16..].copy_from_slice(&okm[..32]);
+    hi.read_be(&buf[..]).unwrap();
+    let mut lo = FqRepr::default();
+    buf[16..].copy_from_slice(&okm[32..]);
+    lo.read_be(&buf[..]).unwrap();
+
+    let mut res = Fq::from_repr(hi).unwrap();
+    res.mul_assign(&Fq::from_repr(FqRepr([0, 0, 0, 0, 1, 0])).unwrap());
+    res.add_assign(&Fq::from_repr(lo).unwrap());
+    res
+}
+
+fn fq(repr: &FqRepr) -> Fq {
+    Fq::from_repr(*repr).unwrap()
+}
+
+fn fq2(repr: &(FqRepr, FqRepr)) -> Fq2 {
+    Fq2 {
+        c0: fq(&repr.0),
+        c1: fq(&repr.1),
+    }
+}
+
+fn sgn0_fq(a: &Fq) -> bool {
+    a.into_repr().is_odd()
+}
+
+fn sgn0_fq2(a: &Fq2) -> bool {
+    sgn0_fq(&a.c0) || (a.c0.is_zero() && sgn0_fq(&a.c1))
+}
+
+fn map_to_curve_g1(u: &Fq) -> G1Affine {
+    let (x, y) = simple_swu(
+        u,
+        &fq(&G1_ISO_A),
+        &fq(&G1_ISO_B),
+        &fq(&G1_SSWU_Z),
+        sgn0_fq,
+    );
+
+    match iso_map(
+        &x,
+        &y,
+        &G1_ISO_X_NUM,
+        &G1_ISO_X_DEN,
+        &G1_ISO_Y_NUM,
+        &G1_ISO_Y_DEN,
+        fq,
+    ) {
+        Some((x, y)) => G1Affine {
+            x,
+            y,
+            infinity: false,
+        },
+        None => G1Affine::zero(),
+    }
+}
+
+fn map_to_curve_g2(u: &Fq2) -> G2Affine {
+    let (x, y) = simple_swu(
+        u,
+        &fq2(&G2_ISO_A),
+        &fq2(&G2_ISO_B),
+        &fq2(&G2_SSWU_Z),
+        sgn0_fq2,
+    );
+
+    match iso_map(
+        &x,
+        &y,
+        &G2_ISO_X_NUM,
+        &G2_ISO_X_DEN,
+        &G2_ISO_Y_NUM,
+        &G2_ISO_Y_DEN,
+        fq2,
+    ) {
+        Some((x, y)) => G2Affine {
+            x,
+            y,
+            infinity: false,
+        },
+        None => G2Affine::zero(),
+    }
+}
+
+/// The simplified SWU map to y^2 = x^3 + A * x + B (section 6.6.2).
+fn simple_swu<F: SqrtField>(u: &F, a: &F, b: &F, z: &F, sgn0: fn(&F) -> bool) -> (F, F) {
+    let g = |x: &F| {
+        let mut gx = *x;
+        gx.square();
+        gx.add_assign(a);
+        gx.mul_assign(x);
+        gx.add_assign(b);
+        gx
+    };
+
+    let mut z_u2 = *u;
+    z_u2.square();
+    z_u2.mul_assign(z);
+
+    // tv1 = 1 / (Z^2 * u^4 + Z * u^2)
+    let mut tv1 = z_u2;
+    tv1.square();
+    tv1.add_assign(&z_u2);
+
+    let x1 = match tv1.inverse() {
+        // x1 = (-B / A) * (1 + tv1)
+        Some(mut tv1) => {
+            tv1.add_assign(&F::one());
+            let mut x1 = a.inverse().unwrap();
+            x1.mul_assign(b);
+            x1.negate();
+            x1.mul_assign(&tv1);
+            x1
+        }
+        // x1 = B / (Z * A) in the exceptional case
+        None => {
+            let mut x1 = *z;
+            x1.mul_assign(a);
+            x1 = x1.inverse().unwrap();
+            x1.mul_assign(b);
+            x1
+        }
+    };
+
+    let (x, mut y) = match g(&x1).sqrt() {
+        Some(y) => (x1, y),
+        None => {
+            // g(x2) = Z^3 * u^6 * g(x1) is square whenever g(x1) is not.
+            let mut x2 = z_u2;
+            x2.mul_assign(&x1);
+            let y = g(&x2).sqrt().unwrap();
+            (x2, y)
+        }
+    };
+
+    if sgn0(u) != sgn0(&y) {
+        y.negate();
+    }
+
+    (x, y)
+}
+
+/// Evaluates the isogeny map with the given coefficients, listed from the
+/// constant term up, or returns `None` for points sent to infinity.
+fn iso_map<C, F: Field>(
+    x: &F,
+    y: &F,
+    x_num: &[C],
+    x_den: &[C],
+    y_num: &[C],
+    y_den: &[C],
+    to_field: fn(&C) -> F,
+) -> Option<(F, F)> {
+    let eval = |coeffs: &[C]| {
+        let mut res = F::zero();
+        for c in coeffs.iter().rev() {
+            res.mul_assign(x);
+            res.add_assign(&to_field(c));
+        }
+        res
+    };
+
+    let x_den = eval(x_den);
+    let y_den = eval(y_den);
+
+    // Invert both denominators with a single inversion.
+    let mut inv = x_den;
+    inv.mul_assign(&y_den);
+    let inv = inv.inverse()?;
+    let mut x_den_inv = y_den;
+    x_den_inv.mul_assign(&inv);
+    let mut y_den_inv = x_den;
+    y_den_inv.mul_assign(&inv);
+
+    let mut x_res = eval(x_num);
+    x_res.mul_assign(&x_den_inv);
+    let mut y_res = eval(y_num);
+    y_res.mul_assign(&y_den_inv);
+    y_res.mul_assign(y);
+
+    Some((x_res, y_res))
+}
+
+/// The cofactor multiple h_eff used to clear the cofactor of G1.
+const G1_H_EFF: u64 = 0xd201000000010001;
+
+/// A' of E': y^2 = x^3 + A' * x + B', 11-isogenous to E.
+const G1_ISO_A: FqRepr = FqRepr([
+    0x5cf428082d584c1d,
+    0x98936f8da0e0f97f,
+    0xd8e8981aefd881ac,
+    0xb0ea985383ee66a8,
+    0x3d693a02c96d4982,
+    0x144698a3b8e943,
+]);
+
+/// B' of E'.
+const G1_ISO_B: FqRepr = FqRepr([
+    0xd1cc48e98e172be0,
+    0x5a23215a316ceaa5,
+    0xa0b9c14fcef35ef5,
+    0x2016c1f0f24f4070,
+    0x18b12e8753eee3b,
+    0x12e2908d11688030,
+]);
+
+/// The SSWU constant Z = 11.
+const G1_SSWU_Z: FqRepr = FqRepr([
+    0xb,
+    0x0,
+    0x0,
+    0x0,
+    0x0,
+    0x0,
+]);
+
+/// Coefficients of the 11-isogeny map from E' to E (appendix E.2), from
+/// the constant term up. Both denominators are monic.
+const G1_ISO_X_NUM: [FqRepr; 12] = [
+    FqRepr([
+        0xaeac1662734649b7,
+        0x5610c2d5f2e62d6e,
+        0xf2627b56cdb4e2c8,
+        0x6b303e88a2d7005f,
+        0xb809101dd9981585,
+        0x11a05f2b1e833340,
+    ]),
+    FqRepr([
+        0xe834eef1b3cb83bb,
+        0x4838f2a6f318c356,
+        0xf565e33c70d1e86b,
+        0x7c17e75b2f6a8417,
+        0x588bab22147a81c,
+        0x17294ed3e943ab2f,
+    ]),
+    FqRepr([
+        0xe0179f9dac9edcb0,
+        0x958c3e3d2a09729f,
+        0x6878e501ec68e25c,
+        0xce032473295983e5,
+        0x1d1048c5d10a9a1b,
+        0xd54005db97678ec,
+    ]),
+    FqRepr([
+        0xc5b388641d9b6861,
+        0x5336e25ce3107193,
+        0xf1b33289f1b33083,
+        0xd7f5e4656a8dbf25,
+        0x4e0609d307e55412,
+        0x1778e7166fcc6db7,
+    ]),
+    FqRepr([
+        0x51154ce9ac8895d9,
+        0x985a286f301e77c4,
+        0x86eeb65982fac18,
+        0x99db995a1257fb3f,
+        0x6642b4b3e4118e54,
+        0xe99726a3199f443,
+    ]),
+    FqRepr([
+        0xcd13c1c66f652983,
+        0xa0870d2dcae73d19,
+        0x9ed3ab9097e68f90,
+        0xdb3cb17dd952799b,
+        0x1d1201bf7a74ab5,
+        0x1630c3250d7313ff,
+    ]),
+    FqRepr([
+        0xddd7f225a139ed84,
+        0x8da25128c1052eca,
+        0x9008e218f9c86b2a,
+        0xb11586264f0f8ce1,
+        0x6a3726c38ae652bf,
+        0xd6ed6553fe44d29,
+    ]),
+    FqRepr([
+        0x9ccb5618e3f0c88e,
+        0x39b7c8f8c8f475af,
+        0xa682c62ef0f27533,
+        0x356de5ab275b4db1,
+        0xe8743884d1117e53,
+        0x17b81e7701abdbe2,
+    ]),
+    FqRepr([
+        0x6d71986a8497e317,
+        0x4fa295f296b74e95,
+        0xa2c596c928c5d1de,
+        0xc43b756ce79f5574,
+        0x7b90b33563be990d,
+        0x80d3cf1f9a78fc4,
+    ]),
+    FqRepr([
+        0x7f241067be390c9e,
+        0xa3190b2edc032779,
+        0x676314baf4bb1b7f,
+        0xdd2ecb803a0c5c99,
+        0x2e0c37515d138f22,
+        0x169b1f8e1bcfa7c4,
+    ]),
+    FqRepr([
+        0xca67df3f1605fb7b,
+        0xf69b771f8c285dec,
+        0xd50af36003b14866,
+        0xfa7dccdde6787f96,
+        0x72d8ec09d2565b0d,
+        0x10321da079ce07e2,
+    ]),
+    FqRepr([
+        0xa9c8ba2e8ba2d229,
+        0xc24b1b80b64d391f,
+        0x23c0bf1bc24c6b68,
+        0x31d79d7e22c837bc,
+        0xbd1e962381edee3d,
+        0x6e08c248e260e70,
+    ]),
+];
+
+const G1_ISO_X_DEN: [FqRepr; 11] = [
+    FqRepr([
+        0x993cf9fa40d21b1c,
+        0xb558d681be343df8,
+        0x9c9588617fc8ac62,
+        0x1d5ef4ba35b48ba,
+        0x18b2e62f4bd3fa6f,
+        0x8ca8d548cff19ae,
+    ]),
+    FqRepr([
+        0xe5c8276ec82b3bff,
+        0x13daa8846cb026e9,
+        0x126c2588c48bf57,
+        0x7041e8ca0cf0800c,
+        0x48b4711298e53636,
+        0x12561a5deb559c43,
+    ]),
+    FqRepr([
+        0xfcc239ba5cb83e19,
+        0xd6a3d0967c94fedc,
+        0xfca64e00b11aceac,
+        0x6f89416f5a718cd1,
+        0x8137e629bff2991f,
+        0xb2962fe57a3225e,
+    ]),
+    FqRepr([
+        0x130de8938dc62cd8,
+        0x4976d5243eecf5c4,
+        0x54cca8abc28d6fd0,
+        0x5b08243f16b16551,
+        0xc83aafef7c40eb54,
+        0x3425581a58ae2fe,
+    ]),
+    FqRepr([
+        0x539d395b3532a21e,
+        0x9bd29ba81f35781d,
+        0x8d6b44e833b306da,
+        0xffdfc759a12062bb,
+        0xa6f1d5f43e7a07d,
+        0x13a8e162022914a8,
+    ]),
+    FqRepr([
+        0xc02df9a29f6304a5,
+        0x7400d24bc4228f11,
+        0xa43bcef24b8982f,
+        0x395735e9ce9cad4d,
+        0x55390f7f0506c6e9,
+        0xe7355f8e4e667b9,
+    ]),
+    FqRepr([
+        0xec2574496ee84a3a,
+        0xea73b3538f0de06c,
+        0x4e2e073062aede9c,
+        0x570f5799af53a189,
+        0xf3e0c63e0596721,
+        0x772caacf1693619,
+    ]),
+    FqRepr([
+        0x11f7d99bbdcc5a5e,
+        0xfa5b9489d11e2d3,
+        0x1996e1cdf9822c58,
+        0x6e7f63c21bca68a8,
+        0x30b3f5b074cf0199,
+        0x14a7ac2a9d64a8b2,
+    ]),
+    FqRepr([
+        0x4776ec3a79a1d641,
+        0x3826692abba4370,
+        0x74100da67f398835,
+        0xe07f8d1d7161366b,
+        0x5e920b3dafc7a3cc,
+        0xa10ecf6ada54f82,
+    ]),
+    FqRepr([
+        0x2d6384d168ecdd0a,
+        0x93174e4b4b786500,
+        0x76df533978f31c15,
+        0xf682b4ee96f7d037,
+        0x476d6e3eb3a56680,
+        0x95fc13ab9e92ad4,
+    ]),
+    FqRepr([
+        0x1,
+        0x0,
+        0x0,
+        0x0,
+        0x0,
+        0x0,
+    ]),
+];
+
+const G1_ISO_Y_NUM: [FqRepr; 16] = [
+    FqRepr([
+        0xbe9845719707bb33,
+        0xcd0c7aee9b3ba3c2,
+        0x2b52af6c956543d3,
+        0x11ad138e48a86952,
+        0x259d1f094980dcfa,
+        0x90d97c81ba24ee0,
+    ]),
+    FqRepr([
+        0xe097e75a2e41c696,
+        0xd6c56711962fa8bf,
+        0xf906343eb67ad34,
+        0x1223e96c254f383d,
+        0xd51036d776fb4683,
+        0x134996a104ee5811,
+    ]),
+    FqRepr([
+        0xb8dfe240c72de1f6,
+        0xd26d521628b00523,
+        0xc344be4b91400da7,
+        0x2552e2d658a31ce2,
+        0xf4a384c86a3b4994,
+        0xcc786baa966e66,
+    ]),
+    FqRepr([
+        0xa6355c77b0e5f4cb,
+        0xde405aba9ec61dec,
+        0x9e4a3ec03251cf9,
+        0xd42aa7b90eeb791c,
+        0x7898751ad8746757,
+        0x1f86376e8981c21,
+    ]),
+    FqRepr([
+        0x41b6daecf2e8fedb,
+        0x2ee7f8dc099040a8,
+        0x79833fd221351adc,
+        0x195536fbe3ce50b8,
+        0x5caf4fe2a21529c4,
+        0x8cc03fdefe0ff13,
+    ]),
+    FqRepr([
+        0x99b23ab13633a5f0,
+        0x203f6326c95a8072,
+        0x76505c3d3ad5544e,
+        0x74a7d0d4afadb7bd,
+        0x2211e11db8f0a6a0,
+        0x16603fca40634b6a,
+    ]),
+    FqRepr([
+        0xc961f8855fe9d6f2,
+        0x47a87ac2460f415e,
+        0x5231413c4d634f37,
+        0xe75bb8ca2be184cb,
+        0xb2c977d027796b3c,
+        0x4ab0b9bcfac1bbc,
+    ]),
+    FqRepr([
+        0xa15e4ca31870fb29,
+        0x42f64550fedfe935,
+        0xfd038da6c26c8426,
+        0x170a05bfe3bdd81f,
+        0xde9926bd2ca6c674,
+        0x987c8d5333ab86f,
+    ]),
+    FqRepr([
+        0x60370e577bdba587,
+        0x69d65201c78607a3,
+        0x1e8b6e6a1f20cabe,
+        0x8f3abd16679dc26c,
+        0xe88c9e221e4da1bb,
+        0x9fc4018bd96684b,
+    ]),
+    FqRepr([
+        0x2bafaaebca731c30,
+        0x9b3f7055dd4eba6f,
+        0x6985e7ed1e4d43b,
+        0xc42a0ca7915af6fe,
+        0x223abde7ada14a23,
+        0xe1bba7a1186bdb5,
+    ]),
+    FqRepr([
+        0xe813711ad011c132,
+        0x31bf3a5cce3fbafc,
+        0xd1183e416389e610,
+        0xcd2fcbcb6caf493f,
+        0xdfd0b8f1d43fb93,
+        0x19713e47937cd1be,
+    ]),
+    FqRepr([
+        0xce07c8a4d0074d8e,
+        0x49d9cdf41b44d606,
+        0x2e6bfe7f911f6432,
+        0x523559b8aaf0c246,
+        0xb918c143fed2edcc,
+        0x18b46a908f36f6de,
+    ]),
+    FqRepr([
+        0xd4c04f00b971ef8,
+        0x6c851c1919211f2,
+        0xc02710e807b4633f,
+        0x7aa7b12a3426b08e,
+        0xd155096004f53f44,
+        0xb182cac101b9399,
+    ]),
+    FqRepr([
+        0x42d9d3f5db980133,
+        0xc6cf90ad1c232a64,
+        0x13e6632d3c40659c,
+        0x757b3b080d4c1580,
+        0x72fc00ae7be315dc,
+        0x245a394ad1eca9b,
+    ]),
+    FqRepr([
+        0x866b1e715475224b,
+        0x6ba1049b6579afb7,
+        0xd9ab0f5d396a7ce4,
+        0x5e673d81d7e86568,
+        0x2a159f748c4a3fc,
+        0x5c129645e44cf11,
+    ]),
+    FqRepr([
+        0x4b456be69c8b604,
+        0xb665027efec01c77,
+        0x57add4fa95af01b2,
+        0xcb181d8f84965a39,
+        0x4ea50b3b42df2eb5,
+        0x15e6be4e990f03ce,
+    ]),
+];
+
+const G1_ISO_Y_DEN: [FqRepr; 16] = [
+    FqRepr([
+        0x1479253b03663c1,
+        0x7f3688ef60c206d,
+        0xeec3232b5be72e7a,
+        0x601a6de578980be6,
+        0x52181140fad0eae9,
+        0x16112c4c3a9c98b2,
+    ]),
+    FqRepr([
+        0x32f6102c2e49a03d,
+        0x78a4260763529e35,
+        0xa4a10356f453e01f,
+        0x85c84ff731c4d59c,
+        0x1a0cbd6c43c348b8,
+        0x1962d75c2381201e,
+    ]),
+    FqRepr([
+        0x1e2538b53dbf67f2,
+        0xa6757cd636f96f89,
+        0xc35a5dd279cd2ec,
+        0x78c4855551ae7f31,
+        0x6faaae7d6e8eb157,
+        0x58df3306640da27,
+    ]),
+    FqRepr([
+        0xa8d26d98445f5416,
+        0x727364f2c28297ad,
+        0x123da489e726af41,
+        0xd115c5dbddbcd30e,
+        0xf20d23bf89edb4d1,
+        0x16b7d288798e5395,
+    ]),
+    FqRepr([
+        0xda39142311a5001d,
+        0xa20b15dc0fd2eded,
+        0x542eda0fc9dec916,
+        0xc6d19c9f0f69bbb0,
+        0xb00cc912f8228ddc,
+        0xbe0e079545f43e4,
+    ]),
+    FqRepr([
+        0x2c6477faaf9b7ac,
+        0x49f38db9dfa9cce2,
+        0xc5ecd87b6f0f5a64,
+        0xb70152c65550d881,
+        0x9fb266eaac783182,
+        0x8d9e5297186db2d,
+    ]),
+    FqRepr([
+        0x3d1a1399126a775c,
+        0xd5fa9c01a58b1fb9,
+        0x5dd365bc400a0051,
+        0x5eecfdfa8d0cf8ef,
+        0xc3ba8734ace9824b,
+        0x166007c08a99db2f,
+    ]),
+    FqRepr([
+        0x60ee415a15812ed9,
+        0xb920f5b00801dee4,
+        0xfeb34fd206357132,
+        0xe5a4375efa1f4fd7,
+        0x3bcddfabba6ff6e,
+        0x16a3ef08be3ea7ea,
+    ]),
+    FqRepr([
+        0x6b233d9d55535d4a,
+        0x52cfe2f7bb924883,
+        0xabc5750c4bf39b48,
+        0xf9fb0ce4c6af5920,
+        0x1a1be54fd1d74cc4,
+        0x1866c8ed336c6123,
+    ]),
+    FqRepr([
+        0x346ef48bb8913f55,
+        0xc7385ea3d529b35e,
+        0x5308592e7ea7d4fb,
+        0x3216f763e13d87bb,
+        0xea820597d94a8490,
+        0x167a55cda70a6e1c,
+    ]),
+    FqRepr([
+        0xf8b49cba8f6aa8,
+        0x71a5c29f4f830604,
+        0xe591b36e636a5c8,
+        0x9c6dd039bb61a629,
+        0x48f010a01ad2911d,
+        0x4d2f259eea405bd,
+    ]),
+    FqRepr([
+        0x9684b529e2561092,
+        0x16f968986f7ebbea,
+        0x8c0f9a88cea79135,
+        0x7f94ff8aefce42d2,
+        0xf5852c1e48c50c47,
+        0xaccbb67481d033f,
+    ]),
+    FqRepr([
+        0x1e99b138573345cc,
+        0x93000763e3b90ac1,
+        0x7d5ceef9a00d9b86,
+        0x543346d98adf0226,
+        0xc3613144b45f1496,
+        0xad6b9514c767fe3,
+    ]),
+    FqRepr([
+        0xd1fadc1326ed06f7,
+        0x420517bd8714cc80,
+        0xcb748df27942480e,
+        0xbf565b94e72927c1,
+        0x628bdd0d53cd76f2,
+        0x2660400eb2e4f3b,
+    ]),
+    FqRepr([
+        0x4415473a1d634b8f,
+        0x5ca2f570f1349780,
+        0x324efcd6356caa20,
+        0x71c40f65e273b853,
+        0x6b24255e0d7819c1,
+        0xe0fa1d816ddc03e,
+    ]),
+    FqRepr([
+        0x1,
+        0x0,
+        0x0,
+        0x0,
+        0x0,
+        0x0,
+    ]),
+];
+
+/// The cofactor multiple h_eff used to clear the cofactor of G2.
+const G2_H_EFF: [u64; 10] = [
+    0xe8020005aaa95551,
+    0x59894c0adebbf6b4,
+    0xe954cbc06689f6a3,
+    0x2ec0ec69d7477c1a,
+    0x6d82bf015d1212b0,
+    0x329c2f178731db95,
+    0x9986ff031508ffe1,
+    0x88e2a8e9145ad768,
+    0x584c6a0ea91b3528,
+    0xbc69f08f2ee75b3,
+];
+
+/// A' = 240 * u of E': y^2 = x^3 + A' * x + B', 3-isogenous to E.
+const G2_ISO_A: (FqRepr, FqRepr) = (
+    FqRepr([
+        0x0,
+        0x0,
+        0x0,
+        0x0,
+        0x0,
+        0x0,
+    ]),
+    FqRepr([
+        0xf0,
+        0x0,
+        0x0,
+        0x0,
+        0x0,
+        0x0,
+    ]),
+);
+
+/// B' = 1012 * (1 + u) of E'.
+const G2_ISO_B: (FqRepr, FqRepr) = (
+    FqRepr([
+        0x3f4,
+        0x0,
+        0x0,
+        0x0,
+        0x0,
+        0x0,
+    ]),
+    FqRepr([
+        0x3f4,
+        0x0,
+        0x0,
+        0x0,
+        0x0,
+        0x0,
+    ]),
+);
+
+/// The SSWU constant Z = -(2 + u).
+const G2_SSWU_Z: (FqRepr, FqRepr) = (
+    FqRepr([
+        0xb9feffffffffaaa9,
+        0x1eabfffeb153ffff,
+        0x6730d2a0f6b0f624,
+        0x64774b84f38512bf,
+        0x4b1ba7b6434bacd7,
+        0x1a0111ea397fe69a,
+    ]),
+    FqRepr([
+        0xb9feffffffffaaaa,
+        0x1eabfffeb153ffff,
+        0x6730d2a0f6b0f624,
+        0x64774b84f38512bf,
+        0x4b1ba7b6434bacd7,
+        0x1a0111ea397fe69a,
+    ]),
+);
+
+/// Coefficients of the 3-isogeny map from E' to E (appendix E.3), from the
+/// constant term up. Both denominators are monic.
+const G2_ISO_X_NUM: [(FqRepr, FqRepr); 4] = [
+    (
+        FqRepr([
+            0x6238aaaaaaaa97d6,
+            0x5c2638e343d9c71c,
+            0x88b58423c50ae15d,
+            0x32c52d39fd3a042a,
+            0xbb5b7a9a47d7ed85,
+            0x5c759507e8e333e,
+        ]),
+        FqRepr([
+            0x6238aaaaaaaa97d6,
+            0x5c2638e343d9c71c,
+            0x88b58423c50ae15d,
+            0x32c52d39fd3a042a,
+            0xbb5b7a9a47d7ed85,
+            0x5c759507e8e333e,
+        ]),
+    ),
+    (
+        FqRepr([
+            0x0,
+            0x0,
+            0x0,
+            0x0,
+            0x0,
+            0x0,
+        ]),
+        FqRepr([
+            0x26a9ffffffffc71a,
+            0x1472aaa9cb8d5555,
+            0x9a208c6b4f20a418,
+            0x984f87adf7ae0c7f,
+            0x32126fced787c88f,
+            0x11560bf17baa99bc,
+        ]),
+    ),
+    (
+        FqRepr([
+            0x26a9ffffffffc71e,
+            0x1472aaa9cb8d5555,
+            0x9a208c6b4f20a418,
+            0x984f87adf7ae0c7f,
+            0x32126fced787c88f,
+            0x11560bf17baa99bc,
+        ]),
+        FqRepr([
+            0x9354ffffffffe38d,
+            0xa395554e5c6aaaa,
+            0xcd104635a790520c,
+            0xcc27c3d6fbd7063f,
+            0x190937e76bc3e447,
+            0x8ab05f8bdd54cde,
+        ]),
+    ),
+    (
+        FqRepr([
+            0x88e2aaaaaaaa5ed1,
+            0x7098e38d0f671c71,
+            0x22d6108f142b8575,
+            0xcb14b4e7f4e810aa,
+            0xed6dea691f5fb614,
+            0x171d6541fa38ccfa,
+        ]),
+        FqRepr([
+            0x0,
+            0x0,
+            0x0,
+            0x0,
+            0x0,
+            0x0,
+        ]),
+    ),
+];
+
+const G2_ISO_X_DEN: [(FqRepr, FqRepr); 3] = [
+    (
+        FqRepr([
+            0x0,
+            0x0,
+            0x0,
+            0x0,
+            0x0,
+            0x0,
+        ]),
+        FqRepr([
+            0xb9feffffffffaa63,
+            0x1eabfffeb153ffff,
+            0x6730d2a0f6b0f624,
+            0x64774b84f38512bf,
+            0x4b1ba7b6434bacd7,
+            0x1a0111ea397fe69a,
+        ]),
+    ),
+    (
+        FqRepr([
+            0xc,
+            0x0,
+            0x0,
+            0x0,
+            0x0,
+            0x0,
+        ]),
+        FqRepr([
+            0xb9feffffffffaa9f,
+            0x1eabfffeb153ffff,
+            0x6730d2a0f6b0f624,
+            0x64774b84f38512bf,
+            0x4b1ba7b6434bacd7,
+            0x1a0111ea397fe69a,
+        ]),
+    ),
+    (
+        FqRepr([
+            0x1,
+            0x0,
+            0x0,
+            0x0,
+            0x0,
+            0x0,
+        ]),
+        FqRepr([
+            0x0,
+            0x0,
+            0x0,
+            0x0,
+            0x0,
+            0x0,
+        ]),
+    ),
+];
+
+const G2_ISO_Y_NUM: [(FqRepr, FqRepr); 4] = [
+    (
+        FqRepr([
+            0x12cfc71c71c6d706,
+            0xfc8c25ebf8c92f68,
+            0xf54439d87d27e500,
+            0xf7da5d4a07f649b,
+            0x59a4c18b076d1193,
+            0x1530477c7ab4113b,
+        ]),
+        FqRepr([
+            0x12cfc71c71c6d706,
+            0xfc8c25ebf8c92f68,
+            0xf54439d87d27e500,
+            0xf7da5d4a07f649b,
+            0x59a4c18b076d1193,
+            0x1530477c7ab4113b,
+        ]),
+    ),
+    (
+        FqRepr([
+            0x0,
+            0x0,
+            0x0,
+            0x0,
+            0x0,
+            0x0,
+        ]),
+        FqRepr([
+            0x6238aaaaaaaa97be,
+            0x5c2638e343d9c71c,
+            0x88b58423c50ae15d,
+            0x32c52d39fd3a042a,
+            0xbb5b7a9a47d7ed85,
+            0x5c759507e8e333e,
+        ]),
+    ),
+    (
+        FqRepr([
+            0x26a9ffffffffc71c,
+            0x1472aaa9cb8d5555,
+            0x9a208c6b4f20a418,
+            0x984f87adf7ae0c7f,
+            0x32126fced787c88f,
+            0x11560bf17baa99bc,
+        ]),
+        FqRepr([
+            0x9354ffffffffe38f,
+            0xa395554e5c6aaaa,
+            0xcd104635a790520c,
+            0xcc27c3d6fbd7063f,
+            0x190937e76bc3e447,
+            0x8ab05f8bdd54cde,
+        ]),
+    ),
+    (
+        FqRepr([
+            0xe1b371c71c718b10,
+            0x4e79097a56dc4bd9,
+            0xb0e977c69aa27452,
+            0x761b0f37a1e26286,
+            0xfbf7043de3811ad0,
+            0x124c9ad43b6cf79b,
+        ]),
+        FqRepr([
+            0x0,
+            0x0,
+            0x0,
+            0x0,
+            0x0,
+            0x0,
+        ]),
+    ),
+];
+
+const G2_ISO_Y_DEN: [(FqRepr, FqRepr); 4] = [
+    (
+        FqRepr([
+            0xb9feffffffffa8fb,
+            0x1eabfffeb153ffff,
+            0x6730d2a0f6b0f624,
+            0x64774b84f38512bf,
+            0x4b1ba7b6434bacd7,
+            0x1a0111ea397fe69a,
+        ]),
+        FqRepr([
+            0xb9feffffffffa8fb,
+            0x1eabfffeb153ffff,
+            0x6730d2a0f6b0f624,
+            0x64774b84f38512bf,
+            0x4b1ba7b6434bacd7,
+            0x1a0111ea397fe69a,
+        ]),
+    ),
+    (
+        FqRepr([
+            0x0,
+            0x0,
+            0x0,
+            0x0,
+            0x0,
+            0x0,
+        ]),
+        FqRepr([
+            0xb9feffffffffa9d3,
+            0x1eabfffeb153ffff,
+            0x6730d2a0f6b0f624,
+            0x64774b84f38512bf,
+            0x4b1ba7b6434bacd7,
+            0x1a0111ea397fe69a,
+        ]),
+    ),
+    (
+        FqRepr([
+            0x12,
+            0x0,
+            0x0,
+            0x0,
+            0x0,
+            0x0,
+        ]),
+        FqRepr([
+            0xb9feffffffffaa99,
+            0x1eabfffeb153ffff,
+            0x6730d2a0f6b0f624,
+            0x64774b84f38512bf,
+            0x4b1ba7b6434bacd7,
+            0x1a0111ea397fe69a,
+        ]),
+    ),
+    (
+        FqRepr([
+            0x1,
+            0x0,
+            0x0,
+            0x0,
+            0x0,
+            0x0,
+        ]),
+        FqRepr([
+            0x0,
+            0x0,
+            0x0,
+            0x0,
+            0x0,
+            0x0,
+        ]),
+    ),
+];
@@ -4,6 +4,7 @@ mod fq12;
 mod fq2;
 mod fq6;
 mod fr;
+mod hash_to_curve;
 
 #[cfg(test)]
 mod tests;
@@ -18,7 +19,10 @@ pub use self::fq2::Fq2;
 pub use self::fq6::Fq6;
 pub use self::fr::{Fr, FrRepr};
 
-use super::{BitIterator, CurveAffine, Engine, Field};
+use super::{BitIterator, CanonicalEncoding, CurveAffine, Engine, Field};
+use IoError;
+use utils::{Read, Write};
+use rstd::prelude::*;
 
 // The BLS parameter x for BLS12-381 is -0xd201000000010000
 const BLS_X: u64 = 0xd201000000010000;
@@ -358,6 +362,84 @@ impl G2Prepared {
     }
 }
 
+impl G2Prepared {
+    /// The number of line coefficients `from_affine` computes for a point
+    /// other than the point at infinity.
+    fn num_coeffs() -> usize {
+        let mut num_coeffs = 0;
+
+        let mut found_one = false;
+        for i in BitIterator::new([BLS_X >> 1]) {
+            if !found_one {
+                found_one = i;
+                continue;
+            }
+
+            num_coeffs += if i { 2 } else { 1 };
+        }
+
+        num_coeffs + 1
+    }
+}
+
+/// Encoded as a byte that is 1 for the point at infinity and 0 otherwise,
+/// followed, for other points, by each line coefficient `(c0, c1, c2)` in
+/// the order the Miller loop consumes them, as canonically encoded elements
+/// of Fq2.
+impl CanonicalEncoding for G2Prepared {
+    fn write_canonical<W: Write>(&self, mut writer: W) -> Result<(), IoError> {
+        writer.write_all(&[self.infinity as u8])?;
+
+        for &(ref c0, ref c1, ref c2) in &self.coeffs {
+            c0.write_canonical(&mut writer)?;
+            c1.write_canonical(&mut writer)?;
+            c2.write_canonical(&mut writer)?;
+        }
+
+        Ok(())
+    }
+
+    fn read_canonical<R: Read>(mut reader: R) -> Result<Self, IoError> {
+        let mut infinity = [0u8];
+        reader.read_exact(&mut infinity)?;
+
+        match infinity[0] {
+            0 => {},
+            1 => return Ok(G2Prepared {
+                coeffs: vec![],
+                infinity: true,
+            }),
+            _ => return Err(IoError::Error)
+        }
+
+        let num_coeffs = G2Prepared::num_coeffs();
+        let mut coeffs = Vec::with_capacity(num_coeffs);
+        for _ in 0..num_coeffs {
+            coeffs.push((
+                Fq2::read_canonical(&mut reader)?,
+                Fq2::read_canonical(&mut reader)?,
+                Fq2::read_canonical(&mut reader)?,
+            ));
+        }
+
+        Ok(G2Prepared {
+            coeffs,
+            infinity: false,
+        })
+    }
+
+    fn is_well_formed(&self) -> bool {
+        if self.infinity {
+            self.coeffs.is_empty()
+        } else {
+            self.coeffs.len() == G2Prepared::num_coeffs() &&
+            self.coeffs.iter().all(|&(ref c0, ref c1, ref c2)| {
+                c0.is_well_formed() && c1.is_well_formed() && c2.is_well_formed()
+            })
+        }
+    }
+}
+
 #[test]
 fn bls12_engine_tests() {
     ::tests::engine::engine_tests::<Bls12>();
@@ -96,6 +96,58 @@ fn test_g2_compressed_valid_vectors() {
     test_vectors::<G2, G2Compressed>(include_bytes!("g2_compressed_valid_test_vectors.dat"));
 }
 
+fn test_hash_to_curve_vectors<G: CurveProjective, E: EncodedPoint<Affine = G::Affine>>(
+    hash_to_curve: fn(&[u8], &[u8]) -> G,
+    dst: &[u8],
+    expected: &[u8],
+) {
+    let mut expected = expected;
+    for msg in ::hash_to_curve::test_messages() {
+        let p = hash_to_curve(&msg, dst).into_affine();
+        assert_eq!(E::from_affine(p).as_ref(), &expected[0..E::size()]);
+        expected = &expected[E::size()..];
+    }
+
+    assert!(expected.is_empty());
+}
+
+#[test]
+fn test_g1_hash_to_curve_vectors() {
+    // The BLS12381G1_XMD:SHA-256_SSWU_RO_ vectors of RFC 9380, appendix J.9.1.
+    test_hash_to_curve_vectors::<G1, G1Uncompressed>(
+        G1::hash_to_curve,
+        b"QUUX-V01-CS02-with-BLS12381G1_XMD:SHA-256_SSWU_RO_",
+        include_bytes!("g1_hash_to_curve_test_vectors.dat"),
+    );
+}
+
+#[test]
+fn test_g2_hash_to_curve_vectors() {
+    // The BLS12381G2_XMD:SHA-256_SSWU_RO_ vectors of RFC 9380, appendix J.10.1.
+    test_hash_to_curve_vectors::<G2, G2Uncompressed>(
+        G2::hash_to_curve,
+        b"QUUX-V01-CS02-with-BLS12381G2_XMD:SHA-256_SSWU_RO_",
+        include_bytes!("g2_hash_to_curve_test_vectors.dat"),
+    );
+}
+
+#[test]
+fn test_hash_to_curve_subgroup() {
+    // Different messages and tags hash to different points of the
+    // prime-order subgroups.
+    let a = G1::hash_to_curve(b"message", b"tag").into_affine();
+    let b = G1::hash_to_curve(b"message", b"other tag").into_affine();
+    assert!(a != b);
+    assert!(a.mul_bits(BitIterator::new(Fr::char())).is_zero());
+    assert!(G1Uncompressed::from_affine(a).into_affine().is_ok());
+
+    let a = G2::hash_to_curve(b"message", b"tag").into_affine();
+    let b = G2::hash_to_curve(b"other message", b"tag").into_affine();
+    assert!(a != b);
+    assert!(a.mul_bits(BitIterator::new(Fr::char())).is_zero());
+    assert!(G2Uncompressed::from_affine(a).into_affine().is_ok());
+}
+
 #[test]
 fn test_g1_uncompressed_invalid_vectors() {
     {
@@ -0,0 +1,489 @@
+mod ec;
+mod fq;
+mod fq12;
+mod fq2;
+mod fq6;
+mod fr;
+
+#[cfg(test)]
+mod tests;
+
+pub use self::ec::{
+    G1, G1Affine, G1Compressed, G1Prepared, G1Uncompressed, G2, G2Affine, G2Compressed, G2Prepared,
+    G2Uncompressed,
+};
+pub use self::fq::{Fq, FqRepr};
+pub use self::fq12::Fq12;
+pub use self::fq2::Fq2;
+pub use self::fq6::Fq6;
+pub use self::fr::{Fr, FrRepr};
+
+use self::fq::{FROBENIUS_COEFF_FQ6_C1, XI_TO_Q_MINUS_1_OVER_2};
+use super::{CanonicalEncoding, CurveAffine, Engine, Field};
+use IoError;
+use utils::{Read, Write};
+use rstd::prelude::*;
+
+// The BN parameter u for BN254 is 4965661367192848881
+const BN_U: u64 = 4965661367192848881;
+
+// The non-adjacent form of 6u + 2, least significant digit first, which
+// drives the Miller loop of the optimal ate pairing.
+const SIX_U_PLUS_2_NAF: [i8; 66] = [
+    0, 0, 0, 1, 0, 1, 0, -1, 0, 0, -1, 0, 0, 0, 1, 0,
+    0, -1, 0, -1, 0, 0, 0, 1, 0, -1, 0, 0, 0, 0, -1, 0,
+    0, 1, 0, -1, 0, 0, 1, 0, 0, 0, 0, 0, -1, 0, 0, -1,
+    0, 1, 0, -1, 0, 0, 0, -1, 0, -1, 0, 0, 0, 1, 0, -1,
+    0, 1,
+];
+
+#[derive(Clone, Encode, Decode, Default, PartialEq, Eq, Debug)]
+pub struct Bn256;
+
+impl Engine for Bn256 {
+    type Fr = Fr;
+    type G1 = G1;
+    type G1Affine = G1Affine;
+    type G2 = G2;
+    type G2Affine = G2Affine;
+    type Fq = Fq;
+    type Fqe = Fq2;
+    type Fqk = Fq12;
+
+    fn miller_loop<'a, I>(i: I) -> Self::Fqk
+    where
+        I: IntoIterator<
+            Item = &'a (
+                &'a <Self::G1Affine as CurveAffine>::Prepared,
+                &'a <Self::G2Affine as CurveAffine>::Prepared,
+            ),
+        >,
+    {
+        let mut pairs = vec![];
+        for &(p, q) in i {
+            if !p.is_zero() && !q.is_zero() {
+                pairs.push((p, q.coeffs.iter()));
+            }
+        }
+
+        // Twisting isomorphism from E to E'
+        fn ell(f: &mut Fq12, coeffs: &(Fq2, Fq2, Fq2), p: &G1Affine) {
+            let mut c0 = coeffs.0;
+            let mut c1 = coeffs.1;
+
+            c0.c0.mul_assign(&p.y);
+            c0.c1.mul_assign(&p.y);
+
+            c1.c0.mul_assign(&p.x);
+            c1.c1.mul_assign(&p.x);
+
+            // Sparse multiplication in Fq12
+            f.mul_by_034(&c0, &c1, &coeffs.2);
+        }
+
+        let mut f = Fq12::one();
+
+        // The most significant digit is skipped, as Q itself is the
+        // starting point.
+        for &digit in SIX_U_PLUS_2_NAF[..SIX_U_PLUS_2_NAF.len() - 1].iter().rev() {
+            f.square();
+
+            for &mut (p, ref mut coeffs) in &mut pairs {
+                ell(&mut f, coeffs.next().unwrap(), &p.0);
+            }
+
+            if digit != 0 {
+                for &mut (p, ref mut coeffs) in &mut pairs {
+                    ell(&mut f, coeffs.next().unwrap(), &p.0);
+                }
+            }
+        }
+
+        // The lines through the images of Q under the Frobenius
+        // endomorphism.
+        for &mut (p, ref mut coeffs) in &mut pairs {
+            ell(&mut f, coeffs.next().unwrap(), &p.0);
+        }
+
+        for &mut (p, ref mut coeffs) in &mut pairs {
+            ell(&mut f, coeffs.next().unwrap(), &p.0);
+        }
+
+        f
+    }
+
+    fn final_exponentiation(r: &Fq12) -> Option<Fq12> {
+        let mut f1 = *r;
+        f1.conjugate();
+
+        match r.inverse() {
+            Some(mut f2) => {
+                // r^((q^6 - 1) * (q^2 + 1))
+                let mut r = f1;
+                r.mul_assign(&f2);
+                f2 = r;
+                r.frobenius_map(2);
+                r.mul_assign(&f2);
+
+                // r^((q^4 - q^2 + 1) / r), following Devegili, Scott and
+                // Dahab, "Implementing Cryptographic Pairings over
+                // Barreto-Naehrig Curves", https://eprint.iacr.org/2007/390.pdf
+                fn exp_by_u(f: &Fq12) -> Fq12 {
+                    f.pow(&[BN_U])
+                }
+
+                let mut fp = r;
+                fp.frobenius_map(1);
+                let mut fp2 = r;
+                fp2.frobenius_map(2);
+                let mut fp3 = fp2;
+                fp3.frobenius_map(1);
+
+                let fu = exp_by_u(&r);
+                let fu2 = exp_by_u(&fu);
+                let fu3 = exp_by_u(&fu2);
+
+                let mut y3 = fu;
+                y3.frobenius_map(1);
+                let mut fu2p = fu2;
+                fu2p.frobenius_map(1);
+                let mut fu3p = fu3;
+                fu3p.frobenius_map(1);
+                let mut y2 = fu2;
+                y2.frobenius_map(2);
+
+                let mut y0 = fp;
+                y0.mul_assign(&fp2);
+                y0.mul_assign(&fp3);
+
+                let mut y1 = r;
+                y1.conjugate();
+
+                let mut y5 = fu2;
+                y5.conjugate();
+
+                y3.conjugate();
+
+                let mut y4 = fu;
+                y4.mul_assign(&fu2p);
+                y4.conjugate();
+
+                let mut y6 = fu3;
+                y6.mul_assign(&fu3p);
+                y6.conjugate();
+
+                let mut t0 = y6;
+                t0.square();
+                t0.mul_assign(&y4);
+                t0.mul_assign(&y5);
+
+                let mut t1 = y3;
+                t1.mul_assign(&y5);
+                t1.mul_assign(&t0);
+
+                t0.mul_assign(&y2);
+
+                t1.square();
+                t1.mul_assign(&t0);
+                t1.square();
+
+                t0 = t1;
+                t0.mul_assign(&y1);
+
+                t1.mul_assign(&y0);
+
+                t0.square();
+                t0.mul_assign(&t1);
+
+                Some(t0)
+            }
+            None => None,
+        }
+    }
+}
+
+impl G2Prepared {
+    pub fn is_zero(&self) -> bool {
+        self.infinity
+    }
+
+    pub fn from_affine(q: G2Affine) -> Self {
+        if q.is_zero() {
+            return G2Prepared {
+                coeffs: vec![],
+                infinity: true,
+            };
+        }
+
+        fn doubling_step(r: &mut G2) -> (Fq2, Fq2, Fq2) {
+            // Adaptation of Algorithm 26, https://eprint.iacr.org/2010/354.pdf
+            let mut tmp0 = r.x;
+            tmp0.square();
+
+            let mut tmp1 = r.y;
+            tmp1.square();
+
+            let mut tmp2 = tmp1;
+            tmp2.square();
+
+            let mut tmp3 = tmp1;
+            tmp3.add_assign(&r.x);
+            tmp3.square();
+            tmp3.sub_assign(&tmp0);
+            tmp3.sub_assign(&tmp2);
+            tmp3.double();
+
+            let mut tmp4 = tmp0;
+            tmp4.double();
+            tmp4.add_assign(&tmp0);
+
+            let mut tmp6 = r.x;
+            tmp6.add_assign(&tmp4);
+
+            let mut tmp5 = tmp4;
+            tmp5.square();
+
+            let mut zsquared = r.z;
+            zsquared.square();
+
+            r.x = tmp5;
+            r.x.sub_assign(&tmp3);
+            r.x.sub_assign(&tmp3);
+
+            r.z.add_assign(&r.y);
+            r.z.square();
+            r.z.sub_assign(&tmp1);
+            r.z.sub_assign(&zsquared);
+
+            r.y = tmp3;
+            r.y.sub_assign(&r.x);
+            r.y.mul_assign(&tmp4);
+
+            tmp2.double();
+            tmp2.double();
+            tmp2.double();
+
+            r.y.sub_assign(&tmp2);
+
+            tmp3 = tmp4;
+            tmp3.mul_assign(&zsquared);
+            tmp3.double();
+            tmp3.negate();
+
+            tmp6.square();
+            tmp6.sub_assign(&tmp0);
+            tmp6.sub_assign(&tmp5);
+
+            tmp1.double();
+            tmp1.double();
+
+            tmp6.sub_assign(&tmp1);
+
+            tmp0 = r.z;
+            tmp0.mul_assign(&zsquared);
+            tmp0.double();
+
+            (tmp0, tmp3, tmp6)
+        }
+
+        fn addition_step(r: &mut G2, q: &G2Affine) -> (Fq2, Fq2, Fq2) {
+            // Adaptation of Algorithm 27, https://eprint.iacr.org/2010/354.pdf
+            let mut zsquared = r.z;
+            zsquared.square();
+
+            let mut ysquared = q.y;
+            ysquared.square();
+
+            let mut t0 = zsquared;
+            t0.mul_assign(&q.x);
+
+            let mut t1 = q.y;
+            t1.add_assign(&r.z);
+            t1.square();
+            t1.sub_assign(&ysquared);
+            t1.sub_assign(&zsquared);
+            t1.mul_assign(&zsquared);
+
+            let mut t2 = t0;
+            t2.sub_assign(&r.x);
+
+            let mut t3 = t2;
+            t3.square();
+
+            let mut t4 = t3;
+            t4.double();
+            t4.double();
+
+            let mut t5 = t4;
+            t5.mul_assign(&t2);
+
+            let mut t6 = t1;
+            t6.sub_assign(&r.y);
+            t6.sub_assign(&r.y);
+
+            let mut t9 = t6;
+            t9.mul_assign(&q.x);
+
+            let mut t7 = t4;
+            t7.mul_assign(&r.x);
+
+            r.x = t6;
+            r.x.square();
+            r.x.sub_assign(&t5);
+            r.x.sub_assign(&t7);
+            r.x.sub_assign(&t7);
+
+            r.z.add_assign(&t2);
+            r.z.square();
+            r.z.sub_assign(&zsquared);
+            r.z.sub_assign(&t3);
+
+            let mut t10 = q.y;
+            t10.add_assign(&r.z);
+
+            let mut t8 = t7;
+            t8.sub_assign(&r.x);
+            t8.mul_assign(&t6);
+
+            t0 = r.y;
+            t0.mul_assign(&t5);
+            t0.double();
+
+            r.y = t8;
+            r.y.sub_assign(&t0);
+
+            t10.square();
+            t10.sub_assign(&ysquared);
+
+            let mut ztsquared = r.z;
+            ztsquared.square();
+
+            t10.sub_assign(&ztsquared);
+
+            t9.double();
+            t9.sub_assign(&t10);
+
+            t10 = r.z;
+            t10.double();
+
+            t6.negate();
+
+            t1 = t6;
+            t1.double();
+
+            (t10, t1, t9)
+        }
+
+        let mut coeffs = vec![];
+        let mut r: G2 = q.into();
+
+        let mut negq = q;
+        negq.negate();
+
+        for &digit in SIX_U_PLUS_2_NAF[..SIX_U_PLUS_2_NAF.len() - 1].iter().rev() {
+            coeffs.push(doubling_step(&mut r));
+
+            match digit {
+                1 => coeffs.push(addition_step(&mut r, &q)),
+                -1 => coeffs.push(addition_step(&mut r, &negq)),
+                _ => {}
+            }
+        }
+
+        // q1 is the image of q under the Frobenius endomorphism, brought
+        // back to the twist, and q2 the negation of the image of q1.
+        let mut q1 = q;
+        q1.x.frobenius_map(1);
+        q1.x.mul_assign(&FROBENIUS_COEFF_FQ6_C1[1]);
+        q1.y.frobenius_map(1);
+        q1.y.mul_assign(&XI_TO_Q_MINUS_1_OVER_2);
+
+        let mut q2 = q;
+        q2.x.mul_assign(&FROBENIUS_COEFF_FQ6_C1[2]);
+
+        coeffs.push(addition_step(&mut r, &q1));
+        coeffs.push(addition_step(&mut r, &q2));
+
+        G2Prepared {
+            coeffs,
+            infinity: false,
+        }
+    }
+}
+
+impl G2Prepared {
+    /// The number of line coefficients `from_affine` computes for a point
+    /// other than the point at infinity.
+    fn num_coeffs() -> usize {
+        let mut num_coeffs = 0;
+
+        for &digit in &SIX_U_PLUS_2_NAF[..SIX_U_PLUS_2_NAF.len() - 1] {
+            num_coeffs += if digit != 0 { 2 } else { 1 };
+        }
+
+        num_coeffs + 2
+    }
+}
+
+/// Encoded as a byte that is 1 for the point at infinity and 0 otherwise,
+/// followed, for other points, by each line coefficient `(c0, c1, c2)` in
+/// the order the Miller loop consumes them, as canonically encoded elements
+/// of Fq2.
+impl CanonicalEncoding for G2Prepared {
+    fn write_canonical<W: Write>(&self, mut writer: W) -> Result<(), IoError> {
+        writer.write_all(&[self.infinity as u8])?;
+
+        for &(ref c0, ref c1, ref c2) in &self.coeffs {
+            c0.write_canonical(&mut writer)?;
+            c1.write_canonical(&mut writer)?;
+            c2.write_canonical(&mut writer)?;
+        }
+
+        Ok(())
+    }
+
+    fn read_canonical<R: Read>(mut reader: R) -> Result<Self, IoError> {
+        let mut infinity = [0u8];
+        reader.read_exact(&mut infinity)?;
+
+        match infinity[0] {
+            0 => {},
+            1 => return Ok(G2Prepared {
+                coeffs: vec![],
+                infinity: true,
+            }),
+            _ => return Err(IoError::Error)
+        }
+
+        let num_coeffs = G2Prepared::num_coeffs();
+        let mut coeffs = Vec::with_capacity(num_coeffs);
+        for _ in 0..num_coeffs {
+            coeffs.push((
+                Fq2::read_canonical(&mut reader)?,
+                Fq2::read_canonical(&mut reader)?,
+                Fq2::read_canonical(&mut reader)?,
+            ));
+        }
+
+        Ok(G2Prepared {
+            coeffs,
+            infinity: false,
+        })
+    }
+
+    fn is_well_formed(&self) -> bool {
+        if self.infinity {
+            self.coeffs.is_empty()
+        } else {
+            self.coeffs.len() == G2Prepared::num_coeffs() &&
+            self.coeffs.iter().all(|&(ref c0, ref c1, ref c2)| {
+                c0.is_well_formed() && c1.is_well_formed() && c2.is_well_formed()
+            })
+        }
+    }
+}
+
+#[test]
+fn bn256_engine_tests() {
+    ::tests::engine::engine_tests::<Bn256>();
+}
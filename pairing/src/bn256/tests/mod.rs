@@ -0,0 +1,120 @@
+use super::*;
+use *;
+
+#[test]
+fn test_pairing_result() {
+    // Computed independently, as the Miller loop raised to the power
+    // (q^12 - 1) / r.
+    assert_eq!(Bn256::pairing(G1::one(), G2::one()), Fq12 {
+        c0: Fq6 {
+            c0: Fq2 {
+                c0: Fq::from_str("8493334370784016972005089913588211327688223499729897951716206968320726508021").unwrap(),
+                c1: Fq::from_str("3758435817766288188804561253838670030762970764366672594784247447067868088068").unwrap()
+            },
+            c1: Fq2 {
+                c0: Fq::from_str("6565798094314091391201231504228224566495939541538094766881371862976727043038").unwrap(),
+                c1: Fq::from_str("14656606573936501743457633041048024656612227301473084805627390748872617280984").unwrap()
+            },
+            c2: Fq2 {
+                c0: Fq::from_str("634997487638609332803583491743335852620873788902390365055086820718589720118").unwrap(),
+                c1: Fq::from_str("19455424343576886430889849773367397946457449073528455097210946839000147698372").unwrap()
+            }
+        },
+        c1: Fq6 {
+            c0: Fq2 {
+                c0: Fq::from_str("20049218015652006197026173611347504489508678646783216776320737476707192559881").unwrap(),
+                c1: Fq::from_str("18059168546148152671857026372711724379319778306792011146784665080987064164612").unwrap()
+            },
+            c1: Fq2 {
+                c0: Fq::from_str("12145052038566888241256672223106590273978429515702193755778990643425246950730").unwrap(),
+                c1: Fq::from_str("17918828665069491344039743589118342552553375221610735811112289083834142789347").unwrap()
+            },
+            c2: Fq2 {
+                c0: Fq::from_str("6223602427219597392892794664899549544171383137467762280768257680446283161705").unwrap(),
+                c1: Fq::from_str("7484542354754424633621663080190936924481536615300815203692506276894207018007").unwrap()
+            }
+        }
+    });
+}
+
+#[test]
+fn test_eip197_pairing_check() {
+    // The `jeff1` input of go-ethereum's alt_bn128 pairing precompile
+    // tests, for which the precompile returns 1: each pair is a G1 point
+    // followed by a G2 point, in the layout of the uncompressed encodings.
+    let input = [
+        "1c76476f4def4bb94541d57ebba1193381ffa7aa76ada664dd31c16024c43f59",
+        "3034dd2920f673e204fee2811c678745fc819b55d3e9d294e45c9b03a76aef41",
+        "209dd15ebff5d46c4bd888e51a93cf99a7329636c63514396b4a452003a35bf7",
+        "04bf11ca01483bfa8b34b43561848d28905960114c8ac04049af4b6315a41678",
+        "2bb8324af6cfc93537a2ad1a445cfd0ca2a71acd7ac41fadbf933c2a51be344d",
+        "120a2a4cf30c1bf9845f20c6fe39e07ea2cce61f0c9bb048165fe5e4de877550",
+        "111e129f1cf1097710d41c4ac70fcdfa5ba2023c6ff1cbeac322de49d1b6df7c",
+        "2032c61a830e3c17286de9462bf242fca2883585b93870a73853face6a6bf411",
+        "198e9393920d483a7260bfb731fb5d25f1aa493335a9e71297e485b7aef312c2",
+        "1800deef121f1e76426a00665e5c4479674322d4f75edadd46debd5cd992f6ed",
+        "090689d0585ff075ec9e99ad690c3395bc4b313370b38ef355acdadcd122975b",
+        "12c85ea5db8c6deb4aab71808dcb408fe3d1e7690c43d37b4ce6cc0166fa7daa"
+    ];
+    let input = input.iter().flat_map(|word| {
+        (0..32).map(move |i| u8::from_str_radix(&word[2 * i..2 * i + 2], 16).unwrap())
+    }).collect::<Vec<_>>();
+
+    let pairs = input.chunks(192).map(|pair| {
+        let mut g1 = G1Uncompressed::empty();
+        g1.as_mut().copy_from_slice(&pair[..64]);
+        let mut g2 = G2Uncompressed::empty();
+        g2.as_mut().copy_from_slice(&pair[64..]);
+
+        (g1.into_affine().unwrap().prepare(), g2.into_affine().unwrap().prepare())
+    }).collect::<Vec<_>>();
+
+    let check = |pairs: &[(G1Prepared, G2Prepared)]| {
+        let pairs = pairs.iter().map(|(a, b)| (a, b)).collect::<Vec<_>>();
+        Bn256::final_exponentiation(&Bn256::miller_loop(&pairs)).unwrap() == Fq12::one()
+    };
+    assert!(check(&pairs));
+    assert!(!check(&pairs[..1]));
+}
+
+fn test_encodings<G: CurveProjective, E: EncodedPoint<Affine = G::Affine>>() {
+    let mut e = G::zero();
+
+    for _ in 0..1000 {
+        let e_affine = e.into_affine();
+        let encoded = E::from_affine(e_affine);
+        assert_eq!(encoded.as_ref().len(), E::size());
+
+        let mut decoded = E::empty();
+        decoded.as_mut().copy_from_slice(encoded.as_ref());
+        assert_eq!(decoded.into_affine().unwrap(), e_affine);
+
+        e.add_assign(&G::one());
+    }
+}
+
+#[test]
+fn test_g1_encodings() {
+    test_encodings::<G1, G1Uncompressed>();
+    test_encodings::<G1, G1Compressed>();
+}
+
+#[test]
+fn test_g2_encodings() {
+    test_encodings::<G2, G2Uncompressed>();
+    test_encodings::<G2, G2Compressed>();
+}
+
+#[test]
+fn test_g2_prepared_canonical_encoding() {
+    for p in &[G2Affine::zero(), G2Affine::one()] {
+        let prepared = p.prepare();
+        assert!(prepared.is_well_formed());
+
+        let mut buf = vec![];
+        prepared.write_canonical(&mut buf).unwrap();
+
+        let decoded = G2Prepared::read_canonical(&buf[..]).unwrap();
+        assert_eq!(decoded, prepared);
+    }
+}
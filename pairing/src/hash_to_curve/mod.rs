@@ -0,0 +1,127 @@
+//! Building blocks shared by the hash-to-curve suites of
+//! [RFC 9380](https://www.rfc-editor.org/rfc/rfc9380.html).
+//!
+//! The curve-specific maps live alongside each curve, e.g.
+//! `bls12_381::G1::hash_to_curve`.
+
+use sha256::{sha256, Sha256, BLOCK_SIZE, OUTPUT_SIZE};
+use rstd::prelude::*;
+
+/// The prefix used to hash domain separation tags longer than 255 bytes
+/// (RFC 9380, section 5.3.3).
+const OVERSIZE_DST_PREFIX: &[u8] = b"H2C-OVERSIZE-DST-";
+
+/// Expands `msg` into `len_in_bytes` pseudorandom bytes under the domain
+/// separation tag `dst`, using `expand_message_xmd` with SHA-256
+/// (RFC 9380, section 5.3.1).
+///
+/// # Panics
+///
+/// Panics if `len_in_bytes` is zero or larger than 8160 bytes.
+pub fn expand_message_xmd(msg: &[u8], dst: &[u8], len_in_bytes: usize) -> Vec<u8> {
+    let ell = (len_in_bytes + OUTPUT_SIZE - 1) / OUTPUT_SIZE;
+    assert!(
+        ell > 0 && ell <= 255,
+        "expand_message_xmd cannot produce {} bytes",
+        len_in_bytes
+    );
+
+    let oversize_dst;
+    let dst = if dst.len() > 255 {
+        oversize_dst = sha256(&[OVERSIZE_DST_PREFIX, dst]);
+        &oversize_dst[..]
+    } else {
+        dst
+    };
+    let dst_len = [dst.len() as u8];
+
+    let mut h = Sha256::new();
+    h.update(&[0; BLOCK_SIZE]);
+    h.update(msg);
+    h.update(&[(len_in_bytes >> 8) as u8, len_in_bytes as u8, 0]);
+    h.update(dst);
+    h.update(&dst_len);
+    let b_0 = h.finalize();
+
+    let mut h = Sha256::new();
+    h.update(&b_0);
+    h.update(&[1]);
+    h.update(dst);
+    h.update(&dst_len);
+    let mut b_i = h.finalize();
+
+    let mut uniform_bytes = Vec::with_capacity(ell * OUTPUT_SIZE);
+    uniform_bytes.extend_from_slice(&b_i);
+
+    for i in 2..(ell + 1) {
+        let mut xored = b_0;
+        for (x, b) in xored.iter_mut().zip(b_i.iter()) {
+            *x ^= *b;
+        }
+
+        let mut h = Sha256::new();
+        h.update(&xored);
+        h.update(&[i as u8]);
+        h.update(dst);
+        h.update(&dst_len);
+        b_i = h.finalize();
+
+        uniform_bytes.extend_from_slice(&b_i);
+    }
+
+    uniform_bytes.truncate(len_in_bytes);
+    uniform_bytes
+}
+
+/// The messages used by the test vectors of RFC 9380.
+#[cfg(test)]
+pub fn test_messages() -> Vec<Vec<u8>> {
+    let mut q128 = b"q128_".to_vec();
+    q128.extend_from_slice(&[b'q'; 128]);
+    let mut a512 = b"a512_".to_vec();
+    a512.extend_from_slice(&[b'a'; 512]);
+
+    vec![
+        b"".to_vec(),
+        b"abc".to_vec(),
+        b"abcdef0123456789".to_vec(),
+        q128,
+        a512,
+    ]
+}
+
+#[test]
+fn test_expand_message_xmd_vectors() {
+    // Each output of appendix K.1, for every message at 0x20 bytes
+    // and then every message at 0x80 bytes.
+    let dst = b"QUUX-V01-CS02-with-expander-SHA256-128";
+    let mut expected = &include_bytes!("expand_message_xmd_sha256_test_vectors.dat")[..];
+
+    for &len in &[0x20, 0x80] {
+        for msg in test_messages() {
+            assert_eq!(expand_message_xmd(&msg, dst, len)[..], expected[..len]);
+            expected = &expected[len..];
+        }
+    }
+
+    assert!(expected.is_empty());
+}
+
+#[test]
+fn test_expand_message_xmd_long_dst() {
+    // A tag longer than 255 bytes is replaced by its hash, so both
+    // must expand identically.
+    let dst = [0x42; 256];
+    let hashed = sha256(&[OVERSIZE_DST_PREFIX, &dst]);
+
+    assert_eq!(
+        expand_message_xmd(b"abc", &dst, 0x80),
+        expand_message_xmd(b"abc", &hashed, 0x80)
+    );
+}
+
+#[test]
+#[should_panic]
+fn test_expand_message_xmd_too_long() {
+    expand_message_xmd(b"abc", b"dst", 255 * 32 + 1);
+}
@@ -22,9 +22,13 @@ extern crate alloc;
 extern crate core;
 #[cfg(test)]
 pub mod tests;
+pub mod bls;
 pub mod bls12_381;
+pub mod bn256;
+pub mod hash_to_curve;
 pub mod utils;
 
+mod sha256;
 mod wnaf;
 pub use self::wnaf::Wnaf;
 
@@ -98,7 +102,7 @@ pub trait Engine: Sized + 'static + Clone + PartialEq {
     type Fqe: SqrtField;
 
     /// The extension field that hosts the target group of the pairing.
-    type Fqk: Field + Encode + Decode + Default + PartialEq;
+    type Fqk: Field + Encode + Decode + Default + PartialEq + CanonicalEncoding;
 
     /// Perform a miller loop with some number of (G1, G2) pairs.
     fn miller_loop<'a, I>(i: I) -> Self::Fqk
@@ -181,7 +185,10 @@ pub trait CurveProjective:
     /// Negates this element.
     fn negate(&mut self);
 
-    /// Performs scalar multiplication of this element.
+    /// Performs scalar multiplication of this element, which must be in the
+    /// prime order subgroup like every `CurveAffine` element. Implementations
+    /// may use endomorphisms that only act as scalars on that subgroup, so
+    /// the result for any other point of the curve is unspecified.
     fn mul_assign<S: Into<<Self::Scalar as PrimeField>::Repr>>(&mut self, other: S);
 
     /// Converts this element into its affine representation.
@@ -206,9 +213,9 @@ pub trait CurveAffine:
     type Base: SqrtField;
     type Projective: CurveProjective<Affine = Self, Scalar = Self::Scalar>;
     #[cfg(feature = "std")]
-    type Prepared: Clone + Send + Sync + 'static + Encode + Decode + Default + PartialEq + Eq + Debug;
+    type Prepared: Clone + Send + Sync + 'static + Encode + Decode + Default + PartialEq + Eq + Debug + CanonicalEncoding;
     #[cfg(not(feature = "std"))]
-    type Prepared: Clone + Send + Sync + 'static + Encode + Decode + Default + PartialEq + Eq;
+    type Prepared: Clone + Send + Sync + 'static + Encode + Decode + Default + PartialEq + Eq + CanonicalEncoding;
     type Uncompressed: EncodedPoint<Affine = Self> + Debug;
     type Compressed: EncodedPoint<Affine = Self>;
     type Pair: CurveAffine<Pair = Self>;
@@ -227,7 +234,9 @@ pub trait CurveAffine:
     /// Negates this element.
     fn negate(&mut self);
 
-    /// Performs scalar multiplication of this element with mixed addition.
+    /// Performs scalar multiplication of this element. As with
+    /// `CurveProjective::mul_assign`, the result is unspecified if the
+    /// element is not in the prime order subgroup.
     fn mul<S: Into<<Self::Scalar as PrimeField>::Repr>>(&self, other: S) -> Self::Projective;
 
     /// Prepares this element for pairing purposes.
@@ -239,6 +248,20 @@ pub trait CurveAffine:
     /// Converts this element into its affine representation.
     fn into_projective(&self) -> Self::Projective;
 
+    /// Adds `other[i]` to `acc[i]` for every `i`, in affine coordinates.
+    /// Implementations share a single field inversion between all of the
+    /// additions (Montgomery's trick); the default one adds each pair in
+    /// projective coordinates instead.
+    fn batch_add_assign(acc: &mut [Self], other: &[Self]) {
+        assert_eq!(acc.len(), other.len());
+
+        for (a, b) in acc.iter_mut().zip(other.iter()) {
+            let mut sum = a.into_projective();
+            sum.add_assign_mixed(b);
+            *a = sum.into_affine();
+        }
+    }
+
     /// Converts this element into its compressed encoding, so long as it's not
     /// the point at infinity.
     fn into_compressed(&self) -> Self::Compressed {
@@ -252,6 +275,24 @@ pub trait CurveAffine:
     }
 }
 
+/// A stable byte encoding for values a verifier precomputes once and
+/// stores, such as prepared points and pairing results. Unlike the layout
+/// parity-codec derives from the in-memory representation, this encoding is
+/// part of the API: field elements are written as canonical big-endian
+/// integers, never in Montgomery form.
+pub trait CanonicalEncoding: Sized {
+    /// Writes the canonical encoding of this value.
+    fn write_canonical<W: Write>(&self, writer: W) -> Result<(), IoError>;
+
+    /// Reads a value written by `write_canonical`, rejecting it unless
+    /// `is_well_formed` would hold for it.
+    fn read_canonical<R: Read>(reader: R) -> Result<Self, IoError>;
+
+    /// Checks the invariants of the in-memory representation, for values
+    /// that were obtained some other way, e.g. through `Decode`.
+    fn is_well_formed(&self) -> bool;
+}
+
 /// An encoded elliptic curve point, which should essentially wrap a `[u8; N]`.
 pub trait EncodedPoint:
     Sized + Send + Sync + AsRef<[u8]> + AsMut<[u8]> + Clone + Copy + 'static
@@ -274,7 +315,9 @@ pub trait EncodedPoint:
     /// valid already.
     ///
     /// If the encoding is invalid, this can break API invariants,
-    /// so caution is strongly encouraged.
+    /// so caution is strongly encouraged. In particular, scalar
+    /// multiplication of a point outside of the prime order subgroup
+    /// gives unspecified results.
     fn into_affine_unchecked(&self) -> Result<Self::Affine, GroupDecodingError>;
 
     /// Creates an `EncodedPoint` from an affine point, as long as the
@@ -356,6 +399,7 @@ pub trait SqrtField: Field {
 // #[cfg_attr(feature = "std", derive(Debug))]
 pub enum IoError {
     Error,
+    UnexpectedEof,
     WriteZero,
     Infinity,
     Group(GroupDecodingError),
@@ -368,6 +412,7 @@ impl fmt::Debug for IoError {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         match *self {            
             IoError::Error => write!(f, "encountered an I/O error"),
+            IoError::UnexpectedEof => write!(f, "failed to fill whole buffer"),
             IoError::WriteZero => write!(f, "failed to write whole buffer"),
             IoError::Infinity => write!(f, "point at infinity"),
             IoError::Group(ref err) => write!(f, "GroupDecodingError"),
@@ -382,6 +427,7 @@ impl IoError {
     fn description_str(&self) -> &'static str {
         match *self {            
             IoError::Error => "encountered an I/O error",   
+            IoError::UnexpectedEof => "failed to fill whole buffer",
             IoError::WriteZero => "failed to write whole buffer",
             IoError::Infinity => "point at infinity", 
             IoError::Group(ref err) => err.description_str(),       
@@ -512,9 +558,15 @@ pub trait PrimeFieldRepr:
     }
 
     /// Reads a little endian integer into this representation.
-    fn read_le(&mut self, mut reader: &[u8]) -> Result<(), IoError> {        
-        for digit in self.as_mut().iter_mut() {
-            *digit = reader.read_u64().unwrap();
+    fn read_le(&mut self, reader: &[u8]) -> Result<(), IoError> {
+        use byteorder::{LittleEndian, ByteOrder};
+
+        if reader.len() < 8 * self.as_ref().len() {
+            return Err(IoError::UnexpectedEof);
+        }
+
+        for (i, digit) in self.as_mut().iter_mut().enumerate() {
+            *digit = LittleEndian::read_u64(&reader[8*i..]);
         }
 
         Ok(())
@@ -57,6 +57,7 @@ pub fn curve_tests<G: CurveProjective>() {
     }
 
     random_addition_tests::<G>();
+    random_batch_addition_tests::<G>();
     random_multiplication_tests::<G>();
     random_doubling_tests::<G>();
     random_negation_tests::<G>();
@@ -344,6 +345,42 @@ fn random_addition_tests<G: CurveProjective>() {
     }
 }
 
+fn random_batch_addition_tests<G: CurveProjective>() {
+    let mut rng = XorShiftRng::from_seed([0x5dbe6259, 0x8d313d76, 0x3237db17, 0xe5bc0654]);
+
+    for _ in 0..10 {
+        let mut acc = vec![];
+        let mut other = vec![];
+
+        for _ in 0..20 {
+            let a = G::rand(&mut rng).into_affine();
+            let b = G::rand(&mut rng).into_affine();
+            let mut neg_a = a;
+            neg_a.negate();
+
+            // Distinct points, doubling, opposite points and zero.
+            acc.extend_from_slice(&[a, a, a, G::Affine::zero(), a, G::Affine::zero()]);
+            other.extend_from_slice(&[b, a, neg_a, a, G::Affine::zero(), G::Affine::zero()]);
+        }
+
+        let mut expected = acc.clone();
+        for (e, b) in expected.iter_mut().zip(other.iter()) {
+            let mut sum = e.into_projective();
+            sum.add_assign_mixed(b);
+            *e = sum.into_affine();
+        }
+
+        G::Affine::batch_add_assign(&mut acc, &other);
+        assert_eq!(acc, expected);
+    }
+
+    // Nothing to invert.
+    let mut acc = vec![G::Affine::zero(); 2];
+    G::Affine::batch_add_assign(&mut acc, &[G::Affine::zero(); 2]);
+    assert!(acc.iter().all(|a| a.is_zero()));
+    G::Affine::batch_add_assign(&mut [], &[]);
+}
+
 fn random_transformation_tests<G: CurveProjective>() {
     use rstd::prelude::*;
     let mut rng = XorShiftRng::from_seed([0x5dbe6259, 0x8d313d76, 0x3237db17, 0xe5bc0654]);
@@ -18,7 +18,7 @@ fn random_encoding_tests<R: PrimeFieldRepr>() {
         {
             let mut rdecoded = R::default();
 
-            let mut v: Vec<u8> = vec![];
+            let mut v: Vec<u8> = vec![0; 8 * r.as_ref().len()];
             r.write_be(&mut v).unwrap();
             rdecoded.read_be(&v[0..]).unwrap();
 
@@ -29,7 +29,7 @@ fn random_encoding_tests<R: PrimeFieldRepr>() {
         {
             let mut rdecoded = R::default();
 
-            let mut v: Vec<u8> = vec![];
+            let mut v: Vec<u8> = vec![0; 8 * r.as_ref().len()];
             r.write_le(&mut v).unwrap();
             rdecoded.read_le(&mut v[0..]).unwrap();
 
@@ -40,7 +40,7 @@ fn random_encoding_tests<R: PrimeFieldRepr>() {
             let mut rdecoded_le = R::default();
             let mut rdecoded_be_flip = R::default();
 
-            let mut v: Vec<u8> = vec![];
+            let mut v: Vec<u8> = vec![0; 8 * r.as_ref().len()];
             r.write_le(&mut v).unwrap();
 
             // This reads in little-endian, so we are done.
@@ -43,8 +43,38 @@ pub trait Write {
         }
         Ok(())
     }
-    fn write_u32(&mut self, n: u32) -> Result<(), IoError>;
-    fn write_u64(&mut self, n: u64) -> Result<(), IoError>;
+    fn write_u32(&mut self, n: u32) -> Result<(), IoError> {
+        let mut buf = [0; 4];
+        write_u32_be(&mut buf, n);
+        self.write_all(&buf)
+    }
+    fn write_u64(&mut self, n: u64) -> Result<(), IoError> {
+        let mut buf = [0; 8];
+        write_u64_be(&mut buf, n);
+        self.write_all(&buf)
+    }
+}
+
+impl<'a, W: Write + ?Sized> Write for &'a mut W {
+    #[inline]
+    fn write(&mut self, buf: &[u8]) -> Result<usize, IoError> {
+        (**self).write(buf)
+    }
+
+    #[inline]
+    fn write_all(&mut self, buf: &[u8]) -> Result<(), IoError> {
+        (**self).write_all(buf)
+    }
+
+    #[inline]
+    fn write_u32(&mut self, n: u32) -> Result<(), IoError> {
+        (**self).write_u32(n)
+    }
+
+    #[inline]
+    fn write_u64(&mut self, n: u64) -> Result<(), IoError> {
+        (**self).write_u64(n)
+    }
 }
 
 impl Write for Vec<u8> {
@@ -107,13 +137,43 @@ pub trait Read {
             }
         }
         if !buf.is_empty() {
-            Err(IoError::Error)
+            Err(IoError::UnexpectedEof)
         } else {
             Ok(())
         }
     }
-    fn read_u32(&mut self) -> Result<u32, IoError>;
-    fn read_u64(&mut self) -> Result<u64, IoError>;
+    fn read_u32(&mut self) -> Result<u32, IoError> {
+        let mut buf = [0; 4];
+        self.read_exact(&mut buf)?;
+        Ok(read_u32_be(&buf))
+    }
+    fn read_u64(&mut self) -> Result<u64, IoError> {
+        let mut buf = [0; 8];
+        self.read_exact(&mut buf)?;
+        Ok(read_u64_be(&buf))
+    }
+}
+
+impl<'a, R: Read + ?Sized> Read for &'a mut R {
+    #[inline]
+    fn read(&mut self, buf: &mut [u8]) -> Result<usize, IoError> {
+        (**self).read(buf)
+    }
+
+    #[inline]
+    fn read_exact(&mut self, buf: &mut [u8]) -> Result<(), IoError> {
+        (**self).read_exact(buf)
+    }
+
+    #[inline]
+    fn read_u32(&mut self) -> Result<u32, IoError> {
+        (**self).read_u32()
+    }
+
+    #[inline]
+    fn read_u64(&mut self) -> Result<u64, IoError> {
+        (**self).read_u64()
+    }
 }
 
 impl<'a> Read for &'a [u8] {
@@ -138,9 +198,7 @@ impl<'a> Read for &'a [u8] {
     #[inline]
     fn read_exact(&mut self, buf: &mut [u8]) -> Result<(), IoError> {
         if buf.len() > self.len() {
-            // return Err(Error::new(ErrorKind::UnexpectedEof,
-                                //   "failed to fill whole buffer"));
-            return Err(IoError::Error);
+            return Err(IoError::UnexpectedEof);
         }
         let (a, b) = self.split_at(buf.len());
 
@@ -171,3 +229,74 @@ impl<'a> Read for &'a [u8] {
         Ok(read_u32_be(&buf))
     }
 }
+
+/// Keeps the kinds of `std::io::Error` that callers can act on, such as a
+/// truncated input, and folds the rest into `IoError::Error`.
+#[cfg(feature = "std")]
+fn from_io_error(e: &::std::io::Error) -> IoError {
+    match e.kind() {
+        ::std::io::ErrorKind::UnexpectedEof => IoError::UnexpectedEof,
+        ::std::io::ErrorKind::WriteZero => IoError::WriteZero,
+        _ => IoError::Error,
+    }
+}
+
+/// Adapts a `std::io::Read` into a `Read`, so that parameters and keys
+/// can be streamed from files and sockets.
+#[cfg(feature = "std")]
+#[derive(Debug)]
+pub struct IoReader<R>(pub R);
+
+#[cfg(feature = "std")]
+impl<R: ::std::io::Read> Read for IoReader<R> {
+    fn read(&mut self, buf: &mut [u8]) -> Result<usize, IoError> {
+        loop {
+            match self.0.read(buf) {
+                Err(ref e) if e.kind() == ::std::io::ErrorKind::Interrupted => {}
+                res => return res.map_err(|e| from_io_error(&e)),
+            }
+        }
+    }
+}
+
+/// Adapts a `std::io::Write` into a `Write`.
+#[cfg(feature = "std")]
+#[derive(Debug)]
+pub struct IoWriter<W>(pub W);
+
+#[cfg(feature = "std")]
+impl<W: ::std::io::Write> Write for IoWriter<W> {
+    fn write(&mut self, buf: &[u8]) -> Result<usize, IoError> {
+        loop {
+            match self.0.write(buf) {
+                Err(ref e) if e.kind() == ::std::io::ErrorKind::Interrupted => {}
+                res => return res.map_err(|e| from_io_error(&e)),
+            }
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn test_io_error_kinds() {
+    let mut buf = [0; 4];
+
+    match (&[1u8, 2][..]).read_exact(&mut buf) {
+        Err(IoError::UnexpectedEof) => {}
+        _ => panic!("expected an unexpected EOF"),
+    }
+    match IoReader(&[1u8, 2][..]).read_exact(&mut buf) {
+        Err(IoError::UnexpectedEof) => {}
+        _ => panic!("expected an unexpected EOF"),
+    }
+
+    let err = ::std::io::Error::new(::std::io::ErrorKind::UnexpectedEof, "eof");
+    match from_io_error(&err) {
+        IoError::UnexpectedEof => {}
+        _ => panic!("expected an unexpected EOF"),
+    }
+    match IoWriter(&mut [0u8; 2][..]).write_all(&buf) {
+        Err(IoError::WriteZero) => {}
+        _ => panic!("expected a short write"),
+    }
+}
@@ -71,6 +71,59 @@ pub(crate) fn wnaf_exp<G: CurveProjective>(table: &[G], wnaf: &[i64]) -> G {
     result
 }
 
+/// Computes the sum of `bases[i] * scalars[i]` by interleaving the w-NAF forms of the
+/// scalars, so that all of the bases share one chain of doublings.
+///
+/// This pays off for a handful of short scalars, such as the output of an endomorphism
+/// decomposition; larger batches belong in a multiexp.
+pub(crate) fn wnaf_exp_simultaneous<G: CurveProjective>(
+    bases: &[G],
+    scalars: &[<G::Scalar as PrimeField>::Repr],
+) -> G {
+    assert_eq!(bases.len(), scalars.len());
+
+    let mut tables = vec![];
+    let mut wnafs = vec![];
+    for (base, scalar) in bases.iter().zip(scalars.iter()) {
+        let window = G::recommended_wnaf_for_scalar(*scalar);
+
+        let mut table = vec![];
+        wnaf_table(&mut table, *base, window);
+        tables.push(table);
+
+        let mut wnaf = vec![];
+        wnaf_form(&mut wnaf, *scalar, window);
+        wnafs.push(wnaf);
+    }
+
+    let mut result = G::zero();
+
+    let mut found_one = false;
+
+    let len = wnafs.iter().map(|wnaf| wnaf.len()).max().unwrap_or(0);
+    for i in (0..len).rev() {
+        if found_one {
+            result.double();
+        }
+
+        for (table, wnaf) in tables.iter().zip(wnafs.iter()) {
+            let n = wnaf.get(i).cloned().unwrap_or(0);
+
+            if n != 0 {
+                found_one = true;
+
+                if n > 0 {
+                    result.add_assign(&table[(n / 2) as usize]);
+                } else {
+                    result.sub_assign(&table[((-n) / 2) as usize]);
+                }
+            }
+        }
+    }
+
+    result
+}
+
 /// A "w-ary non-adjacent form" exponentiation context.
 #[derive(Debug)]
 pub struct Wnaf<W, B, S> {
@@ -99,12 +99,16 @@ impl<E: JubjubEngine, Subgroup> PartialEq for Point<E, Subgroup> {
 
 impl<E: JubjubEngine> Point<E, Unknown> {
     pub fn read<R: Read>(
-        reader: R,
+        mut reader: R,
         params: &E::Params
     ) -> io::Result<Self>
     {
+        let mut y_bytes = [0u8; 32];
+        reader.read_exact(&mut y_bytes)?;
+
         let mut y_repr = <E::Fr as PrimeField>::Repr::default();
-        y_repr.read_le(reader)?;
+        y_repr.read_le(&y_bytes[..])
+              .map_err(|_| io::Error::new(io::ErrorKind::InvalidInput, "y is not in field"))?;
 
         let x_sign = (y_repr.as_ref()[3] >> 63) == 1;
         y_repr.as_mut()[3] &= 0x7fffffffffffffff;
@@ -197,7 +201,7 @@ impl<E: JubjubEngine> Point<E, Unknown> {
 impl<E: JubjubEngine, Subgroup> Point<E, Subgroup> {
     pub fn write<W: Write>(
         &self,
-        writer: W
+        mut writer: W
     ) -> io::Result<()>
     {
         let (x, y) = self.into_xy();
@@ -210,7 +214,11 @@ impl<E: JubjubEngine, Subgroup> Point<E, Subgroup> {
             y_repr.as_mut()[3] |= 0x8000000000000000u64;
         }
 
-        y_repr.write_le(writer)
+        let mut y_bytes = [0u8; 32];
+        y_repr.write_le(&mut y_bytes[..])
+              .expect("32 bytes hold the representation");
+
+        writer.write_all(&y_bytes)
     }
 
     /// Convert from a Montgomery point
@@ -11,9 +11,13 @@ use std::io::{self, Read, Write};
 use jubjub::{FixedGenerators, JubjubEngine, JubjubParams, Unknown, edwards::Point};
 use util::{hash_to_scalar};
 
-fn read_scalar<E: JubjubEngine, R: Read>(reader: R) -> io::Result<E::Fs> {
+fn read_scalar<E: JubjubEngine, R: Read>(mut reader: R) -> io::Result<E::Fs> {
+    let mut s_bytes = [0u8; 32];
+    reader.read_exact(&mut s_bytes)?;
+
     let mut s_repr = <E::Fs as PrimeField>::Repr::default();
-    s_repr.read_le(reader)?;
+    s_repr.read_le(&s_bytes[..])
+          .map_err(|_| io::Error::new(io::ErrorKind::InvalidInput, "scalar is not in field"))?;
 
     match E::Fs::from_repr(s_repr) {
         Ok(s) => Ok(s),
@@ -24,8 +28,12 @@ fn read_scalar<E: JubjubEngine, R: Read>(reader: R) -> io::Result<E::Fs> {
     }
 }
 
-fn write_scalar<E: JubjubEngine, W: Write>(s: &E::Fs, writer: W) -> io::Result<()> {
-    s.into_repr().write_le(writer)
+fn write_scalar<E: JubjubEngine, W: Write>(s: &E::Fs, mut writer: W) -> io::Result<()> {
+    let mut s_bytes = [0u8; 32];
+    s.into_repr().write_le(&mut s_bytes[..])
+     .expect("32 bytes hold the representation");
+
+    writer.write_all(&s_bytes)
 }
 
 fn h_star<E: JubjubEngine>(a: &[u8], b: &[u8]) -> E::Fs {